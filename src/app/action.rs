@@ -10,6 +10,8 @@
 //! 2. Add handler in App::execute()
 //! 3. Optionally add keybinding in keybindings.rs
 
+use crate::tables::TableEdit;
+
 use super::state::AppResult;
 use super::App;
 
@@ -24,21 +26,80 @@ pub enum Action {
     NextTab,
     PreviousTab,
     GoToTab(usize),
+    CloseTabAt(usize),
+    CloseOtherTabs(usize),
+    CloseTabsToRight(usize),
+    ReopenClosedTab,
+    DuplicateTab(usize),
 
     // =========================================================================
     // File operations
     // =========================================================================
     Save,
+    SaveAs,
     OpenFile,
     RenameTab,
+    RenameTabAt(usize),
+    RevealInFolder(usize),
+    RevertToSaved(usize),
+    MoveFile(usize),
+    MoveToFolder(usize),
+    NewNoteInFolder,
+    ToggleAutoSave(usize),
 
     // =========================================================================
     // Notes picker
     // =========================================================================
     OpenNotesPicker,
+    NewFromTemplate,
     ConfirmNotesPicker,
     CancelNotesPicker,
 
+    // =========================================================================
+    // Daily notes
+    // =========================================================================
+    OpenTodayNote,
+    OpenJournalCalendar,
+    JournalPreviousDay,
+    JournalNextDay,
+
+    // =========================================================================
+    // Tasks
+    // =========================================================================
+    ToggleTask,
+    OpenTaskList,
+
+    // =========================================================================
+    // Tables
+    // =========================================================================
+    TableInsertRow,
+    TableDeleteRow,
+    TableInsertColumn,
+    TableDeleteColumn,
+
+    // =========================================================================
+    // Outline and folding
+    // =========================================================================
+    OpenOutline,
+    PreviousHeading,
+    NextHeading,
+    ToggleFold,
+    FoldAll,
+    UnfoldAll,
+
+    // =========================================================================
+    // Statistics
+    // =========================================================================
+    OpenStatistics,
+
+    // =========================================================================
+    // Spelling
+    // =========================================================================
+    ToggleSpellCheck,
+    SpellingSuggestions,
+    ApplySuggestion(usize),
+    AddToDictionary,
+
     // =========================================================================
     // Edit operations
     // =========================================================================
@@ -52,6 +113,8 @@ pub enum Action {
     DeleteWordRight,
     Delete,
     Backspace,
+    InsertTab,
+    PreviousTabStop,
 
     // =========================================================================
     // Cursor movement
@@ -62,12 +125,15 @@ pub enum Action {
     CursorDown { selecting: bool },
     CursorWordLeft { selecting: bool },
     CursorWordRight { selecting: bool },
+    CursorSubWordLeft { selecting: bool },
+    CursorSubWordRight { selecting: bool },
     CursorLineStart { selecting: bool },
     CursorLineEnd { selecting: bool },
     CursorDocStart { selecting: bool },
     CursorDocEnd { selecting: bool },
     PageUp { selecting: bool },
     PageDown { selecting: bool },
+    GoToLine,
 
     // =========================================================================
     // Line operations
@@ -79,6 +145,11 @@ pub enum Action {
     // View
     // =========================================================================
     ToggleWordWrap,
+    ToggleSidebar,
+    ToggleBacklinks,
+    ZoomIn,
+    ZoomOut,
+    ZoomReset,
 
     // =========================================================================
     // Modal/Focus operations
@@ -102,17 +173,64 @@ impl App {
             Action::NextTab => self.next_tab(),
             Action::PreviousTab => self.previous_tab(),
             Action::GoToTab(index) => self.go_to_tab(index),
+            Action::CloseTabAt(index) => self.close_tab_at(index),
+            Action::CloseOtherTabs(index) => self.close_other_tabs(index),
+            Action::CloseTabsToRight(index) => self.close_tabs_to_right(index),
+            Action::ReopenClosedTab => self.reopen_closed_tab(),
+            Action::DuplicateTab(index) => self.duplicate_tab(index),
 
             // File operations
             Action::Save => self.save_current(),
+            Action::SaveAs => self.save_current_as(),
             Action::OpenFile => self.open_file(),
             Action::RenameTab => self.rename_current(),
+            Action::RenameTabAt(index) => self.rename_tab_at(index),
+            Action::RevealInFolder(index) => self.reveal_in_folder(index),
+            Action::RevertToSaved(index) => self.revert_tab(index),
+            Action::MoveFile(index) => self.move_tab_file(index),
+            Action::MoveToFolder(index) => self.move_tab_to_folder(index),
+            Action::NewNoteInFolder => self.new_note_in_folder(),
+            Action::ToggleAutoSave(index) => self.toggle_auto_save(index),
 
             // Notes picker
             Action::OpenNotesPicker => self.open_notes_picker(),
+            Action::NewFromTemplate => self.open_template_picker(),
             Action::ConfirmNotesPicker => self.confirm_notes_picker(),
             Action::CancelNotesPicker => self.cancel_notes_picker(),
 
+            // Daily notes
+            Action::OpenTodayNote => self.open_today_note(),
+            Action::OpenJournalCalendar => self.open_journal_calendar(),
+            Action::JournalPreviousDay => self.journal_previous_day(),
+            Action::JournalNextDay => self.journal_next_day(),
+
+            // Tasks
+            Action::ToggleTask => self.toggle_task(),
+            Action::OpenTaskList => self.open_task_list(),
+
+            // Tables
+            Action::TableInsertRow => self.edit_table(TableEdit::InsertRow),
+            Action::TableDeleteRow => self.edit_table(TableEdit::DeleteRow),
+            Action::TableInsertColumn => self.edit_table(TableEdit::InsertColumn),
+            Action::TableDeleteColumn => self.edit_table(TableEdit::DeleteColumn),
+
+            // Outline and folding
+            Action::OpenOutline => self.open_outline(),
+            Action::PreviousHeading => self.go_to_heading(false),
+            Action::NextHeading => self.go_to_heading(true),
+            Action::ToggleFold => self.toggle_fold(),
+            Action::FoldAll => self.fold_all(),
+            Action::UnfoldAll => self.unfold_all(),
+
+            // Statistics
+            Action::OpenStatistics => self.open_statistics(),
+
+            // Spelling
+            Action::ToggleSpellCheck => self.toggle_spell_check(),
+            Action::SpellingSuggestions => self.open_spelling_menu_at_cursor(),
+            Action::ApplySuggestion(index) => self.apply_suggestion(index),
+            Action::AddToDictionary => self.add_to_dictionary(),
+
             // Edit operations
             Action::Undo => self.handle_undo(),
             Action::Redo => self.handle_redo(),
@@ -124,6 +242,8 @@ impl App {
             Action::DeleteWordRight => self.handle_delete_word_right(),
             Action::Delete => self.handle_delete(),
             Action::Backspace => self.handle_backspace(),
+            Action::InsertTab => self.handle_tab(),
+            Action::PreviousTabStop => self.previous_tab_stop(),
 
             // Cursor movement
             Action::CursorLeft { selecting } => self.move_cursor_left(selecting),
@@ -132,12 +252,15 @@ impl App {
             Action::CursorDown { selecting } => self.move_cursor_down(selecting),
             Action::CursorWordLeft { selecting } => self.move_cursor_word_left(selecting),
             Action::CursorWordRight { selecting } => self.move_cursor_word_right(selecting),
+            Action::CursorSubWordLeft { selecting } => self.move_cursor_subword_left(selecting),
+            Action::CursorSubWordRight { selecting } => self.move_cursor_subword_right(selecting),
             Action::CursorLineStart { selecting } => self.move_cursor_to_line_start(selecting),
             Action::CursorLineEnd { selecting } => self.move_cursor_to_line_end(selecting),
             Action::CursorDocStart { selecting } => self.move_cursor_to_start(selecting),
             Action::CursorDocEnd { selecting } => self.move_cursor_to_end(selecting),
             Action::PageUp { selecting } => self.page_up(selecting),
             Action::PageDown { selecting } => self.page_down(selecting),
+            Action::GoToLine => self.open_go_to_line(),
 
             // Line operations
             Action::MoveLinesUp => self.handle_move_lines_up(),
@@ -145,26 +268,60 @@ impl App {
 
             // View
             Action::ToggleWordWrap => self.toggle_word_wrap(),
+            Action::ToggleSidebar => self.toggle_sidebar(),
+            Action::ToggleBacklinks => self.toggle_backlinks(),
+            Action::ZoomIn => self.zoom_in(),
+            Action::ZoomOut => self.zoom_out(),
+            Action::ZoomReset => self.zoom_reset(),
 
             // Modal operations
             Action::Cancel => {
-                // Try canceling in order: notes picker, then rename
+                // Try canceling in order: context menu, notes picker,
+                // calendar, statistics, then rename
+                let result = self.cancel_context_menu();
+                if result.needs_redraw() {
+                    return result;
+                }
                 let result = self.cancel_notes_picker();
                 if result.needs_redraw() {
                     return result;
                 }
+                let result = self.cancel_journal_calendar();
+                if result.needs_redraw() {
+                    return result;
+                }
+                let result = self.close_statistics();
+                if result.needs_redraw() {
+                    return result;
+                }
                 self.cancel_rename()
             }
             Action::Confirm => {
-                // Try confirming in order: notes picker, rename, then insert newline
+                // Try confirming in order: context menu, notes picker,
+                // calendar, statistics, rename, link under the cursor, then
+                // insert newline
+                if self.focus.is_context_menu() {
+                    return self.confirm_context_menu();
+                }
                 let result = self.confirm_notes_picker();
                 if result.needs_redraw() {
                     return result;
                 }
+                if self.focus.is_journal_calendar() {
+                    return self.confirm_journal_calendar();
+                }
+                let result = self.close_statistics();
+                if result.needs_redraw() {
+                    return result;
+                }
                 let result = self.confirm_rename();
                 if result.needs_redraw() {
                     return result;
                 }
+                // Enter inside a [[link]] follows it
+                if let Some(target) = self.link_at_cursor() {
+                    return self.open_wiki_link(&target);
+                }
                 self.handle_char('\n')
             }
 
@@ -0,0 +1,129 @@
+//! Right-click context menus
+//!
+//! Menus are built from `Action`s, so choosing an item goes through
+//! `App::execute` exactly like the equivalent keyboard shortcut.
+
+use crate::tab::SaveMode;
+use crate::ui::{ContextMenu, MenuItem, UiNode};
+
+use super::action::Action;
+use super::focus::Focus;
+use super::state::AppResult;
+use super::App;
+
+impl App {
+    /// Open the editor menu (clipboard, selection and line operations, and
+    /// row and column commands inside a table). Over a misspelled word it
+    /// starts with the spelling suggestions.
+    pub fn open_editor_context_menu(&mut self, x: f32, y: f32) -> AppResult {
+        let has_selection = self.tabs[self.active_tab].selection_range().is_some();
+        let (line, col) = self.line_col_at(x, y);
+        let mut items = self.spelling_items(line, col).unwrap_or_default();
+        items.extend([
+            MenuItem::new("Cut", Action::Cut)
+                .with_shortcut("Ctrl+X")
+                .enabled(has_selection),
+            MenuItem::new("Copy", Action::Copy)
+                .with_shortcut("Ctrl+C")
+                .enabled(has_selection),
+            MenuItem::new("Paste", Action::Paste).with_shortcut("Ctrl+V"),
+            MenuItem::new("Select All", Action::SelectAll).with_shortcut("Ctrl+A"),
+            MenuItem::new("Move Lines Up", Action::MoveLinesUp).with_shortcut("Alt+Up"),
+            MenuItem::new("Move Lines Down", Action::MoveLinesDown).with_shortcut("Alt+Down"),
+        ]);
+        if self.tabs[self.active_tab].in_table() {
+            items.extend([
+                MenuItem::new("Insert Row", Action::TableInsertRow).with_shortcut("Ctrl+Alt+Down"),
+                MenuItem::new("Delete Row", Action::TableDeleteRow).with_shortcut("Ctrl+Alt+Up"),
+                MenuItem::new("Insert Column", Action::TableInsertColumn)
+                    .with_shortcut("Ctrl+Alt+Right"),
+                MenuItem::new("Delete Column", Action::TableDeleteColumn)
+                    .with_shortcut("Ctrl+Alt+Left"),
+            ]);
+        }
+        self.open_context_menu(items, x, y)
+    }
+
+    /// Open the menu for the tab at `index`
+    pub fn open_tab_context_menu(&mut self, index: usize, x: f32, y: f32) -> AppResult {
+        let has_others = self.tabs.len() > 1;
+        let has_right = index + 1 < self.tabs.len();
+        let auto_save_label = match self.tabs.get(index).map(|tab| tab.save_mode()) {
+            Some(SaveMode::Manual) => "Turn On Auto-Save",
+            _ => "Turn Off Auto-Save",
+        };
+        let has_file = self.tabs.get(index).and_then(|tab| tab.path()).is_some();
+        let is_modified = self.tabs.get(index).is_some_and(|tab| tab.is_modified());
+        let items = vec![
+            MenuItem::new("Rename", Action::RenameTabAt(index)),
+            MenuItem::new("Move File…", Action::MoveFile(index)).enabled(has_file),
+            MenuItem::new("Move to Folder…", Action::MoveToFolder(index)),
+            MenuItem::new("Revert to Saved", Action::RevertToSaved(index))
+                .enabled(has_file && is_modified),
+            MenuItem::new("Close", Action::CloseTabAt(index)).with_shortcut("Ctrl+W"),
+            MenuItem::new("Close Others", Action::CloseOtherTabs(index)).enabled(has_others),
+            MenuItem::new("Close to the Right", Action::CloseTabsToRight(index)).enabled(has_right),
+            MenuItem::new("Reveal in Folder", Action::RevealInFolder(index)).enabled(has_file),
+            MenuItem::new(auto_save_label, Action::ToggleAutoSave(index)),
+            MenuItem::new("Duplicate", Action::DuplicateTab(index)),
+        ];
+        self.open_context_menu(items, x, y)
+    }
+
+    pub(super) fn open_context_menu(
+        &mut self,
+        items: Vec<MenuItem<Action>>,
+        x: f32,
+        y: f32,
+    ) -> AppResult {
+        let menu = ContextMenu::new(items, x, y, self.width, self.height, self.scale);
+        self.focus = Focus::ContextMenu { menu };
+        AppResult::Redraw
+    }
+
+    /// Run the highlighted item (Enter)
+    pub fn confirm_context_menu(&mut self) -> AppResult {
+        match self.focus.take_context_menu_action(None) {
+            Some(action) => self.run_context_menu_action(action),
+            None => AppResult::Redraw,
+        }
+    }
+
+    /// Close the menu without running anything (Escape)
+    pub fn cancel_context_menu(&mut self) -> AppResult {
+        if self.focus.cancel_context_menu() {
+            return AppResult::Redraw;
+        }
+        AppResult::Ok
+    }
+
+    /// Handle a left click while a menu is open: run the clicked item, or
+    /// dismiss the menu when clicking elsewhere
+    pub fn handle_context_menu_click(&mut self, x: f32, y: f32) -> AppResult {
+        match self.ui_tree().hit_test(x, y) {
+            UiNode::ContextMenuItem(index) => {
+                let enabled = self
+                    .focus
+                    .context_menu()
+                    .and_then(|menu| menu.action_at(index))
+                    .is_some();
+                if !enabled {
+                    return AppResult::Ok;
+                }
+                match self.focus.take_context_menu_action(Some(index)) {
+                    Some(action) => self.run_context_menu_action(action),
+                    None => AppResult::Redraw,
+                }
+            }
+            UiNode::ContextMenu => AppResult::Ok,
+            _ => self.cancel_context_menu(),
+        }
+    }
+
+    fn run_context_menu_action(&mut self, action: Action) -> AppResult {
+        // The menu is already closed; always redraw so it disappears even
+        // when the action itself changes nothing
+        let _ = self.execute(action);
+        AppResult::Redraw
+    }
+}
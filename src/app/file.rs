@@ -1,5 +1,11 @@
 //! File operations
 
+use std::path::Path;
+use std::process::Command;
+
+use native_dialog::{MessageDialog, MessageType};
+
+use crate::persistence;
 use crate::tab::Tab;
 
 use super::state::AppResult;
@@ -7,7 +13,64 @@ use super::App;
 
 impl App {
     pub fn save_current(&mut self) -> AppResult {
-        self.tabs[self.active_tab].save();
+        let tab = &mut self.tabs[self.active_tab];
+        let moved = tab.finish_rename();
+        tab.save();
+        match moved {
+            Some((old, new)) => self.file_moved(&old, &new),
+            None => {
+                self.refresh_panels();
+                AppResult::Redraw
+            }
+        }
+    }
+
+    pub fn save_current_as(&mut self) -> AppResult {
+        if self.tabs[self.active_tab].save_as() {
+            self.refresh_panels();
+            return AppResult::Redraw;
+        }
+        AppResult::Ok
+    }
+
+    /// Reload the tab at `index` from disk (undoable)
+    pub fn revert_tab(&mut self, index: usize) -> AppResult {
+        let Some(tab) = self.tabs.get_mut(index) else {
+            return AppResult::Ok;
+        };
+        if !tab.revert() {
+            return AppResult::Ok;
+        }
+        if index == self.active_tab {
+            self.auto_scroll();
+        }
+        AppResult::Redraw
+    }
+
+    /// Move the file of the tab at `index` to a new location on disk
+    pub fn move_tab_file(&mut self, index: usize) -> AppResult {
+        match self.tabs.get_mut(index).and_then(|tab| tab.move_file()) {
+            Some((old, new)) => self.file_moved(&old, &new),
+            None => AppResult::Ok,
+        }
+    }
+
+    /// Do every pending rename of a note after its heading, e.g. before
+    /// quitting
+    pub fn finish_renames(&mut self) {
+        let moves: Vec<_> = self.tabs.iter_mut().filter_map(Tab::finish_rename).collect();
+        for (old, new) in moves {
+            let _ = self.file_moved(&old, &new);
+        }
+    }
+
+    /// Bookkeeping after a tab's file moved from `old` to `new` on disk
+    pub(super) fn file_moved(&mut self, old: &Path, new: &Path) -> AppResult {
+        self.closed_tabs.rename_path(old, new);
+        // Write the session right away so a crash cannot leave it pointing
+        // at the old path
+        let _ = persistence::save_session_state(&self.export_session_state());
+        self.refresh_panels();
         AppResult::Redraw
     }
 
@@ -23,7 +86,79 @@ impl App {
     }
 
     pub fn rename_current(&mut self) -> AppResult {
-        self.start_rename(self.active_tab);
+        // In the notes picker's tag list, rename the highlighted tag instead
+        if self.focus.start_tag_rename() {
+            return AppResult::Redraw;
+        }
+        self.rename_tab_at(self.active_tab)
+    }
+
+    pub fn rename_tab_at(&mut self, index: usize) -> AppResult {
+        if index >= self.tabs.len() {
+            return AppResult::Ok;
+        }
+        self.start_rename(index);
         AppResult::Redraw
     }
+
+    /// Show the tab's file in the system file manager
+    pub fn reveal_in_folder(&mut self, index: usize) -> AppResult {
+        if let Some(path) = self.tabs.get(index).and_then(|tab| tab.path()) {
+            reveal_path(path);
+        }
+        AppResult::Ok
+    }
+
+    /// Ask before throwing away unsaved changes in the tabs at `indices`.
+    /// Returns true when none of them are modified or the user agreed.
+    pub(super) fn confirm_discard(&self, indices: impl IntoIterator<Item = usize>) -> bool {
+        let titles: Vec<&str> = indices
+            .into_iter()
+            .filter_map(|index| self.tabs.get(index))
+            .filter(|tab| tab.is_modified())
+            .map(|tab| tab.title())
+            .collect();
+        titles.is_empty() || confirm_discard_dialog(&titles)
+    }
+
+    /// Called before the window closes; false means stay open
+    pub fn confirm_quit(&self) -> bool {
+        self.confirm_discard(0..self.tabs.len())
+    }
+}
+
+fn confirm_discard_dialog(titles: &[&str]) -> bool {
+    let text = match titles {
+        [title] => format!("\"{title}\" has unsaved changes.\n\nClose it and discard them?"),
+        _ => format!(
+            "{} files have unsaved changes:\n\n{}\n\nClose them and discard the changes?",
+            titles.len(),
+            titles.join("\n")
+        ),
+    };
+    MessageDialog::new()
+        .set_type(MessageType::Warning)
+        .set_title("Unsaved changes")
+        .set_text(&text)
+        .show_confirm()
+        .unwrap_or(false)
+}
+
+/// Open the platform file manager at `path`, selecting it where supported
+fn reveal_path(path: &Path) {
+    #[cfg(target_os = "macos")]
+    let result = Command::new("open").arg("-R").arg(path).spawn();
+
+    #[cfg(target_os = "windows")]
+    let result = Command::new("explorer")
+        .arg(format!("/select,{}", path.display()))
+        .spawn();
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let result = Command::new("xdg-open")
+        .arg(path.parent().unwrap_or(path))
+        .spawn();
+
+    // The file manager is best-effort; nothing to do if it is unavailable
+    let _ = result;
 }
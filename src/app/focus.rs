@@ -8,8 +8,12 @@
 //! focused widget. Adding a new focusable widget only requires updating this
 //! module, not the central input handler.
 
+use super::action::Action;
 use super::input_handler::{InputHandler, InputResult};
-use crate::ui::{ListWidget, TextInput};
+use crate::date::Date;
+use crate::stats::Stats;
+use crate::tags;
+use crate::ui::{Calendar, ContextMenu, ListWidget, TextInput};
 use std::path::PathBuf;
 
 /// A note entry for the notes picker
@@ -17,7 +21,171 @@ use std::path::PathBuf;
 pub struct NoteEntry {
     pub path: PathBuf,
     pub title: String,
+    /// Folder relative to the data directory, shown next to the title
+    pub folder: Option<String>,
     pub is_open: bool,
+    pub tags: Vec<String>,
+}
+
+/// A tag with the number of notes using it
+#[derive(Debug, Clone)]
+pub struct TagEntry {
+    pub name: String,
+    pub count: usize,
+}
+
+/// A note template for the "New from Template" picker
+#[derive(Debug, Clone)]
+pub struct TemplateEntry {
+    pub path: PathBuf,
+    pub name: String,
+}
+
+/// A task item for the task list, from any note
+#[derive(Debug, Clone)]
+pub struct TaskEntry {
+    pub path: PathBuf,
+    /// Line in the note body
+    pub line: usize,
+    pub text: String,
+    pub done: bool,
+    /// Title of the note, matched by the search too
+    pub note: String,
+}
+
+/// Heading row above the tasks of one note
+#[derive(Debug, Clone)]
+pub struct TaskNote {
+    pub path: PathBuf,
+    pub title: String,
+}
+
+/// A heading in the outline of the active note
+#[derive(Debug, Clone)]
+pub struct HeadingEntry {
+    pub line: usize,
+    pub level: usize,
+    pub text: String,
+}
+
+/// Which tasks the task list shows; Tab cycles through them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskFilter {
+    Open,
+    Done,
+    All,
+}
+
+impl TaskFilter {
+    fn next(self) -> Self {
+        match self {
+            TaskFilter::Open => TaskFilter::Done,
+            TaskFilter::Done => TaskFilter::All,
+            TaskFilter::All => TaskFilter::Open,
+        }
+    }
+
+    fn includes(self, done: bool) -> bool {
+        match self {
+            TaskFilter::Open => !done,
+            TaskFilter::Done => done,
+            TaskFilter::All => true,
+        }
+    }
+}
+
+/// A row of the notes picker: notes normally, tags while typing `#...`.
+/// The template picker lists templates, the task list tasks grouped under
+/// their notes, and the outline headings.
+#[derive(Debug, Clone)]
+pub enum PickerItem {
+    Note(NoteEntry),
+    Tag(TagEntry),
+    Template(TemplateEntry),
+    TaskNote(TaskNote),
+    Task(TaskEntry),
+    Heading(HeadingEntry),
+}
+
+/// What confirming the notes picker asks the app to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum PickerChoice {
+    Open(PathBuf),
+    /// Open a note with the cursor on `line`
+    OpenAt {
+        path: PathBuf,
+        line: usize,
+    },
+    RenameTag {
+        from: String,
+        to: String,
+    },
+    NewFromTemplate {
+        template: PathBuf,
+        title: String,
+    },
+    /// Move the cursor to `line` and `col` of the active note
+    GoTo {
+        line: usize,
+        col: usize,
+    },
+    /// The picker handled it and stays open (a tag was picked as a filter)
+    Handled,
+}
+
+/// Everything the renderer needs to draw the notes picker
+pub struct NotesPickerView<'a> {
+    pub input: &'a TextInput,
+    /// None while the input is asking for a new tag name
+    pub list: Option<&'a ListWidget<PickerItem>>,
+    pub placeholder: String,
+}
+
+/// How the notes picker reads its query
+#[derive(Debug, PartialEq)]
+enum PickerQuery {
+    /// `#wo` lists the tags containing "wo"
+    TagPrefix(String),
+    /// `#work #home plan` lists notes with both tags whose title or folder
+    /// contains "plan"
+    Notes { tags: Vec<String>, text: String },
+}
+
+impl PickerQuery {
+    fn parse(query: &str) -> Self {
+        let query = query.to_lowercase();
+        if let Some(prefix) = query.strip_prefix('#') {
+            if !prefix.contains(char::is_whitespace) {
+                return PickerQuery::TagPrefix(prefix.to_string());
+            }
+        }
+        let (tags, words): (Vec<&str>, Vec<&str>) = query
+            .split_whitespace()
+            .partition(|word| word.starts_with('#') && word.len() > 1);
+        PickerQuery::Notes {
+            tags: tags.iter().map(|tag| tag[1..].to_string()).collect(),
+            text: words.join(" "),
+        }
+    }
+}
+
+/// Whether the characters of `query` appear in `text` in order
+fn fuzzy_match(text: &str, query: &str) -> bool {
+    let mut chars = text.chars();
+    query
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .all(|wanted| chars.any(|ch| ch == wanted))
+}
+
+/// Read `line` or `line:col`, counted from 1, as a zero-based position
+fn parse_position(text: &str) -> Option<(usize, usize)> {
+    let (line, col): (&str, usize) = match text.split_once(':') {
+        Some((line, col)) => (line, col.parse().ok()?),
+        None => (text, 1),
+    };
+    let line: usize = line.parse().ok()?;
+    Some((line.saturating_sub(1), col.saturating_sub(1)))
 }
 
 /// Represents what currently has keyboard focus
@@ -26,15 +194,42 @@ pub enum Focus {
     /// Main text editor (the active tab's content)
     Editor,
     /// Tab title rename input
-    TabRename {
-        tab_index: usize,
-        input: TextInput,
-    },
+    TabRename { tab_index: usize, input: TextInput },
     /// Notes picker (quick open)
     NotesPicker {
         input: TextInput,
-        list: ListWidget<NoteEntry>,
+        /// Notes followed by tags; the query decides which are shown
+        list: ListWidget<PickerItem>,
+        /// Tag being renamed; the input then holds its new name
+        renaming_tag: Option<String>,
+    },
+    /// Template picker ("New from Template")
+    TemplatePicker {
+        input: TextInput,
+        list: ListWidget<PickerItem>,
+        /// Template picked; the input then holds the new note's title
+        template: Option<TemplateEntry>,
+    },
+    /// Tasks across all notes
+    TaskList {
+        input: TextInput,
+        /// Each note's heading followed by its tasks
+        list: ListWidget<PickerItem>,
+        filter: TaskFilter,
     },
+    /// Headings of the active note
+    Outline {
+        input: TextInput,
+        list: ListWidget<PickerItem>,
+    },
+    /// Go to line input; `lines` is the length of the active note
+    GoToLine { input: TextInput, lines: usize },
+    /// Right-click context menu
+    ContextMenu { menu: ContextMenu<Action> },
+    /// Month calendar for opening a daily note
+    JournalCalendar { calendar: Calendar },
+    /// Statistics of the active note, or of its selection
+    Statistics { stats: Stats, selection: bool },
 }
 
 impl Default for Focus {
@@ -101,69 +296,477 @@ impl Focus {
         }
     }
 
-    /// Check if we're in the notes picker
+    /// Check if we're in the notes picker (or the template picker, task
+    /// list, outline or go-to-line input, which share its overlay)
     pub fn is_notes_picker(&self) -> bool {
-        matches!(self, Focus::NotesPicker { .. })
+        matches!(
+            self,
+            Focus::NotesPicker { .. }
+                | Focus::TemplatePicker { .. }
+                | Focus::TaskList { .. }
+                | Focus::Outline { .. }
+                | Focus::GoToLine { .. }
+        )
+    }
+
+    /// Start the notes picker with a list of notes and the tags they use
+    pub fn start_notes_picker(notes: Vec<NoteEntry>, tags: Vec<TagEntry>) -> Self {
+        let items = notes
+            .into_iter()
+            .map(PickerItem::Note)
+            .chain(tags.into_iter().map(PickerItem::Tag))
+            .collect();
+        let mut focus = Focus::NotesPicker {
+            input: TextInput::new(String::new()),
+            list: ListWidget::new(items),
+            renaming_tag: None,
+        };
+        focus.update_notes_filter();
+        focus
     }
 
-    /// Start the notes picker with a list of notes
-    pub fn start_notes_picker(notes: Vec<NoteEntry>) -> Self {
-        Focus::NotesPicker {
+    /// Start the template picker
+    pub fn start_template_picker(templates: Vec<TemplateEntry>) -> Self {
+        Focus::TemplatePicker {
             input: TextInput::new(String::new()),
-            list: ListWidget::new(notes),
+            list: ListWidget::new(templates.into_iter().map(PickerItem::Template).collect()),
+            template: None,
+        }
+    }
+
+    /// Start the task list showing open tasks
+    pub fn start_task_list(items: Vec<PickerItem>) -> Self {
+        let mut focus = Focus::TaskList {
+            input: TextInput::new(String::new()),
+            list: ListWidget::new(items),
+            filter: TaskFilter::Open,
+        };
+        focus.update_notes_filter();
+        focus
+    }
+
+    /// Start the outline with the heading at index `current` highlighted
+    pub fn start_outline(headings: Vec<HeadingEntry>, current: usize) -> Self {
+        let mut list = ListWidget::new(headings.into_iter().map(PickerItem::Heading).collect());
+        list.select_index(current);
+        Focus::Outline {
+            input: TextInput::new(String::new()),
+            list,
+        }
+    }
+
+    /// Start the go to line input for a note of `lines` lines
+    pub fn start_go_to_line(lines: usize) -> Self {
+        Focus::GoToLine {
+            input: TextInput::new(String::new()),
+            lines,
         }
     }
 
     /// Get notes picker state for rendering
-    pub fn notes_picker_state(&self) -> Option<(&TextInput, &ListWidget<NoteEntry>)> {
+    pub fn notes_picker_view(&self) -> Option<NotesPickerView<'_>> {
         match self {
-            Focus::NotesPicker { input, list } => Some((input, list)),
+            Focus::NotesPicker {
+                input,
+                list,
+                renaming_tag,
+                ..
+            } => Some(NotesPickerView {
+                input,
+                list: renaming_tag.is_none().then_some(list),
+                placeholder: match renaming_tag {
+                    Some(tag) => format!("New name for #{tag}"),
+                    None => "Search notes or #tags...".to_string(),
+                },
+            }),
+            Focus::TemplatePicker {
+                input,
+                list,
+                template,
+            } => Some(NotesPickerView {
+                input,
+                list: template.is_none().then_some(list),
+                placeholder: match template {
+                    Some(template) => format!("Title for the new {} note", template.name),
+                    None => "Search templates...".to_string(),
+                },
+            }),
+            Focus::TaskList {
+                input,
+                list,
+                filter,
+            } => Some(NotesPickerView {
+                input,
+                list: Some(list),
+                placeholder: match filter {
+                    TaskFilter::Open => "Search open tasks (Tab: done)",
+                    TaskFilter::Done => "Search done tasks (Tab: all)",
+                    TaskFilter::All => "Search all tasks (Tab: open)",
+                }
+                .to_string(),
+            }),
+            Focus::Outline { input, list } => Some(NotesPickerView {
+                input,
+                list: Some(list),
+                placeholder: "Go to heading...".to_string(),
+            }),
+            Focus::GoToLine { input, lines } => Some(NotesPickerView {
+                input,
+                list: None,
+                placeholder: format!("Go to line or line:column (1-{lines})"),
+            }),
             _ => None,
         }
     }
 
     /// Get mutable notes picker list for mouse interaction
-    pub fn notes_picker_list_mut(&mut self) -> Option<&mut ListWidget<NoteEntry>> {
+    pub fn notes_picker_list_mut(&mut self) -> Option<&mut ListWidget<PickerItem>> {
         match self {
-            Focus::NotesPicker { list, .. } => Some(list),
+            Focus::NotesPicker {
+                list,
+                renaming_tag: None,
+                ..
+            }
+            | Focus::TemplatePicker {
+                list,
+                template: None,
+                ..
+            }
+            | Focus::TaskList { list, .. }
+            | Focus::Outline { list, .. } => Some(list),
             _ => None,
         }
     }
 
     /// Update filtered notes based on search input
     pub fn update_notes_filter(&mut self) {
-        if let Focus::NotesPicker { input, list } = self {
+        if let Focus::Outline { input, list } = self {
             let query = input.text().to_lowercase();
-            if query.is_empty() {
-                list.clear_filter();
-            } else {
-                list.filter(|note| note.title.to_lowercase().contains(&query));
-            }
+            list.filter(|item| {
+                matches!(item, PickerItem::Heading(heading) if fuzzy_match(&heading.text.to_lowercase(), &query))
+            });
+            return;
+        }
+        if let Focus::TaskList {
+            input,
+            list,
+            filter,
+        } = self
+        {
+            let text = input.text().to_lowercase();
+            let shown = |task: &TaskEntry| {
+                filter.includes(task.done)
+                    && (task.text.to_lowercase().contains(&text)
+                        || task.note.to_lowercase().contains(&text))
+            };
+            // Headings stay for notes with at least one task shown
+            let notes: Vec<PathBuf> = list
+                .items()
+                .iter()
+                .filter_map(|item| match item {
+                    PickerItem::Task(task) if shown(task) => Some(task.path.clone()),
+                    _ => None,
+                })
+                .collect();
+            list.filter(|item| match item {
+                PickerItem::Task(task) => shown(task),
+                PickerItem::TaskNote(note) => notes.contains(&note.path),
+                _ => false,
+            });
+            return;
+        }
+        if let Focus::TemplatePicker {
+            input,
+            list,
+            template: None,
+        } = self
+        {
+            let text = input.text().to_lowercase();
+            list.filter(|item| {
+                matches!(item, PickerItem::Template(template) if template.name.to_lowercase().contains(&text))
+            });
+            return;
+        }
+        let Focus::NotesPicker {
+            input,
+            list,
+            renaming_tag: None,
+        } = self
+        else {
+            return;
+        };
+        match PickerQuery::parse(input.text()) {
+            PickerQuery::TagPrefix(prefix) => list
+                .filter(|item| matches!(item, PickerItem::Tag(tag) if tag.name.contains(&prefix))),
+            PickerQuery::Notes { tags, text } => list.filter(|item| {
+                let PickerItem::Note(note) = item else {
+                    return false;
+                };
+                tags.iter().all(|tag| note.tags.contains(tag))
+                    && (note.title.to_lowercase().contains(&text)
+                        || note
+                            .folder
+                            .as_ref()
+                            .is_some_and(|folder| folder.to_lowercase().contains(&text)))
+            }),
         }
     }
 
     /// Move selection up in notes picker
     pub fn notes_picker_up(&mut self) {
-        if let Focus::NotesPicker { list, .. } = self {
+        if let Some(list) = self.notes_picker_list_mut() {
             list.select_up();
         }
     }
 
     /// Move selection down in notes picker
     pub fn notes_picker_down(&mut self) {
-        if let Focus::NotesPicker { list, .. } = self {
+        if let Some(list) = self.notes_picker_list_mut() {
             list.select_down();
         }
     }
 
-    /// Confirm notes picker selection, returns the selected note path
-    pub fn confirm_notes_picker(&mut self) -> Option<PathBuf> {
-        match std::mem::take(self) {
-            Focus::NotesPicker { list, .. } => {
-                let path = list.selected_item().map(|n| n.path.clone());
+    /// Confirm the notes picker. A note is opened, a tag becomes the
+    /// filter, and a new tag name is applied. In the template picker a
+    /// template is picked, then the typed title creates the note.
+    pub fn confirm_notes_picker(&mut self) -> Option<PickerChoice> {
+        if let Focus::GoToLine { input, .. } = self {
+            let Some((line, col)) = parse_position(input.text()) else {
+                return Some(PickerChoice::Handled);
+            };
+            *self = Focus::Editor;
+            return Some(PickerChoice::GoTo { line, col });
+        }
+        if let Focus::Outline { list, .. } = self {
+            let choice = match list.selected_item() {
+                Some(PickerItem::Heading(heading)) => PickerChoice::GoTo {
+                    line: heading.line,
+                    col: 0,
+                },
+                _ => PickerChoice::Handled,
+            };
+            *self = Focus::Editor;
+            return Some(choice);
+        }
+        if let Focus::TaskList { list, .. } = self {
+            let choice = match list.selected_item() {
+                Some(PickerItem::Task(task)) => PickerChoice::OpenAt {
+                    path: task.path.clone(),
+                    line: task.line,
+                },
+                Some(PickerItem::TaskNote(note)) => PickerChoice::Open(note.path.clone()),
+                _ => PickerChoice::Handled,
+            };
+            *self = Focus::Editor;
+            return Some(choice);
+        }
+        if let Focus::TemplatePicker {
+            input,
+            list,
+            template,
+        } = self
+        {
+            if let Some(chosen) = template {
+                let title = input.text().trim().to_string();
+                if title.is_empty() {
+                    return Some(PickerChoice::Handled);
+                }
+                let template = chosen.path.clone();
+                *self = Focus::Editor;
+                return Some(PickerChoice::NewFromTemplate { template, title });
+            }
+            let Some(PickerItem::Template(chosen)) = list.selected_item() else {
+                return Some(PickerChoice::Handled);
+            };
+            *template = Some(chosen.clone());
+            *input = TextInput::new(String::new());
+            return Some(PickerChoice::Handled);
+        }
+        let Focus::NotesPicker {
+            input,
+            list,
+            renaming_tag,
+            ..
+        } = self
+        else {
+            return None;
+        };
+
+        if let Some(from) = renaming_tag.clone() {
+            let to = tags::normalize(input.text());
+            if to.is_empty() || to.contains(char::is_whitespace) {
+                return Some(PickerChoice::Handled);
+            }
+            *self = Focus::Editor;
+            return Some(PickerChoice::RenameTag { from, to });
+        }
+
+        match list.selected_item() {
+            Some(PickerItem::Tag(tag)) => {
+                *input = TextInput::new(format!("#{} ", tag.name));
+                self.update_notes_filter();
+                Some(PickerChoice::Handled)
+            }
+            Some(PickerItem::Note(note)) => {
+                let path = note.path.clone();
+                *self = Focus::Editor;
+                Some(PickerChoice::Open(path))
+            }
+            // The notes picker never lists templates, tasks or headings
+            Some(
+                PickerItem::Template(_)
+                | PickerItem::TaskNote(_)
+                | PickerItem::Task(_)
+                | PickerItem::Heading(_),
+            )
+            | None => {
+                *self = Focus::Editor;
+                Some(PickerChoice::Handled)
+            }
+        }
+    }
+
+    /// Ask for a new name for the highlighted tag. Returns false unless the
+    /// picker is showing tags.
+    pub fn start_tag_rename(&mut self) -> bool {
+        let Focus::NotesPicker {
+            input,
+            list,
+            renaming_tag,
+            ..
+        } = self
+        else {
+            return false;
+        };
+        let Some(PickerItem::Tag(tag)) = list.selected_item() else {
+            return false;
+        };
+        let name = tag.name.clone();
+        *input = TextInput::new(name.clone());
+        input.select_all();
+        *renaming_tag = Some(name);
+        true
+    }
+
+    /// Cancel notes picker and return to Editor focus. While renaming a tag
+    /// this goes back to the tag list instead.
+    pub fn cancel_notes_picker(&mut self) -> bool {
+        match self {
+            Focus::NotesPicker {
+                input,
+                renaming_tag,
+                ..
+            } if renaming_tag.is_some() => {
+                *input = TextInput::new(format!("#{}", renaming_tag.take().unwrap_or_default()));
+                self.update_notes_filter();
+                true
+            }
+            Focus::TemplatePicker {
+                input, template, ..
+            } if template.is_some() => {
+                *input = TextInput::new(String::new());
+                *template = None;
+                self.update_notes_filter();
+                true
+            }
+            Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. } => {
                 *self = Focus::Editor;
-                path
+                true
             }
+            _ => false,
+        }
+    }
+
+    /// Check if the journal calendar is open
+    pub fn is_journal_calendar(&self) -> bool {
+        matches!(self, Focus::JournalCalendar { .. })
+    }
+
+    /// Get the journal calendar for rendering
+    pub fn journal_calendar(&self) -> Option<&Calendar> {
+        match self {
+            Focus::JournalCalendar { calendar } => Some(calendar),
+            _ => None,
+        }
+    }
+
+    /// Get mutable access to the journal calendar for mouse and paging
+    pub fn journal_calendar_mut(&mut self) -> Option<&mut Calendar> {
+        match self {
+            Focus::JournalCalendar { calendar } => Some(calendar),
+            _ => None,
+        }
+    }
+
+    /// Close the calendar, returning the selected day
+    pub fn confirm_journal_calendar(&mut self) -> Option<Date> {
+        let date = self.journal_calendar()?.selected();
+        *self = Focus::Editor;
+        Some(date)
+    }
+
+    /// Close the calendar and return to Editor focus
+    pub fn cancel_journal_calendar(&mut self) -> bool {
+        if self.is_journal_calendar() {
+            *self = Focus::Editor;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Get the statistics overlay's counts, and whether they cover only
+    /// the selection
+    pub fn statistics(&self) -> Option<(&Stats, bool)> {
+        match self {
+            Focus::Statistics { stats, selection } => Some((stats, *selection)),
+            _ => None,
+        }
+    }
+
+    /// Close the statistics overlay and return to Editor focus
+    pub fn close_statistics(&mut self) -> bool {
+        if self.statistics().is_some() {
+            *self = Focus::Editor;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Check if a context menu is open
+    pub fn is_context_menu(&self) -> bool {
+        matches!(self, Focus::ContextMenu { .. })
+    }
+
+    /// Get the open context menu for rendering and hit testing
+    pub fn context_menu(&self) -> Option<&ContextMenu<Action>> {
+        match self {
+            Focus::ContextMenu { menu } => Some(menu),
+            _ => None,
+        }
+    }
+
+    /// Get mutable access to the open context menu for hover updates
+    pub fn context_menu_mut(&mut self) -> Option<&mut ContextMenu<Action>> {
+        match self {
+            Focus::ContextMenu { menu } => Some(menu),
+            _ => None,
+        }
+    }
+
+    /// Close the context menu, returning the action of the item at `index`
+    /// (or of the highlighted item when `index` is None)
+    pub fn take_context_menu_action(&mut self, index: Option<usize>) -> Option<Action> {
+        match std::mem::take(self) {
+            Focus::ContextMenu { menu } => match index {
+                Some(index) => menu.action_at(index).copied(),
+                None => menu.selected_action().copied(),
+            },
             other => {
                 *self = other;
                 None
@@ -171,9 +774,9 @@ impl Focus {
         }
     }
 
-    /// Cancel notes picker and return to Editor focus
-    pub fn cancel_notes_picker(&mut self) -> bool {
-        if self.is_notes_picker() {
+    /// Close the context menu and return to Editor focus
+    pub fn cancel_context_menu(&mut self) -> bool {
+        if self.is_context_menu() {
             *self = Focus::Editor;
             true
         } else {
@@ -191,7 +794,21 @@ impl InputHandler for Focus {
                 input.insert_char(ch);
                 InputResult::Handled
             }
-            Focus::NotesPicker { input, .. } => {
+            Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
+            Focus::TaskList { filter, .. } if ch == '\t' => {
+                *filter = filter.next();
+                self.update_notes_filter();
+                InputResult::Handled
+            }
+            // Only digits and the line:column separator are typed
+            Focus::GoToLine { .. } if !(ch.is_ascii_digit() || ch == ':') => InputResult::Handled,
+            Focus::NotesPicker { input, .. }
+            | Focus::TemplatePicker { input, .. }
+            | Focus::TaskList { input, .. }
+            | Focus::Outline { input, .. }
+            | Focus::GoToLine { input, .. } => {
                 input.insert_char(ch);
                 self.update_notes_filter();
                 InputResult::Handled
@@ -206,7 +823,14 @@ impl InputHandler for Focus {
                 input.backspace();
                 InputResult::Handled
             }
-            Focus::NotesPicker { input, .. } => {
+            Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
+            Focus::NotesPicker { input, .. }
+            | Focus::TemplatePicker { input, .. }
+            | Focus::TaskList { input, .. }
+            | Focus::Outline { input, .. }
+            | Focus::GoToLine { input, .. } => {
                 input.backspace();
                 self.update_notes_filter();
                 InputResult::Handled
@@ -221,7 +845,14 @@ impl InputHandler for Focus {
                 input.delete();
                 InputResult::Handled
             }
-            Focus::NotesPicker { .. } => InputResult::Ignored,
+            Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. }
+            | Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
         }
     }
 
@@ -232,7 +863,14 @@ impl InputHandler for Focus {
                 input.delete_word_left();
                 InputResult::Handled
             }
-            Focus::NotesPicker { .. } => InputResult::Ignored,
+            Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. }
+            | Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
         }
     }
 
@@ -243,7 +881,14 @@ impl InputHandler for Focus {
                 input.delete_word_right();
                 InputResult::Handled
             }
-            Focus::NotesPicker { .. } => InputResult::Ignored,
+            Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. }
+            | Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
         }
     }
 
@@ -254,7 +899,14 @@ impl InputHandler for Focus {
                 input.select_all();
                 InputResult::Handled
             }
-            Focus::NotesPicker { .. } => InputResult::Ignored,
+            Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. }
+            | Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
         }
     }
 
@@ -265,7 +917,17 @@ impl InputHandler for Focus {
                 input.move_left(selecting);
                 InputResult::Handled
             }
-            Focus::NotesPicker { .. } => InputResult::Ignored,
+            Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. }
+            | Focus::ContextMenu { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
+            Focus::JournalCalendar { calendar } => {
+                calendar.move_days(-1);
+                InputResult::Handled
+            }
         }
     }
 
@@ -276,29 +938,63 @@ impl InputHandler for Focus {
                 input.move_right(selecting);
                 InputResult::Handled
             }
-            Focus::NotesPicker { .. } => InputResult::Ignored,
+            Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. }
+            | Focus::ContextMenu { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
+            Focus::JournalCalendar { calendar } => {
+                calendar.move_days(1);
+                InputResult::Handled
+            }
         }
     }
 
     fn move_up(&mut self, _selecting: bool) -> InputResult {
         match self {
             Focus::Editor => InputResult::NotHandled,
-            Focus::TabRename { .. } => InputResult::Ignored,
-            Focus::NotesPicker { .. } => {
+            Focus::TabRename { .. } | Focus::Statistics { .. } => InputResult::Ignored,
+            Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. } => {
                 self.notes_picker_up();
                 InputResult::Handled
             }
+            Focus::ContextMenu { menu } => {
+                menu.select_previous();
+                InputResult::Handled
+            }
+            Focus::JournalCalendar { calendar } => {
+                calendar.move_days(-7);
+                InputResult::Handled
+            }
         }
     }
 
     fn move_down(&mut self, _selecting: bool) -> InputResult {
         match self {
             Focus::Editor => InputResult::NotHandled,
-            Focus::TabRename { .. } => InputResult::Ignored,
-            Focus::NotesPicker { .. } => {
+            Focus::TabRename { .. } | Focus::Statistics { .. } => InputResult::Ignored,
+            Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. } => {
                 self.notes_picker_down();
                 InputResult::Handled
             }
+            Focus::ContextMenu { menu } => {
+                menu.select_next();
+                InputResult::Handled
+            }
+            Focus::JournalCalendar { calendar } => {
+                calendar.move_days(7);
+                InputResult::Handled
+            }
         }
     }
 
@@ -309,7 +1005,14 @@ impl InputHandler for Focus {
                 input.move_word_left(selecting);
                 InputResult::Handled
             }
-            Focus::NotesPicker { .. } => InputResult::Ignored,
+            Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. }
+            | Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
         }
     }
 
@@ -320,7 +1023,50 @@ impl InputHandler for Focus {
                 input.move_word_right(selecting);
                 InputResult::Handled
             }
-            Focus::NotesPicker { .. } => InputResult::Ignored,
+            Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. }
+            | Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
+        }
+    }
+
+    fn move_subword_left(&mut self, selecting: bool) -> InputResult {
+        match self {
+            Focus::Editor => InputResult::NotHandled,
+            Focus::TabRename { input, .. } => {
+                input.move_subword_left(selecting);
+                InputResult::Handled
+            }
+            Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. }
+            | Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
+        }
+    }
+
+    fn move_subword_right(&mut self, selecting: bool) -> InputResult {
+        match self {
+            Focus::Editor => InputResult::NotHandled,
+            Focus::TabRename { input, .. } => {
+                input.move_subword_right(selecting);
+                InputResult::Handled
+            }
+            Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. }
+            | Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
         }
     }
 
@@ -331,7 +1077,14 @@ impl InputHandler for Focus {
                 input.move_to_start(selecting);
                 InputResult::Handled
             }
-            Focus::NotesPicker { .. } => InputResult::Ignored,
+            Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. }
+            | Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
         }
     }
 
@@ -342,21 +1095,44 @@ impl InputHandler for Focus {
                 input.move_to_end(selecting);
                 InputResult::Handled
             }
-            Focus::NotesPicker { .. } => InputResult::Ignored,
+            Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. }
+            | Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
         }
     }
 
     fn move_to_start(&mut self, _selecting: bool) -> InputResult {
         match self {
             Focus::Editor => InputResult::NotHandled,
-            Focus::TabRename { .. } | Focus::NotesPicker { .. } => InputResult::Ignored,
+            Focus::TabRename { .. }
+            | Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. }
+            | Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
         }
     }
 
     fn move_to_end(&mut self, _selecting: bool) -> InputResult {
         match self {
             Focus::Editor => InputResult::NotHandled,
-            Focus::TabRename { .. } | Focus::NotesPicker { .. } => InputResult::Ignored,
+            Focus::TabRename { .. }
+            | Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. }
+            | Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
         }
     }
 
@@ -364,7 +1140,14 @@ impl InputHandler for Focus {
         match self {
             Focus::Editor => None,
             Focus::TabRename { input, .. } => input.copy(),
-            Focus::NotesPicker { .. } => None,
+            Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. }
+            | Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => None,
         }
     }
 
@@ -372,7 +1155,14 @@ impl InputHandler for Focus {
         match self {
             Focus::Editor => None,
             Focus::TabRename { input, .. } => input.cut(),
-            Focus::NotesPicker { .. } => None,
+            Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. }
+            | Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => None,
         }
     }
 
@@ -383,21 +1173,191 @@ impl InputHandler for Focus {
                 input.paste(text);
                 InputResult::Handled
             }
-            Focus::NotesPicker { .. } => InputResult::Ignored,
+            Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. }
+            | Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
+        }
+    }
+
+    fn commit_text(&mut self, text: &str) -> InputResult {
+        match self {
+            Focus::Editor => InputResult::NotHandled,
+            Focus::TabRename { input, .. } => {
+                input.paste(text);
+                InputResult::Handled
+            }
+            Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
+            Focus::NotesPicker { input, .. }
+            | Focus::TemplatePicker { input, .. }
+            | Focus::TaskList { input, .. }
+            | Focus::Outline { input, .. }
+            | Focus::GoToLine { input, .. } => {
+                input.paste(text);
+                self.update_notes_filter();
+                InputResult::Handled
+            }
         }
     }
 
     fn undo(&mut self) -> InputResult {
         match self {
             Focus::Editor => InputResult::NotHandled,
-            Focus::TabRename { .. } | Focus::NotesPicker { .. } => InputResult::Ignored,
+            Focus::TabRename { .. }
+            | Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. }
+            | Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
         }
     }
 
     fn redo(&mut self) -> InputResult {
         match self {
             Focus::Editor => InputResult::NotHandled,
-            Focus::TabRename { .. } | Focus::NotesPicker { .. } => InputResult::Ignored,
+            Focus::TabRename { .. }
+            | Focus::NotesPicker { .. }
+            | Focus::TemplatePicker { .. }
+            | Focus::TaskList { .. }
+            | Focus::Outline { .. }
+            | Focus::GoToLine { .. }
+            | Focus::ContextMenu { .. }
+            | Focus::JournalCalendar { .. }
+            | Focus::Statistics { .. } => InputResult::Ignored,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_picker_query() {
+        assert_eq!(
+            PickerQuery::parse("#Wo"),
+            PickerQuery::TagPrefix("wo".to_string())
+        );
+        assert_eq!(
+            PickerQuery::parse("#"),
+            PickerQuery::TagPrefix(String::new())
+        );
+        assert_eq!(
+            PickerQuery::parse("#work Plan #home"),
+            PickerQuery::Notes {
+                tags: vec!["work".to_string(), "home".to_string()],
+                text: "plan".to_string(),
+            }
+        );
+    }
+
+    fn task(note: &str, text: &str, done: bool) -> PickerItem {
+        PickerItem::Task(TaskEntry {
+            path: PathBuf::from(format!("{note}.md")),
+            line: 0,
+            text: text.to_string(),
+            done,
+            note: note.to_string(),
+        })
+    }
+
+    fn heading(note: &str) -> PickerItem {
+        PickerItem::TaskNote(TaskNote {
+            path: PathBuf::from(format!("{note}.md")),
+            title: note.to_string(),
+        })
+    }
+
+    fn shown(focus: &Focus) -> Vec<String> {
+        let list = focus
+            .notes_picker_view()
+            .and_then(|view| view.list)
+            .unwrap();
+        list.filtered_indices()
+            .iter()
+            .map(|index| match &list.items()[*index] {
+                PickerItem::TaskNote(note) => note.title.clone(),
+                PickerItem::Task(task) => format!("- {}", task.text),
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_go_to_line_input() {
+        assert_eq!(parse_position("12"), Some((11, 0)));
+        assert_eq!(parse_position("3:7"), Some((2, 6)));
+        assert_eq!(parse_position(":4"), None);
+
+        let mut focus = Focus::start_go_to_line(50);
+        for ch in "2x:5".chars() {
+            focus.handle_char(ch);
+        }
+        assert_eq!(
+            focus.confirm_notes_picker(),
+            Some(PickerChoice::GoTo { line: 1, col: 4 })
+        );
+    }
+
+    #[test]
+    fn test_outline_fuzzy_filter() {
+        let headings = ["Introduction", "Getting started", "Installation"]
+            .iter()
+            .enumerate()
+            .map(|(line, text)| HeadingEntry {
+                line: line * 10,
+                level: 2,
+                text: text.to_string(),
+            })
+            .collect();
+        let mut focus = Focus::start_outline(headings, 2);
+        for ch in "instl".chars() {
+            focus.handle_char(ch);
+        }
+        let list = focus
+            .notes_picker_view()
+            .and_then(|view| view.list)
+            .unwrap();
+        assert_eq!(list.filtered_indices(), [2]);
+        assert_eq!(
+            focus.confirm_notes_picker(),
+            Some(PickerChoice::GoTo { line: 20, col: 0 })
+        );
+        assert!(matches!(focus, Focus::Editor));
+    }
+
+    #[test]
+    fn test_task_list_filters() {
+        let mut focus = Focus::start_task_list(vec![
+            heading("Home"),
+            task("Home", "paint", true),
+            task("Home", "clean", false),
+            heading("Work"),
+            task("Work", "ship", true),
+        ]);
+        assert_eq!(shown(&focus), ["Home", "- clean"]);
+
+        // Tab shows done tasks, keeping only headings with tasks shown
+        focus.handle_char('\t');
+        assert_eq!(shown(&focus), ["Home", "- paint", "Work", "- ship"]);
+        focus.handle_char('s');
+        assert_eq!(shown(&focus), ["Work", "- ship"]);
+
+        focus.handle_char('\t');
+        focus.handle_backspace();
+        assert_eq!(shown(&focus).len(), 5);
+        assert!(matches!(
+            focus.confirm_notes_picker(),
+            Some(PickerChoice::Open(_))
+        ));
+    }
+}
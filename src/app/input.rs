@@ -7,6 +7,7 @@
 
 use super::input_handler::InputHandler;
 use super::state::AppResult;
+use super::ui_state::ImePreedit;
 use super::App;
 
 impl App {
@@ -37,7 +38,7 @@ impl App {
             });
         }
 
-        self.tabs[self.active_tab].auto_save();
+        self.auto_save_active();
         self.auto_scroll();
         AppResult::Redraw
     }
@@ -51,7 +52,7 @@ impl App {
         }
 
         self.tabs[self.active_tab].backspace();
-        self.tabs[self.active_tab].auto_save();
+        self.auto_save_active();
         self.auto_scroll();
         AppResult::Redraw
     }
@@ -65,7 +66,7 @@ impl App {
         }
 
         self.tabs[self.active_tab].delete_word_left();
-        self.tabs[self.active_tab].auto_save();
+        self.auto_save_active();
         self.auto_scroll();
         AppResult::Redraw
     }
@@ -92,7 +93,7 @@ impl App {
         }
 
         self.tabs[self.active_tab].delete_word_right();
-        self.tabs[self.active_tab].auto_save();
+        self.auto_save_active();
         self.auto_scroll();
         AppResult::Redraw
     }
@@ -161,6 +162,30 @@ impl App {
         AppResult::Redraw
     }
 
+    pub fn move_cursor_subword_left(&mut self, selecting: bool) -> AppResult {
+        let result = self.focus.move_subword_left(selecting);
+        if result.was_handled() {
+            self.ui_state.reset_cursor_blink();
+            return result.into();
+        }
+
+        self.tabs[self.active_tab].move_subword_left(selecting);
+        self.auto_scroll();
+        AppResult::Redraw
+    }
+
+    pub fn move_cursor_subword_right(&mut self, selecting: bool) -> AppResult {
+        let result = self.focus.move_subword_right(selecting);
+        if result.was_handled() {
+            self.ui_state.reset_cursor_blink();
+            return result.into();
+        }
+
+        self.tabs[self.active_tab].move_subword_right(selecting);
+        self.auto_scroll();
+        AppResult::Redraw
+    }
+
     pub fn move_cursor_up(&mut self, selecting: bool) -> AppResult {
         let result = self.focus.move_up(selecting);
         if result.was_handled() {
@@ -239,7 +264,7 @@ impl App {
         }
 
         if self.tabs[self.active_tab].move_lines_up() {
-            self.tabs[self.active_tab].auto_save();
+            self.auto_save_active();
             self.auto_scroll();
             return AppResult::Redraw;
         }
@@ -252,7 +277,7 @@ impl App {
         }
 
         if self.tabs[self.active_tab].move_lines_down() {
-            self.tabs[self.active_tab].auto_save();
+            self.auto_save_active();
             self.auto_scroll();
             return AppResult::Redraw;
         }
@@ -270,7 +295,7 @@ impl App {
         }
 
         if self.tabs[self.active_tab].undo() {
-            self.tabs[self.active_tab].auto_save();
+            self.auto_save_active();
             self.auto_scroll();
             return AppResult::Redraw;
         }
@@ -284,7 +309,7 @@ impl App {
         }
 
         if self.tabs[self.active_tab].redo() {
-            self.tabs[self.active_tab].auto_save();
+            self.auto_save_active();
             self.auto_scroll();
             return AppResult::Redraw;
         }
@@ -305,6 +330,32 @@ impl App {
         AppResult::Redraw
     }
 
+    pub fn zoom_in(&mut self) -> AppResult {
+        self.set_zoom(self.zoom + crate::config::zoom::STEP)
+    }
+
+    pub fn zoom_out(&mut self) -> AppResult {
+        self.set_zoom(self.zoom - crate::config::zoom::STEP)
+    }
+
+    pub fn zoom_reset(&mut self) -> AppResult {
+        self.set_zoom(crate::config::zoom::DEFAULT)
+    }
+
+    /// Apply a new content zoom factor; line height and char width follow it
+    fn set_zoom(&mut self, zoom: f32) -> AppResult {
+        use crate::config::zoom::{MAX, MIN};
+        // Round to the step grid so repeated steps don't accumulate float error
+        let zoom = ((zoom * 100.0).round() / 100.0).clamp(MIN, MAX);
+        if (zoom - self.zoom).abs() < f32::EPSILON {
+            return AppResult::Ok;
+        }
+        self.zoom = zoom;
+        self.renderer.set_zoom(zoom);
+        self.auto_scroll();
+        AppResult::Redraw
+    }
+
     // =========================================================================
     // Clipboard operations
     // =========================================================================
@@ -348,7 +399,7 @@ impl App {
             if let Some(clipboard) = &mut self.clipboard {
                 let _ = clipboard.set_text(text);
             }
-            self.tabs[self.active_tab].auto_save();
+            self.auto_save_active();
             return AppResult::Redraw;
         }
         AppResult::Ok
@@ -366,7 +417,7 @@ impl App {
                 // Only allow editor paste when in editor focus
                 if matches!(self.focus, super::focus::Focus::Editor) {
                     self.tabs[self.active_tab].paste_text(&text);
-                    self.tabs[self.active_tab].auto_save();
+                    self.auto_save_active();
                     self.auto_scroll();
                     return AppResult::Redraw;
                 }
@@ -374,4 +425,53 @@ impl App {
         }
         AppResult::Ok
     }
+
+    /// Update the IME composition shown inline at the cursor
+    pub fn handle_ime_preedit(&mut self, text: String, cursor: Option<(usize, usize)>) -> AppResult {
+        let preedit = (!text.is_empty()).then_some(ImePreedit { text, cursor });
+        if self.ui_state.ime_preedit == preedit {
+            return AppResult::Ok;
+        }
+        self.ui_state.ime_preedit = preedit;
+        self.ui_state.reset_cursor_blink();
+        AppResult::Redraw
+    }
+
+    /// Insert text committed by the IME (or a compose/dead-key sequence)
+    ///
+    /// The whole commit goes through `insert_str`, so it is undone in one step.
+    pub fn handle_ime_commit(&mut self, text: &str) -> AppResult {
+        self.ui_state.ime_preedit = None;
+        if text.is_empty() {
+            return AppResult::Redraw;
+        }
+
+        let result = self.focus.commit_text(text);
+        if result.was_handled() {
+            self.ui_state.reset_cursor_blink();
+            return AppResult::Redraw;
+        }
+
+        let line = self.tabs[self.active_tab].cursor_line();
+        let col = self.tabs[self.active_tab].cursor_col();
+        self.tabs[self.active_tab].paste_text(text);
+        self.ui_state
+            .typing_flame_positions
+            .push((line, col, std::time::Instant::now()));
+
+        self.auto_save_active();
+        self.auto_scroll();
+        AppResult::Redraw
+    }
+
+    /// Auto-save the active tab; a newly created note also appears in the
+    /// sidebar
+    pub(super) fn auto_save_active(&mut self) {
+        let tab = &mut self.tabs[self.active_tab];
+        let was_on_disk = tab.path().is_some();
+        tab.auto_save();
+        if !was_on_disk && tab.path().is_some() {
+            self.refresh_panels();
+        }
+    }
 }
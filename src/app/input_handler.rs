@@ -92,6 +92,16 @@ pub trait InputHandler {
         InputResult::Ignored
     }
 
+    /// Move cursor to the previous camelCase / snake_case part
+    fn move_subword_left(&mut self, _selecting: bool) -> InputResult {
+        InputResult::Ignored
+    }
+
+    /// Move cursor to the next camelCase / snake_case part
+    fn move_subword_right(&mut self, _selecting: bool) -> InputResult {
+        InputResult::Ignored
+    }
+
     /// Move to line start (Home)
     fn move_to_line_start(&mut self, _selecting: bool) -> InputResult {
         InputResult::Ignored
@@ -137,6 +147,11 @@ pub trait InputHandler {
         InputResult::Ignored
     }
 
+    /// Insert text committed by an IME or compose sequence
+    fn commit_text(&mut self, _text: &str) -> InputResult {
+        InputResult::Ignored
+    }
+
     /// Handle undo
     fn undo(&mut self) -> InputResult {
         InputResult::Ignored
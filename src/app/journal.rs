@@ -0,0 +1,114 @@
+//! Daily notes: today's note, stepping between days and the calendar picker
+
+use crate::date::Date;
+use crate::persistence;
+use crate::ui::{calendar_rect, Calendar};
+
+use super::focus::Focus;
+use super::state::AppResult;
+use super::App;
+
+impl App {
+    /// Open today's daily note, creating it from the template first
+    pub fn open_today_note(&mut self) -> AppResult {
+        self.open_journal_day(Date::today())
+    }
+
+    /// Open the daily note of `date`, creating it when missing
+    pub fn open_journal_day(&mut self, date: Date) -> AppResult {
+        let existed = persistence::journal_note_path(date).exists();
+        let Ok(path) = persistence::create_journal_note(date) else {
+            return AppResult::Ok;
+        };
+        if !existed {
+            self.refresh_panels();
+        }
+        self.open_note_by_path(path)
+    }
+
+    /// Day of the active tab's daily note, if it is one
+    fn active_journal_date(&self) -> Option<Date> {
+        persistence::journal_date(self.tabs[self.active_tab].path()?)
+    }
+
+    /// Open the closest earlier daily note. From a note that is not a daily
+    /// note this starts at today.
+    pub fn journal_previous_day(&mut self) -> AppResult {
+        let from = self.active_journal_date().unwrap_or_else(Date::today);
+        let previous = persistence::journal_dates()
+            .into_iter()
+            .rev()
+            .find(|date| *date < from);
+        match previous {
+            Some(date) => self.open_journal_day(date),
+            None => AppResult::Ok,
+        }
+    }
+
+    /// Open the closest later daily note, or today's once there are no
+    /// later notes before it
+    pub fn journal_next_day(&mut self) -> AppResult {
+        let today = Date::today();
+        let from = self.active_journal_date().unwrap_or(today);
+        let next = persistence::journal_dates()
+            .into_iter()
+            .find(|date| *date > from)
+            .or((from < today).then_some(today));
+        match next {
+            Some(date) => self.open_journal_day(date),
+            None => AppResult::Ok,
+        }
+    }
+
+    /// Show the month calendar, starting at the active daily note or today
+    pub fn open_journal_calendar(&mut self) -> AppResult {
+        let today = Date::today();
+        let selected = self.active_journal_date().unwrap_or(today);
+        let calendar = Calendar::new(selected, today, persistence::journal_dates());
+        self.focus = Focus::JournalCalendar { calendar };
+        AppResult::Redraw
+    }
+
+    pub fn confirm_journal_calendar(&mut self) -> AppResult {
+        match self.focus.confirm_journal_calendar() {
+            Some(date) => self.open_journal_day(date),
+            None => AppResult::Ok,
+        }
+    }
+
+    pub fn cancel_journal_calendar(&mut self) -> AppResult {
+        if self.focus.cancel_journal_calendar() {
+            return AppResult::Redraw;
+        }
+        AppResult::Ok
+    }
+
+    /// Turn the calendar by whole months (Page Up/Down)
+    pub(super) fn page_journal_calendar(&mut self, months: i32) -> Option<AppResult> {
+        let calendar = self.focus.journal_calendar_mut()?;
+        calendar.move_months(months);
+        Some(AppResult::Redraw)
+    }
+
+    /// Clicking a day selects it, clicking it again opens it; clicking
+    /// outside the calendar closes it
+    pub(super) fn handle_journal_calendar_click(&mut self, x: f32, y: f32) -> AppResult {
+        let rect = calendar_rect(self.width, self.scale);
+        if !rect.contains(x, y) {
+            return self.cancel_journal_calendar();
+        }
+        let scale = self.scale;
+        let Some(calendar) = self.focus.journal_calendar_mut() else {
+            return AppResult::Ok;
+        };
+        let Some(index) = calendar.index_at(rect, scale, x, y) else {
+            return AppResult::Ok;
+        };
+        if calendar.days().selected_index() == index {
+            return self.confirm_journal_calendar();
+        }
+        let date = calendar.month().add_days(index as i64);
+        calendar.select(date);
+        AppResult::Redraw
+    }
+}
@@ -59,6 +59,7 @@ pub enum Key {
     PageUp,
     PageDown,
     Space,
+    F7,
 }
 
 /// A keyboard input event (key + modifiers)
@@ -79,6 +80,17 @@ impl KeyEvent {
 /// This is the single source of truth for all keyboard shortcuts.
 /// Returns None if the key event doesn't map to any action.
 pub fn resolve(event: &KeyEvent) -> Option<Action> {
+    resolve_with(event, true)
+}
+
+/// Resolve a key event when the cursor is not in a table: Ctrl+Alt+arrows
+/// then do what they would without Alt's table meaning (Ctrl+Alt+Left and
+/// Right still move by word)
+pub fn resolve_outside_table(event: &KeyEvent) -> Option<Action> {
+    resolve_with(event, false)
+}
+
+fn resolve_with(event: &KeyEvent, in_table: bool) -> Option<Action> {
     let KeyEvent { key, modifiers } = event;
     let Modifiers { ctrl, shift, alt } = *modifiers;
 
@@ -92,6 +104,8 @@ pub fn resolve(event: &KeyEvent) -> Option<Action> {
         // =================================================================
         // Enter - Confirm or insert newline
         // =================================================================
+        // Ctrl+Enter checks or unchecks the task on the cursor line
+        Key::Enter if ctrl => Some(Action::ToggleTask),
         Key::Enter => Some(Action::Confirm),
 
         // =================================================================
@@ -99,7 +113,9 @@ pub fn resolve(event: &KeyEvent) -> Option<Action> {
         // =================================================================
         Key::Tab if ctrl && shift => Some(Action::PreviousTab),
         Key::Tab if ctrl => Some(Action::NextTab),
-        Key::Tab if !ctrl && !alt => Some(Action::InsertChar('\t')),
+        // Tab expands snippets and moves between their tab stops
+        Key::Tab if shift && !ctrl && !alt => Some(Action::PreviousTabStop),
+        Key::Tab if !ctrl && !alt => Some(Action::InsertTab),
 
         // =================================================================
         // Backspace/Delete
@@ -112,9 +128,19 @@ pub fn resolve(event: &KeyEvent) -> Option<Action> {
         // =================================================================
         // Arrow keys
         // =================================================================
+        // Ctrl+Alt+arrows insert and delete table rows and columns
+        Key::ArrowLeft if ctrl && alt && in_table => Some(Action::TableDeleteColumn),
+        Key::ArrowRight if ctrl && alt && in_table => Some(Action::TableInsertColumn),
+        Key::ArrowUp if ctrl && alt && in_table => Some(Action::TableDeleteRow),
+        Key::ArrowDown if ctrl && alt && in_table => Some(Action::TableInsertRow),
+        // Ctrl+Up/Down jump between headings
+        Key::ArrowUp if ctrl => Some(Action::PreviousHeading),
+        Key::ArrowDown if ctrl => Some(Action::NextHeading),
         Key::ArrowLeft if ctrl => Some(Action::CursorWordLeft { selecting: shift }),
+        Key::ArrowLeft if alt => Some(Action::CursorSubWordLeft { selecting: shift }),
         Key::ArrowLeft => Some(Action::CursorLeft { selecting: shift }),
         Key::ArrowRight if ctrl => Some(Action::CursorWordRight { selecting: shift }),
+        Key::ArrowRight if alt => Some(Action::CursorSubWordRight { selecting: shift }),
         Key::ArrowRight => Some(Action::CursorRight { selecting: shift }),
         Key::ArrowUp if alt => Some(Action::MoveLinesUp),
         Key::ArrowUp => Some(Action::CursorUp { selecting: shift }),
@@ -132,9 +158,16 @@ pub fn resolve(event: &KeyEvent) -> Option<Action> {
         // =================================================================
         // Page Up/Down
         // =================================================================
+        Key::PageUp if alt => Some(Action::JournalPreviousDay),
+        Key::PageDown if alt => Some(Action::JournalNextDay),
         Key::PageUp => Some(Action::PageUp { selecting: shift }),
         Key::PageDown => Some(Action::PageDown { selecting: shift }),
 
+        // =================================================================
+        // Function keys
+        // =================================================================
+        Key::F7 => Some(Action::ToggleSpellCheck),
+
         // =================================================================
         // Space
         // =================================================================
@@ -157,12 +190,28 @@ fn resolve_char(c: char, ctrl: bool, shift: bool, alt: bool) -> Option<Action> {
 
     match lower {
         // Ctrl+<key> shortcuts
+        'n' if ctrl && alt => Some(Action::NewFromTemplate),
+        'n' if ctrl && shift => Some(Action::NewNoteInFolder),
         'n' if ctrl => Some(Action::NewTab),
         'w' if ctrl => Some(Action::CloseTab),
+        't' if ctrl && shift => Some(Action::ReopenClosedTab),
+        's' if ctrl && shift => Some(Action::SaveAs),
         's' if ctrl => Some(Action::Save),
+        'o' if ctrl && shift => Some(Action::OpenOutline),
         'o' if ctrl => Some(Action::OpenFile),
         'p' if ctrl => Some(Action::OpenNotesPicker),
+        'g' if ctrl => Some(Action::GoToLine),
+        'i' if ctrl && shift => Some(Action::OpenStatistics),
+        '.' if ctrl => Some(Action::SpellingSuggestions),
         'r' if ctrl => Some(Action::RenameTab),
+        'j' if ctrl && shift => Some(Action::OpenJournalCalendar),
+        'j' if ctrl => Some(Action::OpenTodayNote),
+        'd' if ctrl && shift => Some(Action::OpenTaskList),
+        'b' if ctrl && shift => Some(Action::ToggleBacklinks),
+        '[' | '{' if ctrl && shift => Some(Action::ToggleFold),
+        '[' if ctrl && alt => Some(Action::FoldAll),
+        ']' if ctrl && alt => Some(Action::UnfoldAll),
+        'b' if ctrl => Some(Action::ToggleSidebar),
         'a' if ctrl => Some(Action::SelectAll),
         'c' if ctrl => Some(Action::Copy),
         'x' if ctrl => Some(Action::Cut),
@@ -171,6 +220,11 @@ fn resolve_char(c: char, ctrl: bool, shift: bool, alt: bool) -> Option<Action> {
         'z' if ctrl => Some(Action::Undo),
         'y' if ctrl => Some(Action::Redo),
 
+        // Zoom (Ctrl+= / Ctrl++ / Ctrl+- / Ctrl+0)
+        '=' | '+' if ctrl => Some(Action::ZoomIn),
+        '-' | '_' if ctrl => Some(Action::ZoomOut),
+        '0' if ctrl => Some(Action::ZoomReset),
+
         // Alt+<key> shortcuts
         'z' if alt => Some(Action::ToggleWordWrap),
 
@@ -204,9 +258,161 @@ mod tests {
         assert_eq!(resolve(&event), Some(Action::InsertChar('a')));
     }
 
+    #[test]
+    fn test_zoom_shortcuts() {
+        let zoom_in = KeyEvent::new(Key::Char('='), Modifiers::ctrl());
+        assert_eq!(resolve(&zoom_in), Some(Action::ZoomIn));
+        let zoom_in_shifted = KeyEvent::new(Key::Char('+'), Modifiers::ctrl_shift());
+        assert_eq!(resolve(&zoom_in_shifted), Some(Action::ZoomIn));
+        let zoom_out = KeyEvent::new(Key::Char('-'), Modifiers::ctrl());
+        assert_eq!(resolve(&zoom_out), Some(Action::ZoomOut));
+        let reset = KeyEvent::new(Key::Char('0'), Modifiers::ctrl());
+        assert_eq!(resolve(&reset), Some(Action::ZoomReset));
+    }
+
+    #[test]
+    fn test_reopen_closed_tab() {
+        let event = KeyEvent::new(Key::Char('T'), Modifiers::ctrl_shift());
+        assert_eq!(resolve(&event), Some(Action::ReopenClosedTab));
+    }
+
+    #[test]
+    fn test_save_as() {
+        let event = KeyEvent::new(Key::Char('S'), Modifiers::ctrl_shift());
+        assert_eq!(resolve(&event), Some(Action::SaveAs));
+    }
+
+    #[test]
+    fn test_notebook_shortcuts() {
+        let event = KeyEvent::new(Key::Char('N'), Modifiers::ctrl_shift());
+        assert_eq!(resolve(&event), Some(Action::NewNoteInFolder));
+        let event = KeyEvent::new(
+            Key::Char('n'),
+            Modifiers { ctrl: true, shift: false, alt: true },
+        );
+        assert_eq!(resolve(&event), Some(Action::NewFromTemplate));
+        let event = KeyEvent::new(Key::Char('b'), Modifiers::ctrl());
+        assert_eq!(resolve(&event), Some(Action::ToggleSidebar));
+        let event = KeyEvent::new(Key::Char('B'), Modifiers::ctrl_shift());
+        assert_eq!(resolve(&event), Some(Action::ToggleBacklinks));
+    }
+
+    #[test]
+    fn test_journal_shortcuts() {
+        let event = KeyEvent::new(Key::Char('j'), Modifiers::ctrl());
+        assert_eq!(resolve(&event), Some(Action::OpenTodayNote));
+        let event = KeyEvent::new(Key::Char('J'), Modifiers::ctrl_shift());
+        assert_eq!(resolve(&event), Some(Action::OpenJournalCalendar));
+        let event = KeyEvent::new(Key::PageUp, Modifiers::alt());
+        assert_eq!(resolve(&event), Some(Action::JournalPreviousDay));
+        let event = KeyEvent::new(Key::PageDown, Modifiers::alt());
+        assert_eq!(resolve(&event), Some(Action::JournalNextDay));
+    }
+
+    #[test]
+    fn test_tab_keys() {
+        let event = KeyEvent::new(Key::Tab, Modifiers::none());
+        assert_eq!(resolve(&event), Some(Action::InsertTab));
+        let event = KeyEvent::new(Key::Tab, Modifiers::shift());
+        assert_eq!(resolve(&event), Some(Action::PreviousTabStop));
+        let event = KeyEvent::new(Key::Tab, Modifiers::ctrl());
+        assert_eq!(resolve(&event), Some(Action::NextTab));
+    }
+
+    #[test]
+    fn test_task_shortcuts() {
+        let event = KeyEvent::new(Key::Enter, Modifiers::ctrl());
+        assert_eq!(resolve(&event), Some(Action::ToggleTask));
+        let event = KeyEvent::new(Key::Enter, Modifiers::none());
+        assert_eq!(resolve(&event), Some(Action::Confirm));
+        let event = KeyEvent::new(Key::Char('D'), Modifiers::ctrl_shift());
+        assert_eq!(resolve(&event), Some(Action::OpenTaskList));
+    }
+
+    #[test]
+    fn test_table_shortcuts() {
+        let ctrl_alt = Modifiers {
+            ctrl: true,
+            shift: false,
+            alt: true,
+        };
+        let event = KeyEvent::new(Key::ArrowDown, ctrl_alt);
+        assert_eq!(resolve(&event), Some(Action::TableInsertRow));
+        let event = KeyEvent::new(Key::ArrowLeft, ctrl_alt);
+        assert_eq!(resolve(&event), Some(Action::TableDeleteColumn));
+        let event = KeyEvent::new(Key::ArrowDown, Modifiers::alt());
+        assert_eq!(resolve(&event), Some(Action::MoveLinesDown));
+    }
+
+    #[test]
+    fn test_table_shortcuts_outside_a_table() {
+        let ctrl_alt = Modifiers {
+            ctrl: true,
+            shift: false,
+            alt: true,
+        };
+        let event = KeyEvent::new(Key::ArrowLeft, ctrl_alt);
+        assert_eq!(
+            resolve_outside_table(&event),
+            Some(Action::CursorWordLeft { selecting: false })
+        );
+        let event = KeyEvent::new(Key::ArrowRight, Modifiers { shift: true, ..ctrl_alt });
+        assert_eq!(
+            resolve_outside_table(&event),
+            Some(Action::CursorWordRight { selecting: true })
+        );
+    }
+
+    #[test]
+    fn test_outline_shortcuts() {
+        let event = KeyEvent::new(Key::Char('O'), Modifiers::ctrl_shift());
+        assert_eq!(resolve(&event), Some(Action::OpenOutline));
+        let event = KeyEvent::new(Key::Char('o'), Modifiers::ctrl());
+        assert_eq!(resolve(&event), Some(Action::OpenFile));
+        let event = KeyEvent::new(Key::ArrowDown, Modifiers::ctrl());
+        assert_eq!(resolve(&event), Some(Action::NextHeading));
+        let event = KeyEvent::new(Key::Char('{'), Modifiers::ctrl_shift());
+        assert_eq!(resolve(&event), Some(Action::ToggleFold));
+        let ctrl_alt = Modifiers {
+            ctrl: true,
+            shift: false,
+            alt: true,
+        };
+        let event = KeyEvent::new(Key::Char(']'), ctrl_alt);
+        assert_eq!(resolve(&event), Some(Action::UnfoldAll));
+    }
+
+    #[test]
+    fn test_go_to_line_shortcut() {
+        let event = KeyEvent::new(Key::Char('g'), Modifiers::ctrl());
+        assert_eq!(resolve(&event), Some(Action::GoToLine));
+    }
+
+    #[test]
+    fn test_statistics_shortcut() {
+        let event = KeyEvent::new(Key::Char('I'), Modifiers::ctrl_shift());
+        assert_eq!(resolve(&event), Some(Action::OpenStatistics));
+    }
+
+    #[test]
+    fn test_spelling_shortcuts() {
+        let event = KeyEvent::new(Key::F7, Modifiers::none());
+        assert_eq!(resolve(&event), Some(Action::ToggleSpellCheck));
+        let event = KeyEvent::new(Key::Char('.'), Modifiers::ctrl());
+        assert_eq!(resolve(&event), Some(Action::SpellingSuggestions));
+    }
+
     #[test]
     fn test_shift_arrow() {
         let event = KeyEvent::new(Key::ArrowLeft, Modifiers::shift());
         assert_eq!(resolve(&event), Some(Action::CursorLeft { selecting: true }));
     }
+
+    #[test]
+    fn test_alt_arrow_sub_word() {
+        let event = KeyEvent::new(Key::ArrowRight, Modifiers::alt());
+        assert_eq!(resolve(&event), Some(Action::CursorSubWordRight { selecting: false }));
+        let event = KeyEvent::new(Key::ArrowUp, Modifiers::alt());
+        assert_eq!(resolve(&event), Some(Action::MoveLinesUp));
+    }
 }
@@ -0,0 +1,271 @@
+//! Wiki links: following `[[links]]`, the backlinks panel and keeping links
+//! pointed at renamed notes
+
+use std::path::{Path, PathBuf};
+
+use native_dialog::{MessageDialog, MessageType};
+
+use crate::config::layout;
+use crate::links;
+use crate::persistence;
+use crate::ui::{backlinks_list_top, backlinks_rect, BacklinksPanel, Rect, UiNode};
+
+use super::focus::Focus;
+use super::state::AppResult;
+use super::ui_state::MouseInteraction;
+use super::App;
+
+impl App {
+    pub fn toggle_backlinks(&mut self) -> AppResult {
+        self.backlinks = match self.backlinks.take() {
+            Some(_) => None,
+            None => {
+                let note = self.tabs[self.active_tab].path().cloned();
+                let entries = backlink_entries(note.as_ref());
+                Some(BacklinksPanel::new(note, entries))
+            }
+        };
+        self.auto_scroll();
+        AppResult::Redraw
+    }
+
+    /// Re-read the backlinks of the active note
+    pub(super) fn refresh_backlinks(&mut self) {
+        let note = self.tabs[self.active_tab].path().cloned();
+        if let Some(panel) = &mut self.backlinks {
+            let entries = backlink_entries(note.as_ref());
+            panel.refresh(note, entries);
+        }
+    }
+
+    /// Follow the active tab: refresh the panel when it shows another note.
+    /// Returns whether it was refreshed.
+    pub(super) fn sync_backlinks(&mut self) -> bool {
+        let note = self.tabs[self.active_tab].path();
+        let stale = self
+            .backlinks
+            .as_ref()
+            .is_some_and(|panel| panel.note() != note.map(|path| path.as_path()));
+        if stale {
+            self.refresh_backlinks();
+        }
+        stale
+    }
+
+    pub(crate) fn backlinks_rect(&self) -> Option<Rect> {
+        self.backlinks
+            .as_ref()
+            .map(|_| backlinks_rect(self.width, self.height, self.scale))
+    }
+
+    /// Right edge of the editor area; the backlinks panel pulls it left
+    pub(crate) fn content_right(&self) -> f32 {
+        self.backlinks_rect().map_or(self.width, |rect| rect.x)
+    }
+
+    fn backlinks_row_height(&self) -> f32 {
+        layout::SIDEBAR_ROW_HEIGHT * self.scale
+    }
+
+    fn backlinks_row_at(&self, x: f32, y: f32) -> Option<usize> {
+        let rect = self.backlinks_rect()?;
+        if !rect.contains(x, y) {
+            return None;
+        }
+        let list_top = backlinks_list_top(rect, self.scale);
+        self.backlinks
+            .as_ref()?
+            .row_at(y - list_top, self.backlinks_row_height())
+    }
+
+    /// Update the hovered backlink; returns true when it changed
+    pub(super) fn hover_backlinks(&mut self, x: f32, y: f32) -> bool {
+        let row = self.backlinks_row_at(x, y);
+        self.backlinks
+            .as_mut()
+            .is_some_and(|panel| panel.set_hovered(row))
+    }
+
+    /// Clicking a backlink opens the linking note
+    pub(super) fn handle_backlinks_click(&mut self, x: f32, y: f32) -> AppResult {
+        let Some(index) = self.backlinks_row_at(x, y) else {
+            return AppResult::Ok;
+        };
+        let path = self
+            .backlinks
+            .as_ref()
+            .and_then(|panel| panel.entries().get(index))
+            .map(|(path, _)| path.clone());
+        match path {
+            Some(path) => self.open_note_by_path(path),
+            None => AppResult::Ok,
+        }
+    }
+
+    /// Scroll the backlinks panel when the pointer is over it. Returns None
+    /// when the scroll belongs to the editor instead.
+    pub(super) fn scroll_backlinks(&mut self, delta: isize) -> Option<AppResult> {
+        let rect = self.backlinks_rect()?;
+        if !rect.contains(self.ui_state.last_mouse_x, self.ui_state.last_mouse_y) {
+            return None;
+        }
+        let list_height = rect.y + rect.height - backlinks_list_top(rect, self.scale);
+        let visible_rows = (list_height / self.backlinks_row_height()).floor().max(1.0) as usize;
+        let panel = self.backlinks.as_mut()?;
+        Some(if panel.scroll_by(delta, visible_rows) {
+            AppResult::Redraw
+        } else {
+            AppResult::Ok
+        })
+    }
+
+    /// Target of the `[[link]]` the editor cursor is inside
+    pub(super) fn link_at_cursor(&self) -> Option<String> {
+        if !matches!(self.focus, Focus::Editor) {
+            return None;
+        }
+        let tab = &self.tabs[self.active_tab];
+        links::link_at(tab.line_text(tab.cursor_line()), tab.cursor_col())
+    }
+
+    /// Ctrl+click: place the cursor, then follow the link under it
+    pub fn ctrl_click_at(&mut self, x: f32, y: f32) -> AppResult {
+        let on_text = matches!(self.ui_tree().hit_test(x, y), UiNode::TextArea);
+        let result = self.click_at(x, y, false);
+        if !on_text {
+            return result;
+        }
+        match self.link_at_cursor() {
+            Some(target) => {
+                self.ui_state.mouse_interaction = MouseInteraction::None;
+                self.open_wiki_link(&target)
+            }
+            None => result,
+        }
+    }
+
+    /// Open the note a link points at, creating it when it does not exist
+    pub fn open_wiki_link(&mut self, target: &str) -> AppResult {
+        let resolved = persistence::NoteIndex::load()
+            .resolve(target)
+            .map(|path| path.to_path_buf());
+        let path = match resolved {
+            Some(path) => path,
+            None => match persistence::create_note(target) {
+                Ok(path) => {
+                    self.refresh_panels();
+                    path
+                }
+                Err(_) => return AppResult::Ok,
+            },
+        };
+        self.open_note_by_path(path)
+    }
+
+    /// After a note was renamed, offer to point the links to its old title
+    /// at the new one. When its file was renamed too (`moved`), links by the
+    /// old file name follow the new file name.
+    pub(super) fn offer_link_update(
+        &mut self,
+        old_title: &str,
+        new_title: &str,
+        moved: Option<(&Path, &Path)>,
+    ) {
+        let stem = |path: &Path| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+        };
+        let mut renames = vec![(old_title.to_string(), new_title.to_string())];
+        if let Some((Some(old_stem), Some(new_stem))) =
+            moved.map(|(old, new)| (stem(old), stem(new)))
+        {
+            renames.push((old_stem, new_stem));
+        }
+        renames.retain(|(from, to)| {
+            let from = links::normalize(from);
+            !from.is_empty() && from != links::normalize(to)
+        });
+        if renames.is_empty() {
+            return;
+        }
+        let targets: Vec<String> = renames
+            .iter()
+            .map(|(from, _)| links::normalize(from))
+            .collect();
+
+        let open_paths: Vec<&PathBuf> = self.tabs.iter().filter_map(|tab| tab.path()).collect();
+        let index = persistence::NoteIndex::load();
+        let on_disk = index
+            .linking_to(Some(old_title), moved.map(|(old, _)| old))
+            .into_iter()
+            .filter(|path| !open_paths.iter().any(|open| open.as_path() == *path))
+            .count();
+        let in_tabs = self
+            .tabs
+            .iter()
+            .filter(|tab| {
+                links::note_links(tab.content())
+                    .iter()
+                    .any(|link| targets.contains(link))
+            })
+            .count();
+        let count = on_disk + in_tabs;
+        if count > 0 && confirm_update_links_dialog(old_title, new_title, count) {
+            for (from, to) in &renames {
+                self.retarget_links(from, to);
+            }
+        }
+    }
+
+    /// Point every `[[from]]` link at `to`. Open tabs are changed in their
+    /// buffers (undoable); other notes are rewritten on disk.
+    pub fn retarget_links(&mut self, from: &str, to: &str) {
+        for tab in &mut self.tabs {
+            if tab.retarget_links(from, to) {
+                tab.auto_save();
+            }
+        }
+        let open_paths: Vec<&PathBuf> = self.tabs.iter().filter_map(|tab| tab.path()).collect();
+        let _ = persistence::retarget_links_in_notes(from, to, &open_paths);
+        self.refresh_backlinks();
+    }
+}
+
+/// Notes linking to `note` with their display titles
+fn backlink_entries(note: Option<&PathBuf>) -> Vec<(PathBuf, String)> {
+    let Some(note) = note else {
+        return Vec::new();
+    };
+    let index = persistence::NoteIndex::load();
+    index
+        .backlinks(note)
+        .into_iter()
+        .map(|path| {
+            let title = index.title_of(path).map_or_else(
+                || {
+                    path.file_stem()
+                        .map(|stem| stem.to_string_lossy().to_string())
+                        .unwrap_or_default()
+                },
+                str::to_string,
+            );
+            (path.to_path_buf(), title)
+        })
+        .collect()
+}
+
+fn confirm_update_links_dialog(old_title: &str, new_title: &str, count: usize) -> bool {
+    let notes = if count == 1 {
+        "1 note links".to_string()
+    } else {
+        format!("{count} notes link")
+    };
+    let text =
+        format!("{notes} to \"{old_title}\".\n\nUpdate the links to point to \"{new_title}\"?");
+    MessageDialog::new()
+        .set_type(MessageType::Info)
+        .set_title("Update links")
+        .set_text(&text)
+        .show_confirm()
+        .unwrap_or(false)
+}
@@ -6,42 +6,66 @@
 //! - `App` - coordinates between components, owns tabs and renderer
 
 mod action;
+mod context_menu;
 mod file;
 mod focus;
 mod input;
 mod input_handler;
+mod journal;
 mod keybindings;
+mod links;
 mod mouse;
 mod notes_picker;
+mod outline;
 mod scroll;
 mod scroll_state;
+mod sidebar;
+mod snippets;
+mod spelling;
 mod state;
+mod statistics;
+mod tables;
 mod tabs;
+mod tasks;
+mod templates;
 mod ui_state;
 
 use arboard::Clipboard;
 
-use crate::config::{self, layout, timing};
+use crate::config::{self, layout, timing, zoom};
 use crate::persistence;
-use crate::renderer::Renderer;
+use crate::renderer::{RenderState, Renderer};
+use crate::spelling::SpellChecker;
 use crate::tab::Tab;
+use crate::ui::{BacklinksPanel, NotebookTree, UiTree};
 
-pub use focus::{Focus, NoteEntry};
-pub use keybindings::{Key, KeyEvent, Modifiers, resolve as resolve_keybinding};
+use spelling::Misspelling;
+use tabs::ClosedTabs;
+
+pub use action::Action;
+pub use focus::{Focus, NotesPickerView, PickerItem};
+pub use keybindings::{Key, KeyEvent, Modifiers};
 pub use scroll_state::{ScrollDirection, ScrollInput, ScrollState};
 pub use state::AppResult;
-pub use ui_state::{MouseInteraction, UiState};
+pub use ui_state::{ImePreedit, MouseInteraction, UiState};
 
 pub struct App {
     // Core components
     renderer: Renderer,
     tabs: Vec<Tab>,
     active_tab: usize,
+    closed_tabs: ClosedTabs,
+    sidebar: Option<NotebookTree>, // None while the sidebar is hidden
+    backlinks: Option<BacklinksPanel>, // None while the panel is hidden
+    spell_check: bool,
+    spell_checker: Option<SpellChecker>, // None while off or without a dictionary
+    misspelling: Option<Misspelling>, // Word the spelling menu was opened for
 
     // Window state
     width: f32,
     height: f32,
     scale: f32,
+    zoom: f32,
 
     // Input/clipboard
     clipboard: Option<Clipboard>,
@@ -62,7 +86,13 @@ impl App {
         let renderer = Renderer::new(gl_renderer, width, height, scale);
         let clipboard = Clipboard::new().ok();
 
-        let (mut tabs, active_tab) = if let Some(session) = persistence::load_session_state() {
+        // Upgrade notes from older versions before the session refers to them
+        let _ = persistence::migrate_note_metadata();
+        let _ = persistence::migrate_timestamp_note_names();
+
+        let session = persistence::load_session_state();
+        let spell_check = session.as_ref().is_some_and(|session| session.spell_check);
+        let (mut tabs, active_tab) = if let Some(session) = session {
             let mut loaded_tabs = Vec::new();
             let mut active_index = None;
 
@@ -84,9 +114,11 @@ impl App {
             let active_tab = active_index.unwrap_or(0);
             (loaded_tabs, active_tab)
         } else {
-            let tabs = match persistence::list_notes() {
+            // Notes in folders are left to the sidebar
+            let tabs = match persistence::list_top_level_notes() {
                 Ok(note_paths) if !note_paths.is_empty() => note_paths
                     .into_iter()
+                    .filter(|path| !persistence::is_template(path))
                     .filter_map(|path| Tab::from_file(path))
                     .collect(),
                 _ => vec![Tab::new_untitled()],
@@ -102,9 +134,16 @@ impl App {
             renderer,
             tabs,
             active_tab,
+            closed_tabs: ClosedTabs::default(),
+            sidebar: None,
+            backlinks: None,
+            spell_check,
+            spell_checker: if spell_check { spelling::load_spell_checker() } else { None },
+            misspelling: None,
             width,
             height,
             scale,
+            zoom: zoom::DEFAULT,
             clipboard,
             focus: Focus::default(),
             ui_state: UiState::new(),
@@ -129,6 +168,19 @@ impl App {
             needs_redraw = true;
         }
 
+        // Rename untitled notes after their heading once typing has paused
+        let now = std::time::Instant::now();
+        let moves: Vec<_> = self.tabs.iter_mut().filter_map(|tab| tab.rename_if_due(now)).collect();
+        for (old, new) in moves {
+            let _ = self.file_moved(&old, &new);
+            needs_redraw = true;
+        }
+
+        // The backlinks panel follows tab switches here, off the render path
+        if self.sync_backlinks() {
+            needs_redraw = true;
+        }
+
         // Continuously redraw when flame particles are active
         if self.renderer.has_active_flames() {
             needs_redraw = true;
@@ -151,7 +203,7 @@ impl App {
     pub fn render(&mut self) {
         let renaming_tab_index = self.focus.renaming_tab_index();
         let rename_input = self.focus.rename_input();
-        let notes_picker_state = self.focus.notes_picker_state();
+        let notes_picker = self.focus.notes_picker_view();
 
         let tab_info: Vec<(&str, bool)> = self
             .tabs
@@ -169,35 +221,61 @@ impl App {
                 }
             })
             .collect();
+        let modified_tabs: Vec<bool> = self.tabs.iter().map(|t| t.is_modified()).collect();
 
         let current_tab = &self.tabs[self.active_tab];
+        let ime_preedit = if matches!(self.focus, Focus::Editor) {
+            self.ui_state.ime_preedit.as_ref()
+        } else {
+            None
+        };
 
-        self.renderer.render(
-            &tab_info,
+        self.renderer.render(&RenderState {
+            tabs: &tab_info,
+            modified_tabs: &modified_tabs,
             current_tab,
-            self.ui_state.cursor_visible,
-            self.ui_state.hovered_tab_index,
-            self.ui_state.hovered_plus,
-            self.ui_state.hovered_scrollbar,
-            matches!(self.ui_state.mouse_interaction, MouseInteraction::ScrollbarDrag { .. }),
-            renaming_tab_index,
+            cursor_visible: self.ui_state.cursor_visible,
+            hovered_tab_index: self.ui_state.hovered_tab_index,
+            hovered_tab_close: self.ui_state.hovered_tab_close,
+            hovered_plus: self.ui_state.hovered_plus,
+            hovered_scrollbar: self.ui_state.hovered_scrollbar,
+            dragging_scrollbar: matches!(
+                self.ui_state.mouse_interaction,
+                MouseInteraction::ScrollbarDrag { .. }
+            ),
+            renaming_tab: renaming_tab_index,
             rename_input,
-            &self.ui_state.typing_flame_positions,
-            self.ui_state.hovered_window_minimize,
-            self.ui_state.hovered_window_maximize,
-            self.ui_state.hovered_window_close,
-            notes_picker_state,
-        );
+            typing_flame_positions: &self.ui_state.typing_flame_positions,
+            hovered_window_minimize: self.ui_state.hovered_window_minimize,
+            hovered_window_maximize: self.ui_state.hovered_window_maximize,
+            hovered_window_close: self.ui_state.hovered_window_close,
+            notes_picker,
+            ime_preedit,
+            context_menu: self.focus.context_menu(),
+            sidebar: self.sidebar.as_ref(),
+            backlinks: self.backlinks.as_ref(),
+            journal_calendar: self.focus.journal_calendar(),
+            statistics: self.focus.statistics(),
+            spell_checker: self.spell_checker.as_ref(),
+            no_dictionary: self.spell_check && self.spell_checker.is_none(),
+        });
     }
 
     // =========================================================================
     // Layout helpers
     // =========================================================================
 
+    /// Height of one editor line in physical pixels, including zoom
+    pub(crate) fn line_height(&self) -> f32 {
+        layout::LINE_HEIGHT * self.scale * self.zoom
+    }
+
     pub(crate) fn visible_lines(&self) -> usize {
-        let content_height =
-            self.height - layout::TAB_HEIGHT * self.scale - layout::PADDING * 2.0 * self.scale;
-        (content_height / (layout::LINE_HEIGHT * self.scale))
+        let content_height = self.height
+            - layout::TAB_HEIGHT * self.scale
+            - layout::PADDING * 2.0 * self.scale
+            - layout::STATUS_BAR_HEIGHT * self.scale;
+        (content_height / self.line_height())
             .floor()
             .max(1.0) as usize
     }
@@ -208,12 +286,37 @@ impl App {
 
     pub(crate) fn auto_scroll(&mut self) {
         let visible = self.visible_lines();
-        let visible_width = self.width - layout::PADDING * 2.0 * self.scale;
+        let visible_width =
+            self.content_right() - self.content_left() - layout::PADDING * 2.0 * self.scale;
         let char_width = self.renderer.get_char_width();
-        self.tabs[self.active_tab].ensure_cursor_visible(visible, visible_width, char_width);
+        let tab = &self.tabs[self.active_tab];
+        let cursor_x = self
+            .renderer
+            .line_layout(tab.line_text(tab.cursor_line()))
+            .x_of(tab.cursor_col());
+        self.tabs[self.active_tab].ensure_cursor_visible(visible, visible_width, cursor_x, char_width);
         self.ui_state.reset_cursor_blink();
     }
 
+    /// Caret rectangle (x, y, width, height) in physical pixels, used to
+    /// place the IME candidate window next to the text being composed
+    pub fn ime_cursor_area(&mut self) -> (f32, f32, f32, f32) {
+        let tab = &self.tabs[self.active_tab];
+        let (x, y) = self.text_position(tab.cursor_line(), tab.cursor_col());
+        (x, y, self.renderer.get_char_width(), self.line_height())
+    }
+
+    /// Top-left corner of `col` on `line` in physical pixels
+    pub(crate) fn text_position(&mut self, line: usize, col: usize) -> (f32, f32) {
+        let start_y = self.content_start_y();
+        let padding = self.content_left() + layout::PADDING * self.scale;
+        let tab = &self.tabs[self.active_tab];
+        let visual_line = tab.visual_row_of(line);
+        let x_in_line = self.renderer.line_layout(tab.line_text(line)).x_of(col);
+        let x = padding - tab.scroll_offset_x() + x_in_line;
+        (x, start_y + visual_line as f32 * self.line_height())
+    }
+
     pub(crate) fn tab_titles(&self) -> Vec<(&str, bool)> {
         self.tabs
             .iter()
@@ -222,6 +325,21 @@ impl App {
             .collect()
     }
 
+    /// Hit-testing tree for the current window, tabs, side panels and open
+    /// context menu
+    pub(crate) fn ui_tree(&self) -> UiTree {
+        UiTree::new(
+            self.width,
+            self.height,
+            self.scale,
+            self.ui_state.tab_scroll_x,
+            &self.tab_titles(),
+        )
+        .with_sidebar(self.sidebar_rect())
+        .with_backlinks(self.backlinks_rect())
+        .with_context_menu(self.focus.context_menu().map(|menu| menu.geometry()))
+    }
+
     pub fn hovered_resize_edge(&self) -> Option<crate::ui::ResizeEdge> {
         self.ui_state.hovered_resize_edge
     }
@@ -237,6 +355,17 @@ impl App {
             return AppResult::Ok;
         };
 
+        let delta = match direction {
+            ScrollDirection::Up => -(lines as isize),
+            ScrollDirection::Down => lines as isize,
+        };
+        if let Some(result) = self.scroll_sidebar(delta) {
+            return result;
+        }
+        if let Some(result) = self.scroll_backlinks(delta) {
+            return result;
+        }
+
         match direction {
             ScrollDirection::Up => {
                 for _ in 0..lines {
@@ -295,7 +424,11 @@ impl App {
             .iter()
             .filter_map(|tab| tab.export_state())
             .collect();
-        persistence::SessionState { active_path, tabs }
+        persistence::SessionState {
+            active_path,
+            tabs,
+            spell_check: self.spell_check,
+        }
     }
 }
 
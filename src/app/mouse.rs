@@ -3,8 +3,9 @@
 use std::time::{Duration, Instant};
 
 use crate::config::{layout, timing};
-use crate::ui::{UiAction, UiDragAction, UiNode, UiTree};
+use crate::ui::{UiAction, UiDragAction, UiNode};
 
+use super::focus::Focus;
 use super::state::AppResult;
 use super::ui_state::MouseInteraction;
 use super::App;
@@ -14,14 +15,17 @@ impl App {
         self.ui_state.last_mouse_x = x;
         self.ui_state.last_mouse_y = y;
 
-        let tab_info: Vec<(&str, bool)> = self
-            .tabs
-            .iter()
-            .enumerate()
-            .map(|(i, t)| (t.title(), i == self.active_tab))
-            .collect();
+        if self.focus.is_context_menu() {
+            let hovered = self.ui_tree().hover(x, y, 0, 0, 0).context_menu_item;
+            let changed = self
+                .focus
+                .context_menu_mut()
+                .is_some_and(|menu| menu.set_hovered(hovered));
+            return if changed { AppResult::Redraw } else { AppResult::Ok };
+        }
 
         let prev_hovered_tab_index = self.ui_state.hovered_tab_index;
+        let prev_hovered_tab_close = self.ui_state.hovered_tab_close;
         let prev_hovered_plus = self.ui_state.hovered_plus;
         let prev_hovered_scrollbar = self.ui_state.hovered_scrollbar;
         let prev_hovered_minimize = self.ui_state.hovered_window_minimize;
@@ -29,26 +33,26 @@ impl App {
         let prev_hovered_close = self.ui_state.hovered_window_close;
         let prev_hovered_resize_edge = self.ui_state.hovered_resize_edge;
 
-        let total_lines = self.tabs[self.active_tab].total_lines();
+        let total_lines = self.tabs[self.active_tab].total_rows();
         let visible_lines = self.visible_lines();
-        let scroll_offset = self.tabs[self.active_tab].scroll_offset();
-        let ui_tree = UiTree::new(
-            self.width,
-            self.height,
-            self.scale,
-            self.ui_state.tab_scroll_x,
-            &tab_info,
-        );
+        let scroll_offset = self.tabs[self.active_tab].scroll_row();
+        let ui_tree = self.ui_tree();
         let hover = ui_tree.hover(x, y, total_lines, visible_lines, scroll_offset);
         self.ui_state.hovered_tab_index = hover.tab_index;
+        self.ui_state.hovered_tab_close = hover.tab_close;
         self.ui_state.hovered_plus = hover.plus;
         self.ui_state.hovered_scrollbar = hover.scrollbar;
         self.ui_state.hovered_window_minimize = hover.window_minimize;
         self.ui_state.hovered_window_maximize = hover.window_maximize;
         self.ui_state.hovered_window_close = hover.window_close;
         self.ui_state.hovered_resize_edge = hover.resize_edge;
+        let sidebar_changed = self.hover_sidebar(x, y);
+        let backlinks_changed = self.hover_backlinks(x, y);
 
-        if prev_hovered_tab_index != self.ui_state.hovered_tab_index
+        if sidebar_changed
+            || backlinks_changed
+            || prev_hovered_tab_index != self.ui_state.hovered_tab_index
+            || prev_hovered_tab_close != self.ui_state.hovered_tab_close
             || prev_hovered_plus != self.ui_state.hovered_plus
             || prev_hovered_scrollbar != self.ui_state.hovered_scrollbar
             || prev_hovered_minimize != self.ui_state.hovered_window_minimize
@@ -67,23 +71,21 @@ impl App {
         if self.focus.is_notes_picker() {
             return self.handle_notes_picker_click(x, y);
         }
+        if self.focus.is_context_menu() {
+            return self.handle_context_menu_click(x, y);
+        }
+        if self.focus.is_journal_calendar() {
+            return self.handle_journal_calendar_click(x, y);
+        }
+        // Any click dismisses the statistics overlay
+        if self.focus.statistics().is_some() {
+            return self.close_statistics();
+        }
 
-        let tab_info: Vec<(&str, bool)> = self
-            .tabs
-            .iter()
-            .enumerate()
-            .map(|(i, t)| (t.title(), i == self.active_tab))
-            .collect();
-        let total_lines = self.tabs[self.active_tab].total_lines();
+        let total_lines = self.tabs[self.active_tab].total_rows();
         let visible_lines = self.visible_lines();
-        let scroll_offset = self.tabs[self.active_tab].scroll_offset();
-        let ui_tree = UiTree::new(
-            self.width,
-            self.height,
-            self.scale,
-            self.ui_state.tab_scroll_x,
-            &tab_info,
-        );
+        let scroll_offset = self.tabs[self.active_tab].scroll_row();
+        let ui_tree = self.ui_tree();
 
         match ui_tree.click(x, y, total_lines, visible_lines, scroll_offset, selecting) {
             UiAction::ActivateTab(i) => {
@@ -92,6 +94,10 @@ impl App {
                 self.ui_state.mouse_interaction = MouseInteraction::TabDrag { tab_index: i };
                 return AppResult::Redraw;
             }
+            UiAction::CloseTab(i) => {
+                self.ui_state.mouse_interaction = MouseInteraction::None;
+                return self.close_tab_at(i);
+            }
             UiAction::NewTab => {
                 return self.new_tab();
             }
@@ -123,6 +129,14 @@ impl App {
             UiAction::None => {
                 return AppResult::Ok;
             }
+            UiAction::SidebarClick => {
+                self.ui_state.mouse_interaction = MouseInteraction::None;
+                return self.handle_sidebar_click(x, y);
+            }
+            UiAction::BacklinksClick => {
+                self.ui_state.mouse_interaction = MouseInteraction::None;
+                return self.handle_backlinks_click(x, y);
+            }
             UiAction::TextClick => {
                 self.ui_state.mouse_interaction = MouseInteraction::TextSelection;
             }
@@ -138,7 +152,7 @@ impl App {
         let height = self.visible_lines() as isize;
         let relative_y = y - content_start_y;
         let mut clicked_visual_line =
-            (relative_y / (layout::LINE_HEIGHT * self.scale)).floor() as isize;
+            (relative_y / self.line_height()).floor() as isize;
 
         if selecting {
             if clicked_visual_line < 0 || clicked_visual_line >= height {
@@ -157,19 +171,25 @@ impl App {
             }
         }
 
-        let scroll_offset = self.tabs[self.active_tab].scroll_offset();
-        let clicked_line = (scroll_offset as isize + clicked_visual_line).max(0) as usize;
+        let clicked_line = self.tabs[self.active_tab].line_at_visual_row(clicked_visual_line);
 
-        let char_width = self.renderer.get_char_width();
-        let scroll_offset_x = self.tabs[self.active_tab].scroll_offset_x();
-        let relative_x = (x - layout::PADDING * self.scale + scroll_offset_x).max(0.0);
-        let clicked_visual_col = (relative_x / char_width).round() as usize;
-        
-        // Convert visual column to character column (accounting for tab width)
-        let clicked_col = self.tabs[self.active_tab].visual_col_to_char_col(clicked_line, clicked_visual_col);
+        // Clicking a fold marker in the left padding folds or unfolds there
+        let in_gutter = x < self.content_left() + layout::PADDING * self.scale;
+        if !selecting && in_gutter && self.tabs[self.active_tab].toggle_fold_at(clicked_line) {
+            self.ui_state.mouse_interaction = MouseInteraction::None;
+            return AppResult::Redraw;
+        }
+
+        let clicked_col = self.char_col_at_x(clicked_line, x);
 
         self.tabs[self.active_tab].set_cursor_position(clicked_line, clicked_col, selecting);
 
+        // Clicking a task box checks or unchecks it
+        if !selecting && self.tabs[self.active_tab].is_on_task_box(clicked_line, clicked_col) {
+            self.tabs[self.active_tab].toggle_task(clicked_line);
+            self.auto_save_active();
+        }
+
         if selecting {
             self.auto_scroll();
         }
@@ -179,22 +199,17 @@ impl App {
     }
 
     pub fn handle_double_click(&mut self, x: f32, y: f32) -> AppResult {
-        let tab_info: Vec<(&str, bool)> = self
-            .tabs
-            .iter()
-            .enumerate()
-            .map(|(i, t)| (t.title(), i == self.active_tab))
-            .collect();
-        let total_lines = self.tabs[self.active_tab].total_lines();
+        if self.focus.is_context_menu() {
+            return self.handle_context_menu_click(x, y);
+        }
+        // The first click selected the day, the second opens it
+        if self.focus.is_journal_calendar() {
+            return self.handle_journal_calendar_click(x, y);
+        }
+        let total_lines = self.tabs[self.active_tab].total_rows();
         let visible_lines = self.visible_lines();
-        let scroll_offset = self.tabs[self.active_tab].scroll_offset();
-        let ui_tree = UiTree::new(
-            self.width,
-            self.height,
-            self.scale,
-            self.ui_state.tab_scroll_x,
-            &tab_info,
-        );
+        let scroll_offset = self.tabs[self.active_tab].scroll_row();
+        let ui_tree = self.ui_tree();
 
         match ui_tree.double_click(x, y, total_lines, visible_lines, scroll_offset) {
             UiAction::ActivateTab(i) => {
@@ -207,6 +222,10 @@ impl App {
             }
             UiAction::TextClick => {
                 let _ = self.click_at(x, y, false);
+                // Quick clicks on a task box each toggle it
+                if self.cursor_on_task_box() {
+                    return AppResult::Redraw;
+                }
                 self.tabs[self.active_tab].select_word_at_cursor();
                 return AppResult::Redraw;
             }
@@ -215,22 +234,13 @@ impl App {
     }
 
     pub fn handle_triple_click(&mut self, x: f32, y: f32) -> AppResult {
-        let tab_info: Vec<(&str, bool)> = self
-            .tabs
-            .iter()
-            .enumerate()
-            .map(|(i, t)| (t.title(), i == self.active_tab))
-            .collect();
-        let total_lines = self.tabs[self.active_tab].total_lines();
+        if self.focus.is_context_menu() {
+            return self.handle_context_menu_click(x, y);
+        }
+        let total_lines = self.tabs[self.active_tab].total_rows();
         let visible_lines = self.visible_lines();
-        let scroll_offset = self.tabs[self.active_tab].scroll_offset();
-        let ui_tree = UiTree::new(
-            self.width,
-            self.height,
-            self.scale,
-            self.ui_state.tab_scroll_x,
-            &tab_info,
-        );
+        let scroll_offset = self.tabs[self.active_tab].scroll_row();
+        let ui_tree = self.ui_tree();
 
         match ui_tree.triple_click(x, y, total_lines, visible_lines, scroll_offset) {
             UiAction::ActivateTab(i) => {
@@ -243,6 +253,9 @@ impl App {
             }
             UiAction::TextClick => {
                 let _ = self.click_at(x, y, false);
+                if self.cursor_on_task_box() {
+                    return AppResult::Redraw;
+                }
                 self.tabs[self.active_tab].select_line_at_cursor();
                 return AppResult::Redraw;
             }
@@ -251,25 +264,29 @@ impl App {
     }
 
     pub fn right_click_at(&mut self, x: f32, y: f32) -> AppResult {
-        let tab_info: Vec<(&str, bool)> = self
-            .tabs
-            .iter()
-            .enumerate()
-            .map(|(i, t)| (t.title(), i == self.active_tab))
-            .collect();
-
-        let ui_tree = UiTree::new(
-            self.width,
-            self.height,
-            self.scale,
-            self.ui_state.tab_scroll_x,
-            &tab_info,
-        );
-        match ui_tree.hit_test(x, y) {
-            UiNode::Tab(i) => {
-                self.start_rename(i);
-                AppResult::Redraw
-            }
+        if self.focus.is_notes_picker()
+            || self.focus.is_journal_calendar()
+            || self.focus.statistics().is_some()
+        {
+            return AppResult::Ok;
+        }
+
+        // Right-clicking while a menu is open reopens it at the new position
+        let _ = self.focus.cancel_context_menu();
+        match self.ui_tree().hit_test(x, y) {
+            UiNode::Tab(i) => self.open_tab_context_menu(i, x, y),
+            UiNode::TextArea => self.open_editor_context_menu(x, y),
+            _ => AppResult::Redraw,
+        }
+    }
+
+    /// Middle-click closes the tab under the pointer
+    pub fn middle_click_at(&mut self, x: f32, y: f32) -> AppResult {
+        if !matches!(self.focus, Focus::Editor) {
+            return AppResult::Ok;
+        }
+        match self.ui_tree().hit_test(x, y) {
+            UiNode::Tab(i) | UiNode::TabClose(i) => self.close_tab_at(i),
             _ => AppResult::Ok,
         }
     }
@@ -293,16 +310,10 @@ impl App {
                 }
             }
             MouseInteraction::ScrollbarDrag { drag_offset } => {
-                let total_lines = self.tabs[self.active_tab].total_lines();
+                let total_lines = self.tabs[self.active_tab].total_rows();
                 let visible_lines = self.visible_lines();
-                let scroll_offset = self.tabs[self.active_tab].scroll_offset();
-                let ui_tree = UiTree::new(
-                    self.width,
-                    self.height,
-                    self.scale,
-                    self.ui_state.tab_scroll_x,
-                    &self.tab_titles(),
-                );
+                let scroll_offset = self.tabs[self.active_tab].scroll_row();
+                let ui_tree = self.ui_tree();
                 match ui_tree.drag_scrollbar(
                     y,
                     total_lines,
@@ -325,7 +336,7 @@ impl App {
         let height = self.visible_lines() as isize;
         let relative_y = y - content_start_y;
         let mut clicked_visual_line =
-            (relative_y / (layout::LINE_HEIGHT * self.scale)).floor() as isize;
+            (relative_y / self.line_height()).floor() as isize;
 
         if clicked_visual_line < 0 || clicked_visual_line >= height {
             if self.ui_state.last_drag_scroll.elapsed()
@@ -342,16 +353,9 @@ impl App {
             }
         }
 
-        let scroll_offset = self.tabs[self.active_tab].scroll_offset();
-        let clicked_line = (scroll_offset as isize + clicked_visual_line).max(0) as usize;
+        let clicked_line = self.tabs[self.active_tab].line_at_visual_row(clicked_visual_line);
 
-        let char_width = self.renderer.get_char_width();
-        let scroll_offset_x = self.tabs[self.active_tab].scroll_offset_x();
-        let relative_x = (x - layout::PADDING * self.scale + scroll_offset_x).max(0.0);
-        let clicked_visual_col = (relative_x / char_width).round() as usize;
-        
-        // Convert visual column to character column (accounting for tab width)
-        let clicked_col = self.tabs[self.active_tab].visual_col_to_char_col(clicked_line, clicked_visual_col);
+        let clicked_col = self.char_col_at_x(clicked_line, x);
 
         self.tabs[self.active_tab].set_cursor_position(clicked_line, clicked_col, true);
         self.auto_scroll();
@@ -359,6 +363,21 @@ impl App {
         AppResult::Redraw
     }
 
+    /// Character column under a screen x position, using the line's glyph layout
+    pub(super) fn char_col_at_x(&mut self, line: usize, x: f32) -> usize {
+        let tab = &self.tabs[self.active_tab];
+        let relative_x =
+            (x - self.content_left() - layout::PADDING * self.scale + tab.scroll_offset_x()).max(0.0);
+        self.renderer
+            .line_layout(tab.line_text(line))
+            .col_at_x(relative_x)
+    }
+
+    fn cursor_on_task_box(&self) -> bool {
+        let tab = &self.tabs[self.active_tab];
+        tab.is_on_task_box(tab.cursor_line(), tab.cursor_col())
+    }
+
     pub fn end_drag(&mut self) {
         self.ui_state.mouse_interaction = MouseInteraction::None;
     }
@@ -368,20 +387,8 @@ impl App {
             return AppResult::Ok;
         }
 
-        let tab_info: Vec<(&str, bool)> = self
-            .tabs
-            .iter()
-            .enumerate()
-            .map(|(i, t)| (t.title(), i == self.active_tab))
-            .collect();
-
-        let ui_tree = UiTree::new(
-            self.width,
-            self.height,
-            self.scale,
-            self.ui_state.tab_scroll_x,
-            &tab_info,
-        );
+
+        let ui_tree = self.ui_tree();
         if let UiNode::Tab(to_index) = ui_tree.hit_test(x, y) {
             if to_index != from_index && from_index < self.tabs.len() && to_index < self.tabs.len()
             {
@@ -408,14 +415,14 @@ impl App {
     }
 
     pub(super) fn jump_scrollbar_to_ratio(&mut self, ratio: f32) -> AppResult {
-        let total_lines = self.tabs[self.active_tab].total_lines();
+        let total_lines = self.tabs[self.active_tab].total_rows();
         let visible_lines = self.visible_lines();
         if total_lines <= visible_lines {
             return AppResult::Ok;
         }
         let max_scroll = total_lines.saturating_sub(visible_lines);
-        let scroll_offset = (ratio.clamp(0.0, 1.0) * max_scroll as f32).round() as usize;
-        if self.tabs[self.active_tab].set_scroll_offset(scroll_offset) {
+        let scroll_row = (ratio.clamp(0.0, 1.0) * max_scroll as f32).round() as usize;
+        if self.tabs[self.active_tab].set_scroll_row(scroll_row) {
             return AppResult::Redraw;
         }
         AppResult::Ok
@@ -5,15 +5,20 @@ use std::path::PathBuf;
 use crate::persistence;
 use crate::tab::Tab;
 
-use super::focus::{Focus, NoteEntry};
+use super::focus::{Focus, NoteEntry, PickerChoice, TagEntry};
 use super::state::AppResult;
 use super::App;
 
 impl App {
     /// Open the notes picker with all available notes
     pub fn open_notes_picker(&mut self) -> AppResult {
-        // Get all notes from the data directory
-        let all_note_paths = persistence::list_notes().unwrap_or_default();
+        // Get all notes from the data directory, without templates
+        let all_note_paths: Vec<PathBuf> = persistence::list_notes()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|path| !persistence::is_template(path))
+            .collect();
+        let note_index = persistence::NoteIndex::load();
 
         // Get paths of currently open tabs
         let open_paths: Vec<&PathBuf> = self
@@ -32,11 +37,15 @@ impl App {
                         .unwrap_or("Unknown")
                         .to_string()
                 });
+                let folder = persistence::note_folder_label(&path);
                 let is_open = open_paths.iter().any(|p| **p == path);
+                let tags = note_index.tags_of(&path).to_vec();
                 NoteEntry {
                     path,
                     title,
+                    folder,
                     is_open,
+                    tags,
                 }
             })
             .collect();
@@ -45,7 +54,12 @@ impl App {
             return AppResult::Ok;
         }
 
-        self.focus = Focus::start_notes_picker(notes);
+        let tags = note_index
+            .tag_counts()
+            .into_iter()
+            .map(|(name, count)| TagEntry { name, count })
+            .collect();
+        self.focus = Focus::start_notes_picker(notes, tags);
         AppResult::Redraw
     }
 
@@ -73,10 +87,30 @@ impl App {
 
     /// Confirm notes picker selection
     pub fn confirm_notes_picker(&mut self) -> AppResult {
-        if let Some(path) = self.focus.confirm_notes_picker() {
-            return self.open_note_by_path(path);
+        match self.focus.confirm_notes_picker() {
+            Some(PickerChoice::Open(path)) => self.open_note_by_path(path),
+            Some(PickerChoice::OpenAt { path, line }) => self.open_note_at_line(path, line),
+            Some(PickerChoice::RenameTag { from, to }) => self.rename_tag(&from, &to),
+            Some(PickerChoice::NewFromTemplate { template, title }) => {
+                self.new_from_template(&template, &title)
+            }
+            Some(PickerChoice::GoTo { line, col }) => self.go_to(line, col),
+            Some(PickerChoice::Handled) => AppResult::Redraw,
+            None => AppResult::Ok,
         }
-        AppResult::Ok
+    }
+
+    /// Rename a tag in every note. Open tabs are changed in their buffers
+    /// (undoable); other notes are rewritten on disk.
+    pub fn rename_tag(&mut self, from: &str, to: &str) -> AppResult {
+        for tab in &mut self.tabs {
+            if tab.rename_tag(from, to) {
+                tab.auto_save();
+            }
+        }
+        let open_paths: Vec<&PathBuf> = self.tabs.iter().filter_map(|tab| tab.path()).collect();
+        let _ = persistence::rename_tag_in_notes(from, to, &open_paths);
+        AppResult::Redraw
     }
 
     /// Cancel notes picker
@@ -128,8 +162,9 @@ impl App {
         }
         
         // Check if click is outside the overlay (cancel)
-        let list_count = self.focus.notes_picker_state()
-            .map(|(_, list)| list.len().min(max_visible_items))
+        let list_count = self.focus.notes_picker_view()
+            .and_then(|view| view.list)
+            .map(|list| list.len().min(max_visible_items))
             .unwrap_or(0);
         let list_height = list_count as f32 * item_height;
         let overlay_height = input_height + list_height + 16.0 * scale;
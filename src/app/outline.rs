@@ -0,0 +1,87 @@
+//! Heading outline of the active note, heading motions, folding and go to
+//! line
+
+use crate::tab::Tab;
+
+use super::focus::{Focus, HeadingEntry};
+use super::state::AppResult;
+use super::App;
+
+impl App {
+    /// Open the outline with the heading above the cursor highlighted
+    pub fn open_outline(&mut self) -> AppResult {
+        let tab = &self.tabs[self.active_tab];
+        let headings = tab.headings();
+        if headings.is_empty() {
+            return AppResult::Ok;
+        }
+        let line = tab.cursor_line();
+        let current = headings
+            .iter()
+            .rposition(|heading| heading.line <= line)
+            .unwrap_or(0);
+        let entries = headings
+            .into_iter()
+            .map(|heading| HeadingEntry {
+                line: heading.line,
+                level: heading.level,
+                text: heading.text,
+            })
+            .collect();
+        self.focus = Focus::start_outline(entries, current);
+        AppResult::Redraw
+    }
+
+    /// Ask for a line (or line:column) to go to
+    pub fn open_go_to_line(&mut self) -> AppResult {
+        let lines = self.tabs[self.active_tab].total_lines();
+        self.focus = Focus::start_go_to_line(lines);
+        AppResult::Redraw
+    }
+
+    /// Put the cursor at `line` and `col` in the active note
+    pub(super) fn go_to(&mut self, line: usize, col: usize) -> AppResult {
+        self.tabs[self.active_tab].set_cursor_position(line, col, false);
+        self.auto_scroll();
+        self.ui_state.reset_cursor_blink();
+        AppResult::Redraw
+    }
+
+    /// Move the cursor to the next (or previous) heading
+    pub fn go_to_heading(&mut self, forward: bool) -> AppResult {
+        if !matches!(self.focus, Focus::Editor) {
+            return AppResult::Ok;
+        }
+        if !self.tabs[self.active_tab].go_to_heading(forward) {
+            return AppResult::Ok;
+        }
+        self.auto_scroll();
+        self.ui_state.reset_cursor_blink();
+        AppResult::Redraw
+    }
+
+    /// Fold the innermost region around the cursor, or unfold the fold on
+    /// the cursor line
+    pub fn toggle_fold(&mut self) -> AppResult {
+        self.change_folds(Tab::toggle_fold)
+    }
+
+    pub fn fold_all(&mut self) -> AppResult {
+        self.change_folds(Tab::fold_all)
+    }
+
+    pub fn unfold_all(&mut self) -> AppResult {
+        self.change_folds(Tab::unfold_all)
+    }
+
+    fn change_folds(&mut self, change: fn(&mut Tab) -> bool) -> AppResult {
+        if !matches!(self.focus, Focus::Editor) {
+            return AppResult::Ok;
+        }
+        if !change(&mut self.tabs[self.active_tab]) {
+            return AppResult::Ok;
+        }
+        self.auto_scroll();
+        AppResult::Redraw
+    }
+}
@@ -9,6 +9,9 @@ impl App {
         if self.focus.is_renaming() {
             return AppResult::Ok;
         }
+        if let Some(result) = self.page_journal_calendar(-1) {
+            return result;
+        }
         let page_size = self.visible_lines().saturating_sub(1).max(1);
 
         for _ in 0..page_size {
@@ -24,6 +27,9 @@ impl App {
         if self.focus.is_renaming() {
             return AppResult::Ok;
         }
+        if let Some(result) = self.page_journal_calendar(1) {
+            return result;
+        }
         let page_size = self.visible_lines().saturating_sub(1).max(1);
 
         for _ in 0..page_size {
@@ -0,0 +1,183 @@
+//! Notebook sidebar and folder operations
+
+use std::path::PathBuf;
+
+use native_dialog::FileDialog;
+
+use crate::config::layout;
+use crate::persistence;
+use crate::tab::Tab;
+use crate::ui::{sidebar_rect, NotebookTree, Rect, SidebarRowKind};
+
+use super::state::AppResult;
+use super::App;
+
+impl App {
+    pub fn toggle_sidebar(&mut self) -> AppResult {
+        self.sidebar = match self.sidebar.take() {
+            Some(_) => None,
+            None => {
+                let (folders, notes) = notebook_contents();
+                Some(NotebookTree::new(
+                    persistence::get_data_dir(),
+                    folders,
+                    notes,
+                ))
+            }
+        };
+        self.auto_scroll();
+        AppResult::Redraw
+    }
+
+    /// Re-read the data directory after notes were created, moved or renamed
+    pub(super) fn refresh_panels(&mut self) {
+        if let Some(tree) = &mut self.sidebar {
+            let (folders, notes) = notebook_contents();
+            tree.refresh(folders, notes);
+        }
+        self.refresh_backlinks();
+    }
+
+    pub(crate) fn sidebar_rect(&self) -> Option<Rect> {
+        self.sidebar
+            .as_ref()
+            .map(|_| sidebar_rect(self.height, self.scale))
+    }
+
+    /// Left edge of the editor area; the sidebar pushes it to the right
+    pub(crate) fn content_left(&self) -> f32 {
+        self.sidebar_rect().map_or(0.0, |rect| rect.width)
+    }
+
+    fn sidebar_row_height(&self) -> f32 {
+        layout::SIDEBAR_ROW_HEIGHT * self.scale
+    }
+
+    fn sidebar_row_at(&self, x: f32, y: f32) -> Option<usize> {
+        let rect = self.sidebar_rect()?;
+        if !rect.contains(x, y) {
+            return None;
+        }
+        let list_top = rect.y + layout::PADDING * 0.5 * self.scale;
+        self.sidebar
+            .as_ref()?
+            .row_at(y - list_top, self.sidebar_row_height())
+    }
+
+    /// Update the hovered sidebar row; returns true when it changed
+    pub(super) fn hover_sidebar(&mut self, x: f32, y: f32) -> bool {
+        let row = self.sidebar_row_at(x, y);
+        self.sidebar
+            .as_mut()
+            .is_some_and(|tree| tree.set_hovered(row))
+    }
+
+    /// Clicking a folder expands or collapses it; clicking a note opens it
+    pub(super) fn handle_sidebar_click(&mut self, x: f32, y: f32) -> AppResult {
+        let Some(index) = self.sidebar_row_at(x, y) else {
+            return AppResult::Ok;
+        };
+        let Some(row) = self
+            .sidebar
+            .as_ref()
+            .and_then(|tree| tree.rows().get(index))
+        else {
+            return AppResult::Ok;
+        };
+        let path = row.path.clone();
+        match row.kind {
+            SidebarRowKind::Folder { .. } => {
+                if let Some(tree) = &mut self.sidebar {
+                    tree.toggle_folder(&path);
+                }
+                AppResult::Redraw
+            }
+            SidebarRowKind::Note => self.open_note_by_path(path),
+        }
+    }
+
+    /// Scroll the sidebar when the pointer is over it. Returns None when the
+    /// scroll belongs to the editor instead.
+    pub(super) fn scroll_sidebar(&mut self, delta: isize) -> Option<AppResult> {
+        let rect = self.sidebar_rect()?;
+        if !rect.contains(self.ui_state.last_mouse_x, self.ui_state.last_mouse_y) {
+            return None;
+        }
+        let visible_rows = (rect.height / self.sidebar_row_height()).floor().max(1.0) as usize;
+        let tree = self.sidebar.as_mut()?;
+        Some(if tree.scroll_by(delta, visible_rows) {
+            AppResult::Redraw
+        } else {
+            AppResult::Ok
+        })
+    }
+
+    /// New untitled tab whose note will be created in a folder picked in a
+    /// dialog (the dialog can also create the folder)
+    pub fn new_note_in_folder(&mut self) -> AppResult {
+        let Some(folder) = choose_folder() else {
+            return AppResult::Ok;
+        };
+        self.tabs.push(Tab::new_untitled_in(folder));
+        self.active_tab = self.tabs.len() - 1;
+        self.auto_scroll();
+        AppResult::Redraw
+    }
+
+    /// Move the tab's note into a folder picked in a dialog
+    pub fn move_tab_to_folder(&mut self, index: usize) -> AppResult {
+        if index >= self.tabs.len() {
+            return AppResult::Ok;
+        }
+        let Some(folder) = choose_folder() else {
+            return AppResult::Ok;
+        };
+        let open_paths: Vec<PathBuf> = self
+            .tabs
+            .iter()
+            .enumerate()
+            .filter(|&(other, _)| other != index)
+            .filter_map(|(_, tab)| tab.path().cloned())
+            .collect();
+        match self.tabs[index].move_to_folder(&folder, &open_paths) {
+            Some((old, new)) => self.file_moved(&old, &new),
+            None => AppResult::Ok,
+        }
+    }
+}
+
+/// Folders and titled notes below the data directory. Templates are left
+/// out; they are reached through the template picker.
+fn notebook_contents() -> (Vec<PathBuf>, Vec<(PathBuf, String)>) {
+    let folders = persistence::list_folders()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|folder| !persistence::is_template(folder))
+        .collect();
+    let notes = persistence::list_notes()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|path| !persistence::is_template(path))
+        .map(|path| {
+            let title = persistence::load_note_title(&path).unwrap_or_else(|| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("Unknown")
+                    .to_string()
+            });
+            (path, title)
+        })
+        .collect();
+    (folders, notes)
+}
+
+/// Folder picked in a dialog. Only folders inside the data directory are
+/// accepted, as notes elsewhere are not auto-saved.
+fn choose_folder() -> Option<PathBuf> {
+    let root = persistence::ensure_data_dir().ok()?;
+    FileDialog::new()
+        .set_location(&root)
+        .show_open_single_dir()
+        .ok()?
+        .filter(|folder| persistence::is_in_data_dir(folder))
+}
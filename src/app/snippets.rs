@@ -0,0 +1,47 @@
+//! Tab key: snippet expansion, tab stops, table cells and list indentation
+
+use crate::persistence;
+
+use super::focus::Focus;
+use super::state::AppResult;
+use super::App;
+
+impl App {
+    /// Tab: go to the next tab stop of an expanded snippet, expand the
+    /// snippet trigger before the cursor, move to the next table cell, indent
+    /// list items, or insert a tab
+    pub fn handle_tab(&mut self) -> AppResult {
+        if !matches!(self.focus, Focus::Editor) {
+            return self.handle_char('\t');
+        }
+        let tab = &mut self.tabs[self.active_tab];
+        if tab.cycle_tab_stop(true)
+            || tab.expand_snippet(&persistence::load_snippets())
+            || tab.move_table_cell(false)
+            || tab.indent_list_items(false)
+        {
+            self.auto_save_active();
+            self.auto_scroll();
+            self.ui_state.reset_cursor_blink();
+            return AppResult::Redraw;
+        }
+        self.handle_char('\t')
+    }
+
+    /// Shift+Tab: go back to the previous tab stop or table cell, or outdent
+    /// list items
+    pub fn previous_tab_stop(&mut self) -> AppResult {
+        if !matches!(self.focus, Focus::Editor) {
+            return AppResult::Ok;
+        }
+        let tab = &mut self.tabs[self.active_tab];
+        if !tab.cycle_tab_stop(false) && !tab.move_table_cell(true) && !tab.indent_list_items(true)
+        {
+            return AppResult::Ok;
+        }
+        self.auto_save_active();
+        self.auto_scroll();
+        self.ui_state.reset_cursor_blink();
+        AppResult::Redraw
+    }
+}
@@ -0,0 +1,132 @@
+//! Spell checking: turning it on and off, and the suggestion menu for a
+//! misspelled word
+
+use crate::persistence;
+use crate::spelling::SpellChecker;
+use crate::ui::MenuItem;
+
+use super::action::Action;
+use super::focus::Focus;
+use super::state::AppResult;
+use super::App;
+
+/// The word a spelling menu was opened for
+pub(super) struct Misspelling {
+    line: usize,
+    start: usize,
+    end: usize,
+    word: String,
+    suggestions: Vec<String>,
+}
+
+impl App {
+    pub fn toggle_spell_check(&mut self) -> AppResult {
+        self.spell_check = !self.spell_check;
+        // Look for the dictionary again in case one was installed since
+        self.spell_checker = if self.spell_check { load_spell_checker() } else { None };
+        // Remember the choice for the next start
+        let _ = persistence::save_session_state(&self.export_session_state());
+        AppResult::Redraw
+    }
+
+    /// Show suggestions for the misspelled word at the cursor, just below it
+    pub fn open_spelling_menu_at_cursor(&mut self) -> AppResult {
+        if !matches!(self.focus, Focus::Editor) {
+            return AppResult::Ok;
+        }
+        let tab = &self.tabs[self.active_tab];
+        let line = tab.cursor_line();
+        let Some(items) = self.spelling_items(line, tab.cursor_col()) else {
+            return AppResult::Ok;
+        };
+        let start = self.misspelling.as_ref().map_or(0, |target| target.start);
+        let (x, y) = self.text_position(line, start);
+        self.open_context_menu(items, x, y + self.line_height())
+    }
+
+    /// Menu items for the misspelled word at `line` and `col`: its
+    /// suggestions, then adding it to the personal dictionary. The word is
+    /// remembered for whichever item is chosen.
+    pub(super) fn spelling_items(
+        &mut self,
+        line: usize,
+        col: usize,
+    ) -> Option<Vec<MenuItem<Action>>> {
+        self.misspelling = None;
+        let checker = self.spell_checker.as_ref()?;
+        let (start, end, word) = self.tabs[self.active_tab].misspelling_at(checker, line, col)?;
+        let suggestions = checker.suggestions(&word);
+
+        let mut items: Vec<MenuItem<Action>> = suggestions
+            .iter()
+            .enumerate()
+            .map(|(index, suggestion)| {
+                MenuItem::new(suggestion.clone(), Action::ApplySuggestion(index))
+            })
+            .collect();
+        if items.is_empty() {
+            items.push(MenuItem::new("No Suggestions", Action::ApplySuggestion(0)).enabled(false));
+        }
+        items.push(MenuItem::new(
+            format!("Add “{word}” to Dictionary"),
+            Action::AddToDictionary,
+        ));
+        self.misspelling = Some(Misspelling {
+            line,
+            start,
+            end,
+            word,
+            suggestions,
+        });
+        Some(items)
+    }
+
+    /// Replace the misspelled word with suggestion `index`
+    pub fn apply_suggestion(&mut self, index: usize) -> AppResult {
+        let Some(target) = self.misspelling.take() else {
+            return AppResult::Ok;
+        };
+        let Some(suggestion) = target.suggestions.get(index) else {
+            return AppResult::Ok;
+        };
+        self.tabs[self.active_tab].replace_in_line(
+            target.line,
+            target.start,
+            target.end,
+            suggestion,
+        );
+        self.auto_save_active();
+        self.auto_scroll();
+        self.ui_state.reset_cursor_blink();
+        AppResult::Redraw
+    }
+
+    /// Accept the misspelled word from now on, in every note
+    pub fn add_to_dictionary(&mut self) -> AppResult {
+        let Some(target) = self.misspelling.take() else {
+            return AppResult::Ok;
+        };
+        if let Some(checker) = &mut self.spell_checker {
+            checker.add_word(&target.word);
+        }
+        let _ = persistence::add_to_personal_dictionary(&target.word);
+        AppResult::Redraw
+    }
+
+    /// Line and column under a point in the text area
+    pub(super) fn line_col_at(&mut self, x: f32, y: f32) -> (usize, usize) {
+        let row = ((y - self.content_start_y()) / self.line_height()).floor() as isize;
+        let line = self.tabs[self.active_tab].line_at_visual_row(row);
+        (line, self.char_col_at_x(line, x))
+    }
+}
+
+/// Spell checker with the system dictionary and the personal word list, or
+/// None when no dictionary is installed
+pub(super) fn load_spell_checker() -> Option<SpellChecker> {
+    let dictionary = SpellChecker::load_dictionary()?;
+    Some(SpellChecker::new(
+        dictionary,
+        persistence::load_personal_dictionary(),
+    ))
+}
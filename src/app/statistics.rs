@@ -0,0 +1,22 @@
+//! Statistics overlay: counts and reading time for the active note or its
+//! selection
+
+use super::focus::Focus;
+use super::state::AppResult;
+use super::App;
+
+impl App {
+    /// Show statistics for the selection, or the whole note without one
+    pub fn open_statistics(&mut self) -> AppResult {
+        let (stats, selection) = self.tabs[self.active_tab].statistics();
+        self.focus = Focus::Statistics { stats, selection };
+        AppResult::Redraw
+    }
+
+    pub fn close_statistics(&mut self) -> AppResult {
+        if self.focus.close_statistics() {
+            return AppResult::Redraw;
+        }
+        AppResult::Ok
+    }
+}
@@ -0,0 +1,32 @@
+//! Table commands: inserting and deleting rows and columns
+
+use crate::tables::TableEdit;
+
+use super::action::Action;
+use super::focus::Focus;
+use super::keybindings::{self, KeyEvent};
+use super::state::AppResult;
+use super::App;
+
+impl App {
+    /// Action for a key press. The table shortcuts only apply with the
+    /// cursor in a table; elsewhere their keys keep their usual meaning.
+    pub fn resolve_key(&self, event: &KeyEvent) -> Option<Action> {
+        if matches!(self.focus, Focus::Editor) && self.tabs[self.active_tab].in_table() {
+            keybindings::resolve(event)
+        } else {
+            keybindings::resolve_outside_table(event)
+        }
+    }
+
+    /// Apply a row or column edit to the table at the cursor
+    pub fn edit_table(&mut self, edit: TableEdit) -> AppResult {
+        if !matches!(self.focus, Focus::Editor) || !self.tabs[self.active_tab].edit_table(edit) {
+            return AppResult::Ok;
+        }
+        self.auto_save_active();
+        self.auto_scroll();
+        self.ui_state.reset_cursor_blink();
+        AppResult::Redraw
+    }
+}
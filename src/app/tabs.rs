@@ -1,11 +1,51 @@
 //! Tab management operations
 
-use crate::tab::Tab;
+use std::path::Path;
+
+use crate::config::{self, tabs::CLOSED_TAB_HISTORY};
+use crate::persistence::TabState;
+use crate::tab::{SaveMode, Tab};
 
 use super::focus::Focus;
 use super::state::AppResult;
 use super::App;
 
+/// A closed tab remembered for Ctrl+Shift+T
+#[derive(Debug, Clone)]
+pub struct ClosedTab {
+    pub state: TabState,
+    /// Position the tab had in the tab bar
+    pub index: usize,
+}
+
+/// Most recently closed tabs, newest last, capped at `CLOSED_TAB_HISTORY`
+#[derive(Debug, Default)]
+pub struct ClosedTabs {
+    entries: Vec<ClosedTab>,
+}
+
+impl ClosedTabs {
+    pub fn push(&mut self, state: TabState, index: usize) {
+        self.entries.push(ClosedTab { state, index });
+        if self.entries.len() > CLOSED_TAB_HISTORY {
+            self.entries.remove(0);
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<ClosedTab> {
+        self.entries.pop()
+    }
+
+    /// Follow a file that was moved while its tab was closed
+    pub fn rename_path(&mut self, from: &Path, to: &Path) {
+        for entry in &mut self.entries {
+            if entry.state.path == from {
+                entry.state.path = to.to_path_buf();
+            }
+        }
+    }
+}
+
 impl App {
     pub fn new_tab(&mut self) -> AppResult {
         self.tabs.push(Tab::new_untitled());
@@ -15,17 +55,129 @@ impl App {
     }
 
     pub fn close_current_tab(&mut self) -> AppResult {
-        if self.tabs.len() <= 1 {
+        self.close_tab_at(self.active_tab)
+    }
+
+    /// Close the tab at `index`; closing the last tab leaves an empty one
+    pub fn close_tab_at(&mut self, index: usize) -> AppResult {
+        if index >= self.tabs.len() || !self.confirm_discard([index]) {
             return AppResult::Ok;
         }
-        self.tabs.remove(self.active_tab);
-        if self.active_tab >= self.tabs.len() {
-            self.active_tab = self.tabs.len() - 1;
+        let _ = self.focus.cancel_rename();
+        self.remove_tab(index);
+        if self.tabs.is_empty() {
+            self.tabs.push(Tab::new_untitled());
+        }
+        if index < self.active_tab || self.active_tab >= self.tabs.len() {
+            self.active_tab = self.active_tab.saturating_sub(1);
         }
         self.auto_scroll();
         AppResult::Redraw
     }
 
+    /// Close every tab except the one at `index`
+    pub fn close_other_tabs(&mut self, index: usize) -> AppResult {
+        if index >= self.tabs.len() || self.tabs.len() <= 1 {
+            return AppResult::Ok;
+        }
+        if !self.confirm_discard((0..self.tabs.len()).filter(|&i| i != index)) {
+            return AppResult::Ok;
+        }
+        let _ = self.focus.cancel_rename();
+        // Right to left, so reopening restores them left to right at their
+        // original positions
+        for i in (0..self.tabs.len()).rev() {
+            if i != index {
+                self.remove_tab(i);
+            }
+        }
+        self.active_tab = 0;
+        self.auto_scroll();
+        AppResult::Redraw
+    }
+
+    /// Close every tab after the one at `index`
+    pub fn close_tabs_to_right(&mut self, index: usize) -> AppResult {
+        if index + 1 >= self.tabs.len() || !self.confirm_discard(index + 1..self.tabs.len()) {
+            return AppResult::Ok;
+        }
+        let _ = self.focus.cancel_rename();
+        for i in (index + 1..self.tabs.len()).rev() {
+            self.remove_tab(i);
+        }
+        self.active_tab = self.active_tab.min(index);
+        self.auto_scroll();
+        AppResult::Redraw
+    }
+
+    /// Reopen the most recently closed tab with its cursor and scroll position
+    pub fn reopen_closed_tab(&mut self) -> AppResult {
+        while let Some(closed) = self.closed_tabs.pop() {
+            // Already open again (e.g. from the notes picker): just switch to it
+            if let Some(open) = self
+                .tabs
+                .iter()
+                .position(|tab| tab.path() == Some(&closed.state.path))
+            {
+                self.active_tab = open;
+                self.auto_scroll();
+                return AppResult::Redraw;
+            }
+
+            // Skip notes that were deleted since
+            let Some(mut tab) = Tab::from_file(closed.state.path.clone()) else {
+                continue;
+            };
+            tab.apply_state(&closed.state);
+
+            // Replace the empty tab left behind by closing the last one
+            if self.tabs.len() == 1
+                && self.tabs[0].path().is_none()
+                && self.tabs[0].content().is_empty()
+            {
+                self.tabs.clear();
+            }
+            let index = closed.index.min(self.tabs.len());
+            self.tabs.insert(index, tab);
+            self.active_tab = index;
+            self.auto_scroll();
+            return AppResult::Redraw;
+        }
+        AppResult::Ok
+    }
+
+    /// Remove a tab, saving it and remembering it for `reopen_closed_tab`.
+    /// Unsaved changes in manually saved tabs are dropped; callers confirm first.
+    fn remove_tab(&mut self, index: usize) {
+        let mut tab = self.tabs.remove(index);
+        // Untitled tabs that were never edited have nothing to bring back
+        if tab.path().is_none() && tab.content().is_empty() {
+            return;
+        }
+        tab.auto_save();
+        // Closing settles the name of a note still named after its heading
+        let moved = tab.finish_rename();
+        if let Some(state) = tab.export_state() {
+            self.closed_tabs.push(state, index);
+        }
+        if let Some((old, new)) = moved {
+            let _ = self.file_moved(&old, &new);
+        }
+    }
+
+    /// Open a copy of the tab at `index` right after it
+    pub fn duplicate_tab(&mut self, index: usize) -> AppResult {
+        let Some(tab) = self.tabs.get(index) else {
+            return AppResult::Ok;
+        };
+        let mut copy = tab.duplicate();
+        copy.auto_save();
+        self.tabs.insert(index + 1, copy);
+        self.active_tab = index + 1;
+        self.auto_scroll();
+        AppResult::Redraw
+    }
+
     pub fn next_tab(&mut self) -> AppResult {
         if self.tabs.is_empty() {
             return AppResult::Ok;
@@ -57,6 +209,21 @@ impl App {
         AppResult::Redraw
     }
 
+    /// Switch the tab at `index` between auto-save and explicit save
+    pub fn toggle_auto_save(&mut self, index: usize) -> AppResult {
+        let Some(tab) = self.tabs.get_mut(index) else {
+            return AppResult::Ok;
+        };
+        let mode = match tab.save_mode() {
+            SaveMode::Auto => SaveMode::Manual,
+            SaveMode::Manual => SaveMode::Auto,
+        };
+        tab.set_save_mode(mode);
+        // Pending changes are written as soon as auto-save is turned on
+        tab.auto_save();
+        AppResult::Redraw
+    }
+
     pub fn start_rename(&mut self, tab_index: usize) {
         if let Some(tab) = self.tabs.get(tab_index) {
             self.focus = Focus::start_rename(tab_index, tab.title());
@@ -65,10 +232,26 @@ impl App {
 
     pub fn confirm_rename(&mut self) -> AppResult {
         if let Some((tab_index, title)) = self.focus.confirm_rename() {
-            if let Some(tab) = self.tabs.get_mut(tab_index) {
-                tab.set_title(title);
-            }
-            return AppResult::Redraw;
+            let Some(tab) = self.tabs.get_mut(tab_index) else {
+                return AppResult::Redraw;
+            };
+            let old_title = tab.title().to_string();
+            tab.set_title(title.clone());
+            let moved = if config::notes::RENAME_FILES_WITH_TITLE {
+                tab.rename_file_to_title()
+            } else {
+                None
+            };
+            let result = match &moved {
+                Some((old, new)) => self.file_moved(old, new),
+                None => {
+                    self.refresh_panels();
+                    AppResult::Redraw
+                }
+            };
+            let moved = moved.as_ref().map(|(old, new)| (old.as_path(), new.as_path()));
+            self.offer_link_update(&old_title, &title, moved);
+            return result;
         }
         AppResult::Ok
     }
@@ -80,3 +263,57 @@ impl App {
         AppResult::Ok
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn state(name: &str) -> TabState {
+        TabState {
+            path: PathBuf::from(name),
+            cursor_line: 0,
+            cursor_col: 0,
+            scroll_offset: 0,
+            scroll_offset_x: 0.0,
+            word_wrap: false,
+            auto_save: None,
+        }
+    }
+
+    #[test]
+    fn test_closed_tabs_pop_newest_first() {
+        let mut closed = ClosedTabs::default();
+        closed.push(state("a.md"), 0);
+        closed.push(state("b.md"), 3);
+        let last = closed.pop().unwrap();
+        assert_eq!(last.state.path, PathBuf::from("b.md"));
+        assert_eq!(last.index, 3);
+        assert_eq!(closed.pop().unwrap().state.path, PathBuf::from("a.md"));
+        assert!(closed.pop().is_none());
+    }
+
+    #[test]
+    fn test_closed_tabs_follow_moved_files() {
+        let mut closed = ClosedTabs::default();
+        closed.push(state("old.md"), 0);
+        closed.rename_path(Path::new("old.md"), Path::new("notes/new.md"));
+        assert_eq!(closed.pop().unwrap().state.path, PathBuf::from("notes/new.md"));
+    }
+
+    #[test]
+    fn test_closed_tabs_history_is_capped() {
+        let mut closed = ClosedTabs::default();
+        for i in 0..CLOSED_TAB_HISTORY + 5 {
+            closed.push(state(&format!("{i}.md")), i);
+        }
+        let mut count = 0;
+        let mut oldest = None;
+        while let Some(tab) = closed.pop() {
+            count += 1;
+            oldest = Some(tab.index);
+        }
+        assert_eq!(count, CLOSED_TAB_HISTORY);
+        assert_eq!(oldest, Some(5));
+    }
+}
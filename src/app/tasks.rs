@@ -0,0 +1,90 @@
+//! Task checkboxes: toggling them in the editor and the task list across
+//! all notes
+
+use std::fs;
+use std::path::PathBuf;
+
+use crate::front_matter;
+use crate::markdown;
+use crate::persistence;
+
+use super::focus::{Focus, PickerItem, TaskEntry, TaskNote};
+use super::state::AppResult;
+use super::App;
+
+impl App {
+    /// Check or uncheck the task on the cursor line
+    pub fn toggle_task(&mut self) -> AppResult {
+        if !matches!(self.focus, Focus::Editor) {
+            return AppResult::Ok;
+        }
+        let tab = &mut self.tabs[self.active_tab];
+        let line = tab.cursor_line();
+        if !tab.toggle_task(line) {
+            return AppResult::Ok;
+        }
+        self.auto_save_active();
+        AppResult::Redraw
+    }
+
+    /// Open the task list with the tasks of every note, grouped by note.
+    /// Open tabs are read from their buffers so unsaved edits show up.
+    /// Checkboxes in templates are not tasks yet and are left out.
+    pub fn open_task_list(&mut self) -> AppResult {
+        let mut items = Vec::new();
+        let notes = persistence::list_notes().unwrap_or_default();
+        for path in notes
+            .into_iter()
+            .filter(|path| !persistence::is_template(path))
+        {
+            let open = self.tabs.iter().find(|tab| tab.path() == Some(&path));
+            let body = match open {
+                Some(tab) => tab.content().to_string(),
+                None => match fs::read_to_string(&path) {
+                    Ok(content) => front_matter::parse(&content).1.to_string(),
+                    Err(_) => continue,
+                },
+            };
+            let tasks = markdown::tasks(&body);
+            if tasks.is_empty() {
+                continue;
+            }
+            let title = persistence::load_note_title(&path).unwrap_or_else(|| {
+                path.file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            });
+            items.push(PickerItem::TaskNote(TaskNote {
+                path: path.clone(),
+                title: title.clone(),
+            }));
+            items.extend(tasks.into_iter().map(|task| {
+                PickerItem::Task(TaskEntry {
+                    path: path.clone(),
+                    line: task.line,
+                    text: task.text,
+                    done: task.done,
+                    note: title.clone(),
+                })
+            }));
+        }
+
+        if items.is_empty() {
+            return AppResult::Ok;
+        }
+        self.focus = Focus::start_task_list(items);
+        AppResult::Redraw
+    }
+
+    /// Open a note with the cursor at the start of `line`
+    pub(super) fn open_note_at_line(&mut self, path: PathBuf, line: usize) -> AppResult {
+        let result = self.open_note_by_path(path.clone());
+        let tab = &mut self.tabs[self.active_tab];
+        if tab.path() == Some(&path) {
+            tab.set_cursor_position(line, 0, false);
+            self.auto_scroll();
+            return AppResult::Redraw;
+        }
+        result
+    }
+}
@@ -0,0 +1,87 @@
+//! Creating notes from the templates folder
+
+use std::fs;
+use std::path::Path;
+
+use native_dialog::{MessageDialog, MessageType};
+
+use crate::date::{self, Date};
+use crate::front_matter;
+use crate::persistence;
+use crate::templates::{self, Variables};
+
+use super::focus::{Focus, TemplateEntry};
+use super::state::AppResult;
+use super::App;
+
+impl App {
+    /// Open the template picker. Without any templates, explain where they
+    /// go instead.
+    pub fn open_template_picker(&mut self) -> AppResult {
+        let templates: Vec<TemplateEntry> = persistence::list_templates()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|path| {
+                let name = persistence::load_note_title(&path).unwrap_or_else(|| {
+                    path.file_stem()
+                        .map(|stem| stem.to_string_lossy().to_string())
+                        .unwrap_or_default()
+                });
+                TemplateEntry { path, name }
+            })
+            .collect();
+
+        if templates.is_empty() {
+            let dir = persistence::templates_dir();
+            let _ = fs::create_dir_all(&dir);
+            let text = format!(
+                "Add Markdown files to {} to use them as templates.\n\n\
+                 {{{{title}}}}, {{{{date}}}} and {{{{time}}}} are filled in, and the \
+                 cursor starts at {{{{cursor}}}}.",
+                dir.display()
+            );
+            let _ = MessageDialog::new()
+                .set_type(MessageType::Info)
+                .set_title("No templates")
+                .set_text(&text)
+                .show_alert();
+            return AppResult::Ok;
+        }
+
+        self.focus = Focus::start_template_picker(templates);
+        AppResult::Redraw
+    }
+
+    /// Create a note titled `title` from a template and open it with the
+    /// cursor at the template's `{{cursor}}` marker
+    pub fn new_from_template(&mut self, template: &Path, title: &str) -> AppResult {
+        let Ok(content) = fs::read_to_string(template) else {
+            return AppResult::Redraw;
+        };
+        let (front_matter, body) = front_matter::parse(&content);
+        let tags = front_matter
+            .map(|front_matter| front_matter.tags)
+            .unwrap_or_default();
+        let today = Date::today().iso();
+        let time = date::local_time();
+        let expanded = templates::expand(
+            body,
+            &Variables {
+                date: &today,
+                time: &time,
+                title,
+            },
+        );
+        let Ok(path) = persistence::create_note_from_template(title, tags, &expanded.text) else {
+            return AppResult::Redraw;
+        };
+        self.refresh_panels();
+        let result = self.open_note_by_path(path.clone());
+        let tab = &mut self.tabs[self.active_tab];
+        if let Some((line, col)) = expanded.cursor.filter(|_| tab.path() == Some(&path)) {
+            tab.set_cursor_position(line, col, false);
+            self.auto_scroll();
+        }
+        result
+    }
+}
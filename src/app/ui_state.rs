@@ -19,6 +19,14 @@ pub enum MouseInteraction {
     TextSelection,
 }
 
+/// IME composition text shown inline at the cursor before it is committed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImePreedit {
+    pub text: String,
+    /// Byte range of the IME's own cursor within `text`, if it shows one
+    pub cursor: Option<(usize, usize)>,
+}
+
 /// Transient UI state for rendering and interactions
 pub struct UiState {
     // Cursor blink
@@ -27,6 +35,7 @@ pub struct UiState {
 
     // Hover states
     pub hovered_tab_index: Option<usize>,
+    pub hovered_tab_close: Option<usize>,
     pub hovered_plus: bool,
     pub hovered_scrollbar: bool,
     pub hovered_window_minimize: bool,
@@ -45,6 +54,9 @@ pub struct UiState {
 
     // Flame effect positions (line, col, timestamp)
     pub typing_flame_positions: Vec<(usize, usize, Instant)>,
+
+    // In-progress IME composition
+    pub ime_preedit: Option<ImePreedit>,
 }
 
 impl UiState {
@@ -53,6 +65,7 @@ impl UiState {
             cursor_visible: true,
             last_cursor_blink: Instant::now(),
             hovered_tab_index: None,
+            hovered_tab_close: None,
             hovered_plus: false,
             hovered_scrollbar: false,
             hovered_window_minimize: false,
@@ -65,6 +78,7 @@ impl UiState {
             last_mouse_y: 0.0,
             tab_scroll_x: 0.0,
             typing_flame_positions: Vec::new(),
+            ime_preedit: None,
         }
     }
 
@@ -23,6 +23,70 @@ pub mod layout {
     pub const MIN_TAB_WIDTH: f32 = 100.0;
     /// New tab button size
     pub const NEW_TAB_BUTTON_SIZE: f32 = 28.0;
+    /// Size of the close (×) button inside each tab
+    pub const TAB_CLOSE_BUTTON_SIZE: f32 = 16.0;
+    /// Gap between a tab's close button and its right edge
+    pub const TAB_CLOSE_BUTTON_MARGIN: f32 = 8.0;
+    /// Width of the notebook sidebar
+    pub const SIDEBAR_WIDTH: f32 = 240.0;
+    /// Height of one row in the notebook sidebar
+    pub const SIDEBAR_ROW_HEIGHT: f32 = 26.0;
+    /// Extra indent per folder level in the notebook sidebar
+    pub const SIDEBAR_INDENT: f32 = 14.0;
+    /// Width of the backlinks panel
+    pub const BACKLINKS_WIDTH: f32 = 240.0;
+    /// Height of the status bar at the bottom of the window
+    pub const STATUS_BAR_HEIGHT: f32 = 24.0;
+    /// Size of one day in the journal calendar
+    pub const CALENDAR_CELL_SIZE: f32 = 40.0;
+}
+
+/// Tab management constants
+pub mod tabs {
+    /// Number of closed tabs remembered for Ctrl+Shift+T
+    pub const CLOSED_TAB_HISTORY: usize = 20;
+}
+
+/// Note file constants
+pub mod notes {
+    /// Rename a note's file when its title changes
+    pub const RENAME_FILES_WITH_TITLE: bool = true;
+    /// Longest file name stem derived from a title
+    pub const MAX_SLUG_CHARS: usize = 60;
+    /// Quiet time after the last auto-save before an untitled note's file
+    /// is renamed after its first heading
+    pub const RENAME_DELAY_MS: u64 = 2000;
+}
+
+/// Daily note constants
+pub mod journal {
+    /// Folder of the data directory holding daily notes
+    pub const FOLDER: &str = "journal";
+    /// File name stem of a daily note (`%Y`, `%m`, `%d`, `%e`, `%B`, `%b`,
+    /// `%A`, `%a`); must contain the year, month and day
+    pub const FILENAME_PATTERN: &str = "%Y-%m-%d";
+    /// Title given to a new daily note
+    pub const TITLE_PATTERN: &str = "%A, %B %e, %Y";
+    /// Body of a new daily note; date fields are filled in
+    pub const TEMPLATE: &str = "# %A, %B %e, %Y\n\n";
+}
+
+/// Note template constants
+pub mod templates {
+    /// Folder of the data directory holding note templates
+    pub const FOLDER: &str = "templates";
+}
+
+/// Snippet constants
+pub mod snippets {
+    /// Trigger and body of the snippets written to `snippets.json` the
+    /// first time snippets are used
+    pub const DEFAULTS: &[(&str, &str)] = &[
+        ("meeting", "## ${1:Meeting} - ${2:date}\n\n**Attendees:** $3\n\n### Notes\n\n- $0"),
+        ("code", "```${1:rust}\n$0\n```"),
+        ("table", "| ${1:Column} | ${2:Column} |\n| --- | --- |\n| $3 | $4 |\n$0"),
+        ("todo", "- [ ] $0"),
+    ];
 }
 
 /// Timing constants (in milliseconds)
@@ -49,6 +113,32 @@ pub mod rendering {
     pub const TAB_CHAR_WIDTH_RATIO: f32 = 9.0;
 }
 
+/// Font family configuration (resolved through fontconfig)
+pub mod fonts {
+    /// Primary family for editor content and UI text
+    pub const FAMILY: &str = "monospace";
+    /// Families loaded after the primary one for glyphs it does not cover
+    pub const FALLBACK_FAMILIES: &[&str] = &[
+        "DejaVu Sans Mono",
+        "Noto Sans Mono CJK SC",
+        "Droid Sans Fallback",
+        "Noto Color Emoji",
+        "DejaVu Sans",
+    ];
+}
+
+/// Content zoom constants (Ctrl+= / Ctrl+- / Ctrl+0)
+pub mod zoom {
+    /// Default zoom factor
+    pub const DEFAULT: f32 = 1.0;
+    /// Smallest allowed zoom factor
+    pub const MIN: f32 = 0.5;
+    /// Largest allowed zoom factor
+    pub const MAX: f32 = 3.0;
+    /// Zoom change per step
+    pub const STEP: f32 = 0.1;
+}
+
 /// Scroll behavior constants
 pub mod scroll {
     /// Lines to scroll per wheel tick
@@ -0,0 +1,330 @@
+//! Calendar dates and timestamps without a date/time crate
+//!
+//! Conversions use the proleptic Gregorian calendar. Timestamps are UTC;
+//! `Date::today` uses the local time zone, which on Unix is read from the C
+//! library (`localtime_r` through `libc`).
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECS_PER_DAY: i64 = 86_400;
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Monday first
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// A calendar day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// Date of a day count relative to 1970-01-01
+    pub fn from_days(days: i64) -> Self {
+        // Howard Hinnant's civil_from_days
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+        Self { year, month, day }
+    }
+
+    /// Day count relative to 1970-01-01
+    pub fn to_days(self) -> i64 {
+        // Howard Hinnant's days_from_civil
+        let year = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let month = i64::from(self.month);
+        let mp = if month > 2 { month - 3 } else { month + 9 };
+        let doy = (153 * mp + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    /// Today in the local time zone
+    pub fn today() -> Self {
+        Self::from_days((unix_now() + local_offset_secs()).div_euclid(SECS_PER_DAY))
+    }
+
+    pub fn add_days(self, days: i64) -> Self {
+        Self::from_days(self.to_days() + days)
+    }
+
+    /// Same day `months` later, clamped to the end of shorter months
+    pub fn add_months(self, months: i32) -> Self {
+        let index = self.year * 12 + self.month as i32 - 1 + months;
+        let year = index.div_euclid(12);
+        let month = index.rem_euclid(12) as u32 + 1;
+        Self {
+            year,
+            month,
+            day: self.day.min(days_in_month(year, month)),
+        }
+    }
+
+    pub fn first_of_month(self) -> Self {
+        Self { day: 1, ..self }
+    }
+
+    /// Day of the week, 0 for Monday through 6 for Sunday
+    pub fn weekday(self) -> u32 {
+        // 1970-01-01 was a Thursday
+        (self.to_days() + 3).rem_euclid(7) as u32
+    }
+
+    /// `YYYY-MM-DD`
+    pub fn iso(self) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+
+    /// Format with strftime-style fields: `%Y` year, `%m` and `%d`
+    /// zero-padded month and day, `%e` unpadded day, `%B`/`%b` full/short
+    /// month name, `%A`/`%a` full/short weekday name and `%%`. Anything else
+    /// is copied as is.
+    pub fn format(self, pattern: &str) -> String {
+        let month_name = MONTH_NAMES[self.month as usize - 1];
+        let weekday_name = WEEKDAY_NAMES[self.weekday() as usize];
+        let mut out = String::new();
+        let mut chars = pattern.chars();
+        while let Some(ch) = chars.next() {
+            if ch != '%' {
+                out.push(ch);
+                continue;
+            }
+            match chars.next() {
+                Some('Y') => out.push_str(&format!("{:04}", self.year)),
+                Some('m') => out.push_str(&format!("{:02}", self.month)),
+                Some('d') => out.push_str(&format!("{:02}", self.day)),
+                Some('e') => out.push_str(&self.day.to_string()),
+                Some('B') => out.push_str(month_name),
+                Some('b') => out.push_str(&month_name[..3]),
+                Some('A') => out.push_str(weekday_name),
+                Some('a') => out.push_str(&weekday_name[..3]),
+                Some('%') => out.push('%'),
+                Some(other) => {
+                    out.push('%');
+                    out.push(other);
+                }
+                None => out.push('%'),
+            }
+        }
+        out
+    }
+
+    /// Read a date written with `format` and the same pattern. Weekday names
+    /// are accepted but not checked against the date.
+    pub fn parse(pattern: &str, text: &str) -> Option<Self> {
+        let (mut year, mut month, mut day) = (None, None, None);
+        let mut rest = text;
+        let mut chars = pattern.chars();
+        while let Some(ch) = chars.next() {
+            let field = if ch == '%' { chars.next() } else { None };
+            match field {
+                Some('Y') => year = Some(take_number(&mut rest, 4, 4)? as i32),
+                Some('m') => month = Some(take_number(&mut rest, 2, 2)?),
+                Some('d') => day = Some(take_number(&mut rest, 2, 2)?),
+                Some('e') => day = Some(take_number(&mut rest, 1, 2)?),
+                Some('B') => month = Some(take_name(&mut rest, &MONTH_NAMES, None)? + 1),
+                Some('b') => month = Some(take_name(&mut rest, &MONTH_NAMES, Some(3))? + 1),
+                Some('A') => {
+                    take_name(&mut rest, &WEEKDAY_NAMES, None)?;
+                }
+                Some('a') => {
+                    take_name(&mut rest, &WEEKDAY_NAMES, Some(3))?;
+                }
+                Some(other) if other != '%' => {
+                    rest = rest.strip_prefix('%')?.strip_prefix(other)?;
+                }
+                _ => rest = rest.strip_prefix(ch)?,
+            }
+        }
+        let date = Self {
+            year: year?,
+            month: month?,
+            day: day?,
+        };
+        let valid = rest.is_empty()
+            && (1..=12).contains(&date.month)
+            && (1..=days_in_month(date.year, date.month)).contains(&date.day);
+        valid.then_some(date)
+    }
+}
+
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Full name of a month, 1 for January
+pub fn month_name(month: u32) -> &'static str {
+    MONTH_NAMES[(month as usize).clamp(1, 12) - 1]
+}
+
+/// Consume `min..=max` leading ASCII digits
+fn take_number(text: &mut &str, min: usize, max: usize) -> Option<u32> {
+    let len = text
+        .chars()
+        .take(max)
+        .take_while(char::is_ascii_digit)
+        .count();
+    if len < min {
+        return None;
+    }
+    let value = text[..len].parse().ok()?;
+    *text = &text[len..];
+    Some(value)
+}
+
+/// Consume a leading name (or its first `len` letters); returns its index
+fn take_name(text: &mut &str, names: &[&str], len: Option<usize>) -> Option<u32> {
+    names.iter().enumerate().find_map(|(index, name)| {
+        let name = &name[..len.unwrap_or(name.len())];
+        let rest = text.strip_prefix(name)?;
+        *text = rest;
+        Some(index as u32)
+    })
+}
+
+/// Seconds the local time zone is ahead of UTC
+#[cfg(unix)]
+fn local_offset_secs() -> i64 {
+    let now = unix_now() as libc::time_t;
+    // SAFETY: `tm` is a plain C struct, so all zeroes is a valid value.
+    // Both pointers refer to locals that outlive the call. localtime_r is the
+    // re-entrant variant: it only writes to the `tm` we pass in and shares no
+    // static buffer with other threads. On failure it returns null and `tm`
+    // is not read.
+    unsafe {
+        let mut tm: libc::tm = std::mem::zeroed();
+        if libc::localtime_r(&now, &mut tm).is_null() {
+            0
+        } else {
+            tm.tm_gmtoff as i64
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn local_offset_secs() -> i64 {
+    0
+}
+
+/// Seconds since the Unix epoch
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// RFC 3339 timestamp in UTC, e.g. `2024-03-09T14:05:00Z`
+pub fn timestamp(unix_secs: i64) -> String {
+    let date = Date::from_days(unix_secs.div_euclid(SECS_PER_DAY));
+    let secs = unix_secs.rem_euclid(SECS_PER_DAY);
+    format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        date.iso(),
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// Current time as an RFC 3339 UTC timestamp
+pub fn now_timestamp() -> String {
+    timestamp(unix_now())
+}
+
+/// Local wall-clock time as `HH:MM`
+pub fn local_time() -> String {
+    let secs = (unix_now() + local_offset_secs()).rem_euclid(SECS_PER_DAY);
+    format!("{:02}:{:02}", secs / 3600, secs % 3600 / 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_days() {
+        assert_eq!(Date::from_days(-1).iso(), "1969-12-31");
+        assert_eq!(Date::from_days(0).iso(), "1970-01-01");
+        // 2000 was a leap year
+        assert_eq!(Date::from_days(11_016).iso(), "2000-02-29");
+    }
+
+    #[test]
+    fn test_day_arithmetic() {
+        for days in [-1, 0, 11_016, 19_791] {
+            assert_eq!(Date::from_days(days).to_days(), days);
+        }
+        let date = Date {
+            year: 2024,
+            month: 1,
+            day: 31,
+        };
+        assert_eq!(date.add_months(1).iso(), "2024-02-29");
+        assert_eq!(date.add_months(-2).iso(), "2023-11-30");
+        assert_eq!(date.add_days(30).iso(), "2024-03-01");
+        // 2024-01-31 was a Wednesday
+        assert_eq!(date.weekday(), 2);
+    }
+
+    #[test]
+    fn test_format_and_parse() {
+        let date = Date {
+            year: 2024,
+            month: 3,
+            day: 9,
+        };
+        assert_eq!(date.format("%Y-%m-%d"), "2024-03-09");
+        assert_eq!(
+            date.format("%A, %B %e, %Y (%a %b) 100%%"),
+            "Saturday, March 9, 2024 (Sat Mar) 100%"
+        );
+        assert_eq!(Date::parse("%Y-%m-%d", "2024-03-09"), Some(date));
+        assert_eq!(Date::parse("%a %d %b %Y", "Sat 09 Mar 2024"), Some(date));
+        assert_eq!(Date::parse("%Y-%m-%d", "2024-02-30"), None);
+        assert_eq!(Date::parse("%Y-%m-%d", "2024-03-09-notes"), None);
+    }
+
+    #[test]
+    fn test_timestamp() {
+        assert_eq!(timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(timestamp(1_709_993_100), "2024-03-09T14:05:00Z");
+    }
+}
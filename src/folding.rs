@@ -0,0 +1,193 @@
+//! Foldable regions, and mapping between text lines and the rows shown when
+//! some of them are folded
+//!
+//! A fold keeps its header line visible and hides the lines after it. Rows
+//! count only visible lines, so scrolling, drawing and hit testing work in
+//! rows while the buffer keeps working in lines.
+
+use crate::markdown;
+
+/// Columns a tab counts for when comparing indentation
+const TAB_WIDTH: usize = 4;
+
+/// Foldable (first line, last line) ranges in `text`, sorted: indented
+/// blocks, plus sections under headings and fenced code blocks in markdown
+pub fn regions(text: &str, is_markdown: bool) -> Vec<(usize, usize)> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut regions = indent_regions(&lines);
+    if is_markdown {
+        let headings = markdown::headings(text);
+        regions.extend(
+            (0..headings.len()).map(|index| markdown::section(&headings, index, lines.len())),
+        );
+        regions.extend(fence_regions(&lines));
+    }
+    regions.retain(|&(first, last)| last > first);
+    regions.sort_unstable();
+    regions.dedup();
+    regions
+}
+
+/// Indentation width of a line, or None when it is blank
+fn indent_width(line: &str) -> Option<usize> {
+    if line.trim().is_empty() {
+        return None;
+    }
+    Some(
+        line.chars()
+            .take_while(|ch| ch.is_whitespace())
+            .map(|ch| if ch == '\t' { TAB_WIDTH } else { 1 })
+            .sum(),
+    )
+}
+
+/// A line followed by more deeply indented lines, up to the last of them
+fn indent_regions(lines: &[&str]) -> Vec<(usize, usize)> {
+    let indents: Vec<Option<usize>> = lines.iter().map(|line| indent_width(line)).collect();
+    let mut regions = Vec::new();
+    for (first, indent) in indents.iter().enumerate() {
+        let Some(indent) = *indent else {
+            continue;
+        };
+        let mut last = first;
+        for (line, other) in indents.iter().enumerate().skip(first + 1) {
+            match other {
+                None => continue,
+                Some(other) if *other > indent => last = line,
+                Some(_) => break,
+            }
+        }
+        if last > first {
+            regions.push((first, last));
+        }
+    }
+    regions
+}
+
+/// Fenced code blocks, from the opening fence to the closing one
+fn fence_regions(lines: &[&str]) -> Vec<(usize, usize)> {
+    let mut regions = Vec::new();
+    let mut open = None;
+    for (line, content) in lines.iter().enumerate() {
+        if markdown::is_fence(content) {
+            match open.take() {
+                Some(first) => regions.push((first, line)),
+                None => open = Some(line),
+            }
+        }
+    }
+    regions
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FoldMap {
+    /// Header line of every fold
+    headers: Vec<usize>,
+    /// Hidden line ranges (inclusive), sorted and merged
+    hidden: Vec<(usize, usize)>,
+}
+
+impl FoldMap {
+    /// Build from (header line, last hidden line) pairs
+    pub fn new(folds: &[(usize, usize)]) -> Self {
+        let mut headers: Vec<usize> = folds.iter().map(|&(header, _)| header).collect();
+        headers.sort_unstable();
+        let mut ranges: Vec<(usize, usize)> = folds
+            .iter()
+            .filter(|&&(header, last)| last > header)
+            .map(|&(header, last)| (header + 1, last))
+            .collect();
+        ranges.sort_unstable();
+        let mut hidden: Vec<(usize, usize)> = Vec::new();
+        for (first, last) in ranges {
+            match hidden.last_mut() {
+                Some(prev) if first <= prev.1 + 1 => prev.1 = prev.1.max(last),
+                _ => hidden.push((first, last)),
+            }
+        }
+        Self { headers, hidden }
+    }
+
+    pub fn is_hidden(&self, line: usize) -> bool {
+        self.hidden
+            .iter()
+            .any(|&(first, last)| first <= line && line <= last)
+    }
+
+    /// Whether `line` is a visible fold header (drawn with a marker)
+    pub fn is_folded(&self, line: usize) -> bool {
+        self.headers.binary_search(&line).is_ok() && !self.is_hidden(line)
+    }
+
+    /// Row of `line`; a hidden line shares the row of the header above it
+    pub fn row_of(&self, line: usize) -> usize {
+        let hidden_before: usize = self
+            .hidden
+            .iter()
+            .take_while(|&&(first, _)| first <= line)
+            .map(|&(first, last)| last.min(line) - first + 1)
+            .sum();
+        line - hidden_before
+    }
+
+    /// Visible line shown at `row`
+    pub fn line_at_row(&self, row: usize) -> usize {
+        let mut line = row;
+        for &(first, last) in &self.hidden {
+            if first > line {
+                break;
+            }
+            line += last - first + 1;
+        }
+        line
+    }
+
+    /// Number of rows needed to show `total_lines` lines
+    pub fn rows(&self, total_lines: usize) -> usize {
+        match total_lines {
+            0 => 0,
+            _ => self.row_of(total_lines - 1) + 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regions() {
+        let text = "# Title\n- item\n  - nested\n\n## Code\n```\nfn main() {\n    run();\n}\n```\n";
+        assert_eq!(
+            regions(text, true),
+            [(0, 9), (1, 2), (4, 9), (5, 9), (6, 7)]
+        );
+        // Other files only fold by indentation
+        assert_eq!(regions(text, false), [(1, 2), (6, 7)]);
+    }
+
+    #[test]
+    fn test_rows_skip_hidden_lines() {
+        // Lines 2..=4 folded under 1, 7..=8 under 6
+        let map = FoldMap::new(&[(1, 4), (6, 8)]);
+        assert!(map.is_folded(1));
+        assert!(map.is_hidden(3));
+        assert!(!map.is_hidden(5));
+        assert_eq!(map.row_of(5), 2);
+        assert_eq!(map.row_of(3), 1);
+        assert_eq!(map.row_of(9), 4);
+        assert_eq!(map.line_at_row(2), 5);
+        assert_eq!(map.line_at_row(4), 9);
+        assert_eq!(map.rows(10), 5);
+        assert_eq!(map.rows(9), 4);
+    }
+
+    #[test]
+    fn test_nested_folds_merge() {
+        let map = FoldMap::new(&[(0, 6), (2, 4)]);
+        assert!(map.is_folded(0));
+        assert!(!map.is_folded(2));
+        assert_eq!(map.line_at_row(1), 7);
+        assert_eq!(map.rows(8), 2);
+    }
+}
@@ -0,0 +1,195 @@
+//! YAML front matter at the top of a note
+//!
+//! Only the subset the app writes is understood: `title`, `tags`, `created`
+//! and `updated` as plain or quoted scalars, with tags as a flow list
+//! (`[a, b]`) or a block list (`- a`). Any other lines are kept verbatim so
+//! hand-written metadata survives a save.
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrontMatter {
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+    pub created: Option<String>,
+    pub updated: Option<String>,
+    other: Vec<String>,
+}
+
+const DELIMITER: &str = "---";
+
+/// Split a note into its front matter and body. Content without a complete
+/// front matter block is returned unchanged as the body.
+pub fn parse(content: &str) -> (Option<FrontMatter>, &str) {
+    let mut lines = content.split_inclusive('\n');
+    if lines.next().map(trim_line_end) != Some(DELIMITER) {
+        return (None, content);
+    }
+
+    let mut offset = content.find('\n').map_or(content.len(), |i| i + 1);
+    let mut header = Vec::new();
+    for line in lines {
+        offset += line.len();
+        let line = trim_line_end(line);
+        if line == DELIMITER || line == "..." {
+            return (Some(FrontMatter::from_lines(&header)), &content[offset..]);
+        }
+        header.push(line);
+    }
+    (None, content)
+}
+
+/// Front matter followed by the body, ready to be written to disk
+pub fn join(front_matter: Option<&FrontMatter>, body: &str) -> String {
+    match front_matter {
+        Some(front_matter) => front_matter.render() + body,
+        None => body.to_string(),
+    }
+}
+
+impl FrontMatter {
+    fn from_lines(lines: &[&str]) -> Self {
+        let mut front_matter = Self::default();
+        let mut index = 0;
+        while index < lines.len() {
+            let line = lines[index];
+            index += 1;
+            let Some((key, value)) = line.split_once(':') else {
+                front_matter.other.push(line.to_string());
+                continue;
+            };
+            let value = value.trim();
+            match key {
+                "title" => front_matter.title = Some(unquote(value)),
+                "created" => front_matter.created = Some(unquote(value)),
+                "updated" => front_matter.updated = Some(unquote(value)),
+                "tags" if value.is_empty() => {
+                    while let Some(item) = lines
+                        .get(index)
+                        .and_then(|line| line.trim_start().strip_prefix("- "))
+                    {
+                        front_matter.tags.push(unquote(item.trim()));
+                        index += 1;
+                    }
+                }
+                "tags" => {
+                    let list = value.trim_start_matches('[').trim_end_matches(']');
+                    front_matter.tags = list
+                        .split(',')
+                        .map(|tag| unquote(tag.trim()))
+                        .filter(|tag| !tag.is_empty())
+                        .collect();
+                }
+                _ => {
+                    front_matter.other.push(line.to_string());
+                    // Keep a nested block with its key
+                    while let Some(nested) = lines
+                        .get(index)
+                        .filter(|line| line.starts_with([' ', '\t']))
+                    {
+                        front_matter.other.push(nested.to_string());
+                        index += 1;
+                    }
+                }
+            }
+        }
+        front_matter
+    }
+
+    /// The block including its `---` delimiters and trailing newline
+    pub fn render(&self) -> String {
+        let mut out = format!("{DELIMITER}\n");
+        if let Some(title) = &self.title {
+            out.push_str(&format!("title: {}\n", quote(title)));
+        }
+        if !self.tags.is_empty() {
+            let tags: Vec<String> = self.tags.iter().map(|tag| quote(tag)).collect();
+            out.push_str(&format!("tags: [{}]\n", tags.join(", ")));
+        }
+        if let Some(created) = &self.created {
+            out.push_str(&format!("created: {}\n", quote(created)));
+        }
+        if let Some(updated) = &self.updated {
+            out.push_str(&format!("updated: {}\n", quote(updated)));
+        }
+        for line in &self.other {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push_str(DELIMITER);
+        out.push('\n');
+        out
+    }
+}
+
+fn trim_line_end(line: &str) -> &str {
+    line.trim_end_matches(['\n', '\r'])
+}
+
+fn unquote(value: &str) -> String {
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        return value[1..value.len() - 1]
+            .replace("\\\"", "\"")
+            .replace("\\\\", "\\");
+    }
+    if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        return value[1..value.len() - 1].replace("''", "'");
+    }
+    value.to_string()
+}
+
+/// Quote a scalar when writing it bare would change its meaning
+fn quote(value: &str) -> String {
+    let needs_quotes = value.is_empty()
+        || value != value.trim()
+        || value.contains(": ")
+        || value.contains(" #")
+        || value.contains([',', '[', ']', '{', '}'])
+        || value.starts_with([
+            '#', '"', '\'', '-', '?', '&', '*', '!', '|', '>', '%', '@', '`',
+        ]);
+    if needs_quotes {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_render_round_trip() {
+        let content = "---\ntitle: \"Plan: Q3\"\ntags: [work, ideas]\ncreated: 2024-03-09T14:05:00Z\n---\n# Body\n";
+        let (front_matter, body) = parse(content);
+        let front_matter = front_matter.unwrap();
+        assert_eq!(front_matter.title.as_deref(), Some("Plan: Q3"));
+        assert_eq!(front_matter.tags, vec!["work", "ideas"]);
+        assert_eq!(
+            front_matter.created.as_deref(),
+            Some("2024-03-09T14:05:00Z")
+        );
+        assert_eq!(body, "# Body\n");
+        assert_eq!(join(Some(&front_matter), body), content);
+    }
+
+    #[test]
+    fn test_block_tags_and_unknown_keys_are_kept() {
+        let content = "---\r\ntags:\r\n  - a\r\n  - 'b c'\r\nauthor:\r\n  name: Sam\r\n---\r\nText";
+        let (front_matter, body) = parse(content);
+        let front_matter = front_matter.unwrap();
+        assert_eq!(front_matter.tags, vec!["a", "b c"]);
+        assert_eq!(body, "Text");
+        assert_eq!(
+            front_matter.render(),
+            "---\ntags: [a, b c]\nauthor:\n  name: Sam\n---\n"
+        );
+    }
+
+    #[test]
+    fn test_content_without_front_matter() {
+        assert_eq!(parse("# Title\n---\n"), (None, "# Title\n---\n"));
+        // An unterminated block is treated as ordinary text
+        assert_eq!(parse("---\ntitle: x\n"), (None, "---\ntitle: x\n"));
+        assert_eq!(join(None, "text"), "text");
+    }
+}
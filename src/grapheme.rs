@@ -0,0 +1,101 @@
+//! Grapheme cluster boundaries (UAX #29)
+//!
+//! A user-perceived character can span several `char`s: emoji ZWJ sequences,
+//! regional-indicator flags, decomposed Hangul syllables, letters followed by
+//! combining marks, or a CRLF line break. Cursor movement and deletion step
+//! over whole clusters so they never leave half a character behind.
+//!
+//! All functions work on byte offsets into a `&str`; callers holding char
+//! indices (like `TextBuffer`) convert at the edges.
+
+use unicode_segmentation::GraphemeCursor;
+
+/// Byte offset of the grapheme boundary before `offset` (0 at the start)
+pub fn prev_boundary(text: &str, offset: usize) -> usize {
+    let mut cursor = GraphemeCursor::new(offset.min(text.len()), text.len(), true);
+    cursor.prev_boundary(text, 0).ok().flatten().unwrap_or(0)
+}
+
+/// Byte offset of the grapheme boundary after `offset` (`text.len()` at the end)
+pub fn next_boundary(text: &str, offset: usize) -> usize {
+    let mut cursor = GraphemeCursor::new(offset.min(text.len()), text.len(), true);
+    cursor
+        .next_boundary(text, 0)
+        .ok()
+        .flatten()
+        .unwrap_or(text.len())
+}
+
+/// Whether `offset` lies on a grapheme boundary
+pub fn is_boundary(text: &str, offset: usize) -> bool {
+    if offset == 0 || offset >= text.len() {
+        return true;
+    }
+    if !text.is_char_boundary(offset) {
+        return false;
+    }
+    let mut cursor = GraphemeCursor::new(offset, text.len(), true);
+    cursor.is_boundary(text, 0).unwrap_or(true)
+}
+
+/// Move `offset` back to the start of the grapheme cluster containing it
+pub fn floor_boundary(text: &str, offset: usize) -> usize {
+    if is_boundary(text, offset) {
+        offset.min(text.len())
+    } else {
+        prev_boundary(text, offset)
+    }
+}
+
+/// Convert a char index within `text` to a byte offset (clamped to the end)
+pub fn char_to_byte(text: &str, char_idx: usize) -> usize {
+    text.char_indices()
+        .nth(char_idx)
+        .map(|(byte, _)| byte)
+        .unwrap_or(text.len())
+}
+
+/// Convert a byte offset within `text` to a char index
+pub fn byte_to_char(text: &str, byte: usize) -> usize {
+    text[..byte.min(text.len())].chars().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combining_mark_is_one_cluster() {
+        let text = "e\u{301}x"; // e + combining acute accent
+        assert_eq!(next_boundary(text, 0), 3);
+        assert_eq!(prev_boundary(text, 3), 0);
+        assert!(!is_boundary(text, 1));
+        assert_eq!(floor_boundary(text, 1), 0);
+    }
+
+    #[test]
+    fn test_emoji_zwj_sequence_and_flag() {
+        let family = "👨\u{200d}👩\u{200d}👧";
+        assert_eq!(next_boundary(family, 0), family.len());
+        assert_eq!(prev_boundary(family, family.len()), 0);
+
+        let flags = "🇯🇵🇰🇷";
+        assert_eq!(next_boundary(flags, 0), 8);
+        assert_eq!(prev_boundary(flags, flags.len()), 8);
+    }
+
+    #[test]
+    fn test_crlf_and_ascii() {
+        assert_eq!(next_boundary("a\r\nb", 1), 3);
+        assert_eq!(next_boundary("ab", 0), 1);
+        assert_eq!(prev_boundary("ab", 0), 0);
+        assert_eq!(next_boundary("ab", 2), 2);
+    }
+
+    #[test]
+    fn test_char_byte_conversion() {
+        assert_eq!(char_to_byte("日本", 1), 3);
+        assert_eq!(char_to_byte("日本", 5), 6);
+        assert_eq!(byte_to_char("日本", 3), 1);
+    }
+}
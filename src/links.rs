@@ -0,0 +1,175 @@
+//! Wiki-style `[[Note Title]]` links between notes
+//!
+//! `[[Title|label]]` and `[[Title#Heading]]` both link to "Title". Targets
+//! compare case-insensitively against a note's title or file name. Code
+//! spans never contain links; fenced code blocks are skipped when scanning
+//! whole notes.
+
+/// A link within one line; columns are char indices covering the brackets
+#[derive(Debug, Clone, PartialEq)]
+pub struct WikiLink {
+    pub start: usize,
+    pub end: usize,
+    pub target: String,
+}
+
+/// Form used to compare link targets with titles and file names
+pub fn normalize(target: &str) -> String {
+    target.trim().to_lowercase()
+}
+
+/// Char range of the target inside `[[...]]`, before any `|label` or `#heading`
+fn target_len(inner: &[char]) -> usize {
+    inner
+        .iter()
+        .position(|&ch| ch == '|' || ch == '#')
+        .unwrap_or(inner.len())
+}
+
+/// Links in a single line, left to right
+pub fn line_links(line: &str) -> Vec<WikiLink> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut links = Vec::new();
+    let mut in_code = false;
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '`' {
+            in_code = !in_code;
+        } else if !in_code && chars[i] == '[' && chars.get(i + 1) == Some(&'[') {
+            let inner_start = i + 2;
+            let close = chars[inner_start..]
+                .windows(2)
+                .position(|pair| pair == [']', ']']);
+            if let Some(len) = close {
+                let inner = &chars[inner_start..inner_start + len];
+                let target: String = inner[..target_len(inner)].iter().collect();
+                if !inner.contains(&'[') && !target.trim().is_empty() {
+                    let end = inner_start + len + 2;
+                    links.push(WikiLink {
+                        start: i,
+                        end,
+                        target: target.trim().to_string(),
+                    });
+                    i = end;
+                    continue;
+                }
+            }
+        }
+        i += 1;
+    }
+    links
+}
+
+/// Target of the link around `col`. The column must be inside the brackets,
+/// so a cursor just before or after a link is not on it.
+pub fn link_at(line: &str, col: usize) -> Option<String> {
+    line_links(line)
+        .into_iter()
+        .find(|link| link.start < col && col < link.end)
+        .map(|link| link.target)
+}
+
+/// Lines of a body outside fenced code blocks
+fn prose_lines(body: &str) -> impl Iterator<Item = (bool, &str)> {
+    let mut in_fence = false;
+    body.split_inclusive('\n').map(move |line| {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            return (false, line);
+        }
+        (!in_fence, line)
+    })
+}
+
+/// Normalized link targets of a note body, without duplicates
+pub fn note_links(body: &str) -> Vec<String> {
+    let mut targets: Vec<String> = Vec::new();
+    for (_, line) in prose_lines(body).filter(|(prose, _)| *prose) {
+        for link in line_links(line) {
+            let target = normalize(&link.target);
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+    }
+    targets
+}
+
+/// Point every link to `from` at `to`, keeping labels and headings.
+/// None when the body has no such link.
+pub fn retarget(body: &str, from: &str, to: &str) -> Option<String> {
+    let from = normalize(from);
+    let mut changed = false;
+    let mut renamed = String::with_capacity(body.len());
+    for (prose, line) in prose_lines(body) {
+        let links: Vec<WikiLink> = if prose {
+            line_links(line)
+                .into_iter()
+                .filter(|link| normalize(&link.target) == from)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        if links.is_empty() {
+            renamed.push_str(line);
+            continue;
+        }
+        changed = true;
+        let chars: Vec<char> = line.chars().collect();
+        let mut last = 0;
+        for link in links {
+            let inner = &chars[link.start + 2..link.end - 2];
+            renamed.extend(&chars[last..link.start + 2]);
+            renamed.push_str(to);
+            last = link.start + 2 + target_len(inner);
+        }
+        renamed.extend(&chars[last..]);
+    }
+    changed.then_some(renamed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_links() {
+        let line = "See [[Project Plan]], [[ideas|my ideas]] and `[[code]]` or [[]]";
+        let links = line_links(line);
+        assert_eq!(
+            links,
+            vec![
+                WikiLink {
+                    start: 4,
+                    end: 20,
+                    target: "Project Plan".to_string()
+                },
+                WikiLink {
+                    start: 22,
+                    end: 40,
+                    target: "ideas".to_string()
+                },
+            ]
+        );
+        assert_eq!(link_at(line, 10).as_deref(), Some("Project Plan"));
+        // Just before or after the brackets is not on the link
+        assert_eq!(link_at(line, 4), None);
+        assert_eq!(link_at(line, 20), None);
+    }
+
+    #[test]
+    fn test_note_links_skip_code_blocks() {
+        let body = "[[A]] and [[a#Intro]]\n```\n[[B]]\n```\n[[C]]\n";
+        assert_eq!(note_links(body), vec!["a", "c"]);
+    }
+
+    #[test]
+    fn test_retarget() {
+        let body = "[[Old Name]] and [[old name|alias]], [[Old Name#Top]]\n```\n[[Old Name]]\n```\n[[Other]]";
+        assert_eq!(
+            retarget(body, "Old Name", "New").as_deref(),
+            Some("[[New]] and [[New|alias]], [[New#Top]]\n```\n[[Old Name]]\n```\n[[Other]]")
+        );
+        assert_eq!(retarget(body, "Missing", "New"), None);
+    }
+}
@@ -8,15 +8,29 @@
 
 mod app;
 mod config;
+mod date;
+mod folding;
+mod front_matter;
+mod grapheme;
+mod links;
+mod markdown;
 mod persistence;
 mod renderer;
+mod snippets;
+mod spelling;
+mod stats;
 mod tab;
+mod tables;
+mod tags;
+mod templates;
 mod text_buffer;
+mod text_layout;
 mod theme;
 mod ui;
 mod visual_position;
+mod word;
 
-use app::{App, Key as AppKey, KeyEvent, Modifiers, resolve_keybinding};
+use app::{App, Key as AppKey, KeyEvent, Modifiers};
 use glutin::config::ConfigTemplateBuilder;
 use glutin::context::{ContextApi, ContextAttributesBuilder, PossiblyCurrentContext};
 use glutin::display::GetGlDisplay;
@@ -30,7 +44,7 @@ use std::num::NonZeroU32;
 use std::time::{Duration, Instant};
 use winit::application::ApplicationHandler;
 use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize};
-use winit::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::event::{ElementState, Ime, MouseButton, MouseScrollDelta, WindowEvent};
 use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
 use winit::keyboard::{Key, ModifiersState, NamedKey};
 use winit::window::{Window, WindowAttributes, WindowId};
@@ -65,6 +79,8 @@ struct AppHandler {
     last_click_time: Option<Instant>,
     last_click_pos: Option<(f64, f64)>,
     click_count: u32,
+    /// Last caret area reported to the IME, to avoid resending it every frame
+    ime_cursor_area: Option<(f32, f32, f32, f32)>,
 }
 
 struct AppState {
@@ -84,6 +100,7 @@ impl AppHandler {
             last_click_time: None,
             last_click_pos: None,
             click_count: 0,
+            ime_cursor_area: None,
         }
     }
 }
@@ -111,6 +128,7 @@ fn convert_winit_key(key: &Key, modifiers: &ModifiersState) -> Option<KeyEvent>
         Key::Named(NamedKey::PageUp) => AppKey::PageUp,
         Key::Named(NamedKey::PageDown) => AppKey::PageDown,
         Key::Named(NamedKey::Space) => AppKey::Space,
+        Key::Named(NamedKey::F7) => AppKey::F7,
         Key::Character(c) => {
             let ch = c.chars().next()?;
             AppKey::Char(ch)
@@ -170,6 +188,8 @@ impl ApplicationHandler for AppHandler {
             .expect("Failed to create window");
 
         let window = window.expect("Window not created");
+        // Receive composed text from input methods and compose/dead keys
+        window.set_ime_allowed(true);
         let gl_display = gl_config.display();
 
         // Create OpenGL context
@@ -242,9 +262,13 @@ impl ApplicationHandler for AppHandler {
 
         match event {
             WindowEvent::CloseRequested => {
+                if !state.app.confirm_quit() {
+                    return;
+                }
                 if let Some(window_state) = capture_window_state(&state.window) {
                     let _ = save_window_state(window_state);
                 }
+                state.app.finish_renames();
                 let session_state = state.app.export_session_state();
                 let _ = save_session_state(&session_state);
                 event_loop.exit();
@@ -282,16 +306,37 @@ impl ApplicationHandler for AppHandler {
                     let key_event = convert_winit_key(&event.logical_key, &self.modifiers);
                     if let Some(key_event) = key_event {
                         // Resolve to action and execute
-                        if let Some(action) = resolve_keybinding(&key_event) {
+                        if let Some(action) = state.app.resolve_key(&key_event) {
                             let result = state.app.execute(action);
                             if result.needs_redraw() {
                                 state.window.request_redraw();
                             }
                         }
+                    } else if let Some(text) = event.text.as_deref() {
+                        // Keys without a logical mapping (e.g. the result of a
+                        // dead-key sequence) still carry the text they produce
+                        if !self.modifiers.control_key()
+                            && !text.chars().any(char::is_control)
+                            && state.app.handle_ime_commit(text).needs_redraw()
+                        {
+                            state.window.request_redraw();
+                        }
                     }
                 }
             }
 
+            WindowEvent::Ime(ime) => {
+                let result = match ime {
+                    Ime::Preedit(text, cursor) => state.app.handle_ime_preedit(text, cursor),
+                    Ime::Commit(text) => state.app.handle_ime_commit(&text),
+                    Ime::Disabled => state.app.handle_ime_preedit(String::new(), None),
+                    Ime::Enabled => crate::app::AppResult::Ok,
+                };
+                if result.needs_redraw() {
+                    state.window.request_redraw();
+                }
+            }
+
             WindowEvent::MouseWheel { delta, .. } => {
                 use crate::app::ScrollInput;
 
@@ -442,6 +487,10 @@ impl ApplicationHandler for AppHandler {
                                 self.click_count = 0; // Reset after triple click
                                 res
                             }
+                            _ if self.modifiers.control_key() => state.app.ctrl_click_at(
+                                self.mouse_position.0 as f32,
+                                self.mouse_position.1 as f32,
+                            ),
                             _ => {
                                 let shift = self.modifiers.shift_key();
                                 state.app.click_at(
@@ -462,9 +511,13 @@ impl ApplicationHandler for AppHandler {
                                 state.window.set_maximized(!is_maximized);
                             }
                             crate::app::AppResult::WindowClose => {
+                                if !state.app.confirm_quit() {
+                                    return;
+                                }
                                 if let Some(window_state) = capture_window_state(&state.window) {
                                     let _ = save_window_state(window_state);
                                 }
+                                state.app.finish_renames();
                                 let session_state = state.app.export_session_state();
                                 let _ = save_session_state(&session_state);
                                 event_loop.exit();
@@ -505,10 +558,17 @@ impl ApplicationHandler for AppHandler {
                         state.app.reset_scroll_state();
                     }
                 }
-                MouseButton::Right | MouseButton::Other(2) | MouseButton::Middle
+                MouseButton::Middle if button_state == ElementState::Pressed => {
+                    let result = state
+                        .app
+                        .middle_click_at(self.mouse_position.0 as f32, self.mouse_position.1 as f32);
+                    if result.needs_redraw() {
+                        state.window.request_redraw();
+                    }
+                }
+                MouseButton::Right | MouseButton::Other(2)
                     if button_state == ElementState::Pressed =>
                 {
-                    println!("Right-click detected at {:?}", self.mouse_position);
                     let result = state
                         .app
                         .right_click_at(self.mouse_position.0 as f32, self.mouse_position.1 as f32);
@@ -525,6 +585,17 @@ impl ApplicationHandler for AppHandler {
                     .gl_surface
                     .swap_buffers(&state.gl_context)
                     .expect("Failed to swap buffers");
+
+                // Keep the IME candidate window next to the caret
+                let area = state.app.ime_cursor_area();
+                if self.ime_cursor_area != Some(area) {
+                    self.ime_cursor_area = Some(area);
+                    let (x, y, width, height) = area;
+                    state.window.set_ime_cursor_area(
+                        PhysicalPosition::new(x as f64, y as f64),
+                        PhysicalSize::new(width as f64, height as f64),
+                    );
+                }
             }
 
             _ => {}
@@ -0,0 +1,627 @@
+//! Markdown-aware editing: list and quote continuation, list indentation,
+//! bracket / emphasis pairs, task checkboxes and headings
+//!
+//! Each function edits a `TextBuffer` in one undoable step and returns false
+//! when it does not apply, so the caller can fall back to plain editing.
+
+use crate::text_buffer::TextBuffer;
+
+/// Brackets and emphasis that wrap a selection: typed char, open, close
+const PAIRS: [(char, &str, &str); 5] = [
+    ('(', "(", ")"),
+    ('[', "[", "]"),
+    ('{', "{", "}"),
+    ('`', "`", "`"),
+    ('*', "**", "**"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Marker {
+    Bullet(char),
+    Numbered { number: u32, delimiter: char },
+}
+
+impl Marker {
+    /// The marker with its trailing space
+    fn text(self) -> String {
+        match self {
+            Marker::Bullet(bullet) => format!("{bullet} "),
+            Marker::Numbered { number, delimiter } => format!("{number}{delimiter} "),
+        }
+    }
+}
+
+/// The structural start of a line: `> ` quotes, indentation, a list marker
+/// and a task box. The prefix is ASCII, so byte and char offsets agree.
+#[derive(Debug)]
+struct Prefix<'a> {
+    quote: &'a str,
+    indent: &'a str,
+    marker: Option<Marker>,
+    task: bool,
+    /// End of the marker and its space
+    marker_end: usize,
+    /// Start of the content: after the task box, marker or quote
+    len: usize,
+}
+
+impl<'a> Prefix<'a> {
+    fn parse(line: &'a str) -> Self {
+        let bytes = line.as_bytes();
+        let mut i = 0;
+        while bytes.get(i) == Some(&b'>') {
+            i += 1;
+            if bytes.get(i) == Some(&b' ') {
+                i += 1;
+            }
+        }
+        let quote = &line[..i];
+        let indent_len = bytes[i..]
+            .iter()
+            .take_while(|byte| **byte == b' ' || **byte == b'\t')
+            .count();
+        let indent = &line[i..i + indent_len];
+
+        let Some((marker, marker_len)) = parse_marker(&line[i + indent_len..]) else {
+            return Self {
+                quote,
+                indent,
+                marker: None,
+                task: false,
+                marker_end: i,
+                len: i,
+            };
+        };
+        let marker_end = i + indent_len + marker_len;
+        let after = &line[marker_end..];
+        let task_len = ["[ ] ", "[x] ", "[X] "]
+            .iter()
+            .find(|task| after.starts_with(**task))
+            .map(|task| task.len())
+            .or_else(|| ["[ ]", "[x]", "[X]"].contains(&after).then_some(3));
+        Self {
+            quote,
+            indent,
+            marker: Some(marker),
+            task: task_len.is_some(),
+            marker_end,
+            len: marker_end + task_len.unwrap_or(0),
+        }
+    }
+}
+
+/// `- `, `* `, `+ `, `1. ` or `1) ` at the start of `text`, with its length
+fn parse_marker(text: &str) -> Option<(Marker, usize)> {
+    let bytes = text.as_bytes();
+    match bytes.first()? {
+        bullet @ (b'-' | b'*' | b'+') if bytes.get(1) == Some(&b' ') => {
+            Some((Marker::Bullet(*bullet as char), 2))
+        }
+        _ => {
+            let digits = bytes
+                .iter()
+                .take_while(|byte| byte.is_ascii_digit())
+                .count();
+            if digits == 0 || digits > 9 || bytes.get(digits + 1) != Some(&b' ') {
+                return None;
+            }
+            let delimiter = match bytes[digits] {
+                b'.' => '.',
+                b')' => ')',
+                _ => return None,
+            };
+            let number = text[..digits].parse().ok()?;
+            Some((Marker::Numbered { number, delimiter }, digits + 2))
+        }
+    }
+}
+
+fn is_list_item(line: &str) -> bool {
+    Prefix::parse(line).marker.is_some()
+}
+
+/// The run of list item lines around `line`
+fn list_block(buffer: &TextBuffer, line: usize) -> (usize, usize) {
+    let mut first = line;
+    while first > 0 && is_list_item(&buffer.line_text(first - 1)) {
+        first -= 1;
+    }
+    let mut last = line;
+    while last + 1 < buffer.len_lines() && is_list_item(&buffer.line_text(last + 1)) {
+        last += 1;
+    }
+    (first, last)
+}
+
+/// Replace lines `first..=last` (without the final line break)
+fn replace_lines(buffer: &mut TextBuffer, first: usize, last: usize, lines: &[String]) {
+    let start = buffer.line_to_char(first);
+    let end = buffer.line_to_char(last) + buffer.line_text(last).chars().count();
+    buffer.replace_range(start, end, &lines.join("\n"));
+}
+
+/// Number each numbered item one more than the previous item at its
+/// level; the first item of a level keeps its number
+fn renumber(lines: &mut [String]) {
+    // Indent width and previous number of each open level
+    let mut levels: Vec<(usize, Option<u32>)> = Vec::new();
+    for line in lines.iter_mut() {
+        let prefix = Prefix::parse(line);
+        let Some(marker) = prefix.marker else {
+            continue;
+        };
+        let width = prefix.indent.len();
+        let marker_start = prefix.quote.len() + width;
+        let marker_end = prefix.marker_end;
+        while levels.last().is_some_and(|(level, _)| *level > width) {
+            levels.pop();
+        }
+        if levels.last().is_none_or(|(level, _)| *level < width) {
+            levels.push((width, None));
+        }
+        let Some((_, previous)) = levels.last_mut() else {
+            continue;
+        };
+        match marker {
+            Marker::Numbered { number, delimiter } => {
+                let wanted = previous.map_or(number, |previous| previous + 1);
+                *previous = Some(wanted);
+                if wanted != number {
+                    let marker = Marker::Numbered {
+                        number: wanted,
+                        delimiter,
+                    };
+                    line.replace_range(marker_start..marker_end, &marker.text());
+                }
+            }
+            Marker::Bullet(_) => *previous = None,
+        }
+    }
+}
+
+/// Enter: continue a list item or quote on the new line. Enter on an empty
+/// item ends the list (or outdents a nested item), and on an empty quote
+/// line ends the quote.
+pub fn insert_newline(buffer: &mut TextBuffer) -> bool {
+    if buffer.has_selection() {
+        return false;
+    }
+    let (line, col) = buffer.char_to_line_col(buffer.cursor());
+    let text = buffer.line_text(line);
+    let prefix = Prefix::parse(&text);
+    if (prefix.quote.is_empty() && prefix.marker.is_none()) || col < prefix.len {
+        return false;
+    }
+
+    if text[prefix.len..].trim().is_empty() {
+        if prefix.marker.is_some() && !prefix.indent.is_empty() {
+            return indent_list_items(buffer, true);
+        }
+        let kept = if prefix.marker.is_some() {
+            prefix.quote
+        } else {
+            ""
+        };
+        let start = buffer.line_to_char(line);
+        buffer.replace_range(start, start + text.chars().count(), kept);
+        return true;
+    }
+
+    let mut next = prefix.quote.to_string();
+    if let Some(marker) = prefix.marker {
+        let marker = match marker {
+            Marker::Numbered { number, delimiter } => Marker::Numbered {
+                number: number + 1,
+                delimiter,
+            },
+            bullet => bullet,
+        };
+        next.push_str(prefix.indent);
+        next.push_str(&marker.text());
+        if prefix.task {
+            next.push_str("[ ] ");
+        }
+    }
+
+    let (first, last) = list_block(buffer, line);
+    let mut lines: Vec<String> = (first..=last).map(|l| buffer.line_text(l)).collect();
+    let index = line - first;
+    let split = lines[index]
+        .char_indices()
+        .nth(col)
+        .map_or(lines[index].len(), |(byte, _)| byte);
+    let rest = lines[index].split_off(split);
+    lines.insert(index + 1, next + &rest);
+    renumber(&mut lines);
+    let new_col = Prefix::parse(&lines[index + 1]).len;
+    replace_lines(buffer, first, last, &lines);
+    buffer.set_cursor_by_line_col(line + 1, new_col, false);
+    true
+}
+
+/// Tab / Shift+Tab on list items: indent them under the item above, or
+/// outdent them to their parent's level. Applies when the cursor line, or
+/// every selected line, is a list item.
+pub fn indent_list_items(buffer: &mut TextBuffer, outdent: bool) -> bool {
+    let (start, end) = buffer
+        .selection_range()
+        .unwrap_or((buffer.cursor(), buffer.cursor()));
+    let (first_selected, start_col) = buffer.char_to_line_col(start);
+    let (mut last_selected, end_col) = buffer.char_to_line_col(end);
+    if end_col == 0 && last_selected > first_selected {
+        last_selected -= 1;
+    }
+    if !(first_selected..=last_selected).all(|line| is_list_item(&buffer.line_text(line))) {
+        return false;
+    }
+
+    let first = list_block(buffer, first_selected).0;
+    let last = list_block(buffer, last_selected).1;
+    let original: Vec<String> = (first..=last).map(|l| buffer.line_text(l)).collect();
+    let mut lines = original.clone();
+    for line in first_selected..=last_selected {
+        let index = line - first;
+        let prefix = Prefix::parse(&original[index]);
+        let indent = if outdent {
+            // The nearest shallower item above is the parent
+            original[..index]
+                .iter()
+                .rev()
+                .map(|line| Prefix::parse(line))
+                .find(|parent| parent.indent.len() < prefix.indent.len())
+                .map_or(String::new(), |parent| parent.indent.to_string())
+        } else {
+            let width = prefix.marker.map_or(0, |marker| marker.text().len());
+            format!("{}{}", prefix.indent, " ".repeat(width))
+        };
+        // An item indented into a new sublist starts its numbering at 1
+        let marker = match prefix.marker {
+            Some(Marker::Numbered { delimiter, .. }) if !outdent => Marker::Numbered {
+                number: 1,
+                delimiter,
+            },
+            marker => marker.unwrap_or(Marker::Bullet('-')),
+        };
+        lines[index] = format!(
+            "{}{}{}{}",
+            prefix.quote,
+            indent,
+            marker.text(),
+            &original[index][prefix.marker_end..]
+        );
+    }
+    renumber(&mut lines);
+
+    let shifted = |line: usize, col: usize| {
+        let index = line - first;
+        let delta =
+            lines[index].chars().count() as isize - original[index].chars().count() as isize;
+        let quote = Prefix::parse(&original[index]).quote.len() as isize;
+        (
+            line,
+            (col as isize + delta).max(quote.min(col as isize)) as usize,
+        )
+    };
+    let (start_line, start_col) = shifted(first_selected, start_col);
+    let (end_line, end_col) = if end == start {
+        (start_line, start_col)
+    } else {
+        let (line, col) = buffer.char_to_line_col(end);
+        if line > last_selected {
+            (line, col)
+        } else {
+            shifted(line, col)
+        }
+    };
+    replace_lines(buffer, first, last, &lines);
+    let anchor = buffer.line_to_char(start_line) + start_col;
+    let cursor = buffer.line_to_char(end_line) + end_col;
+    buffer.set_selection(anchor, cursor);
+    true
+}
+
+/// Typing a bracket, `` ` `` or `*` with a selection wraps it (`*` wraps in
+/// `**`). Without one, opening brackets get their closing bracket and a
+/// typed closing bracket steps over the one already there.
+pub fn insert_pair(buffer: &mut TextBuffer, ch: char) -> bool {
+    if let Some((start, end)) = buffer.selection_range() {
+        let Some((_, open, close)) = PAIRS.iter().find(|(typed, _, _)| *typed == ch) else {
+            return false;
+        };
+        let inner = buffer.selected_text();
+        buffer.replace_range(start, end, &format!("{open}{inner}{close}"));
+        let inner_start = start + open.chars().count();
+        buffer.set_selection(inner_start, inner_start + inner.chars().count());
+        return true;
+    }
+
+    let cursor = buffer.cursor();
+    let next = buffer.char_at(cursor);
+    if matches!(ch, ')' | ']' | '}') && next == Some(ch) {
+        buffer.set_selection(cursor + 1, cursor + 1);
+        return true;
+    }
+    let close = match ch {
+        '(' => ')',
+        '[' => ']',
+        '{' => '}',
+        _ => return false,
+    };
+    // Only close before whitespace, a closing bracket or the end of a line
+    if next.is_some_and(|next| !next.is_whitespace() && !matches!(next, ')' | ']' | '}')) {
+        return false;
+    }
+    buffer.replace_range(cursor, cursor, &format!("{ch}{close}"));
+    buffer.set_selection(cursor + 1, cursor + 1);
+    true
+}
+
+/// Backspace between an empty bracket pair deletes both brackets
+pub fn delete_pair(buffer: &mut TextBuffer) -> bool {
+    let cursor = buffer.cursor();
+    if buffer.has_selection() || cursor == 0 {
+        return false;
+    }
+    let pair = (buffer.char_at(cursor - 1), buffer.char_at(cursor));
+    if !matches!(
+        pair,
+        (Some('('), Some(')')) | (Some('['), Some(']')) | (Some('{'), Some('}'))
+    ) {
+        return false;
+    }
+    buffer.replace_range(cursor - 1, cursor + 1, "");
+    true
+}
+
+/// A `- [ ]` / `- [x]` item
+#[derive(Debug, Clone, PartialEq)]
+pub struct Task {
+    pub line: usize,
+    pub done: bool,
+    pub text: String,
+}
+
+/// Column of the `[` of the task box on `line`, if it is a task item
+pub fn task_box(line: &str) -> Option<usize> {
+    let prefix = Prefix::parse(line);
+    prefix.task.then_some(prefix.marker_end)
+}
+
+/// Every task item in `text`
+pub fn tasks(text: &str) -> Vec<Task> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(line, content)| {
+            let prefix = Prefix::parse(content);
+            prefix.task.then(|| Task {
+                line,
+                done: content.as_bytes()[prefix.marker_end + 1] != b' ',
+                text: content[prefix.len..].trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Check or uncheck the task box on `line`, leaving the cursor and
+/// selection where they are. Returns false when the line is not a task.
+pub fn toggle_task(buffer: &mut TextBuffer, line: usize) -> bool {
+    let text = buffer.line_text(line);
+    let Some(col) = task_box(&text) else {
+        return false;
+    };
+    let mark = if text.as_bytes()[col + 1] == b' ' {
+        "x"
+    } else {
+        " "
+    };
+    let (anchor, cursor) = buffer
+        .selection_range()
+        .unwrap_or((buffer.cursor(), buffer.cursor()));
+    let at = buffer.line_to_char(line) + col + 1;
+    buffer.replace_range(at, at + 1, mark);
+    buffer.set_selection(anchor, cursor);
+    true
+}
+
+/// An ATX heading (`# Title`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heading {
+    pub line: usize,
+    /// 1 for `#` through 6 for `######`
+    pub level: usize,
+    pub text: String,
+}
+
+/// Whether a line opens or closes a fenced code block
+pub fn is_fence(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("```") || trimmed.starts_with("~~~")
+}
+
+/// Every heading in `text`, skipping fenced code blocks
+pub fn headings(text: &str) -> Vec<Heading> {
+    let mut in_fence = false;
+    let mut headings = Vec::new();
+    for (line, content) in text.lines().enumerate() {
+        if is_fence(content) {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        let level = content.bytes().take_while(|byte| *byte == b'#').count();
+        let rest = &content[level..];
+        if (1..=6).contains(&level) && (rest.is_empty() || rest.starts_with([' ', '\t'])) {
+            headings.push(Heading {
+                line,
+                level,
+                text: rest.trim().trim_end_matches('#').trim_end().to_string(),
+            });
+        }
+    }
+    headings
+}
+
+/// Lines of the section under the heading at `index`: from the heading to
+/// the line before the next heading of the same or a higher level
+pub fn section(headings: &[Heading], index: usize, total_lines: usize) -> (usize, usize) {
+    let heading = &headings[index];
+    let end = headings[index + 1..]
+        .iter()
+        .find(|next| next.level <= heading.level)
+        .map_or(total_lines, |next| next.line);
+    (heading.line, end.saturating_sub(1).max(heading.line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Buffer with the cursor at the `|` marker
+    fn buffer(text: &str) -> TextBuffer {
+        let cursor = text.chars().position(|ch| ch == '|').unwrap();
+        let mut buffer = TextBuffer::from_str(&text.replacen('|', "", 1));
+        buffer.set_selection(cursor, cursor);
+        buffer
+    }
+
+    fn with_cursor(buffer: &TextBuffer) -> String {
+        let mut text: Vec<char> = buffer.content().chars().collect();
+        text.insert(buffer.cursor(), '|');
+        text.into_iter().collect()
+    }
+
+    #[test]
+    fn test_newline_continues_lists() {
+        let mut b = buffer("- one|");
+        assert!(insert_newline(&mut b));
+        assert_eq!(with_cursor(&b), "- one\n- |");
+
+        let mut b = buffer("  * [x] done|");
+        assert!(insert_newline(&mut b));
+        assert_eq!(with_cursor(&b), "  * [x] done\n  * [ ] |");
+
+        let mut b = buffer("> quoted|");
+        assert!(insert_newline(&mut b));
+        assert_eq!(with_cursor(&b), "> quoted\n> |");
+
+        let mut b = buffer("plain|");
+        assert!(!insert_newline(&mut b));
+    }
+
+    #[test]
+    fn test_newline_renumbers() {
+        let mut b = buffer("1. a|\n2. b\n3) c");
+        assert!(insert_newline(&mut b));
+        assert_eq!(with_cursor(&b), "1. a\n2. |\n3. b\n4) c");
+        b.undo();
+        assert_eq!(b.content(), "1. a\n2. b\n3) c");
+    }
+
+    #[test]
+    fn test_newline_on_empty_item_ends_list() {
+        let mut b = buffer("- a\n- |");
+        assert!(insert_newline(&mut b));
+        assert_eq!(with_cursor(&b), "- a\n|");
+
+        let mut b = buffer("- a\n  - |");
+        assert!(insert_newline(&mut b));
+        assert_eq!(with_cursor(&b), "- a\n- |");
+
+        let mut b = buffer("> - a\n> - |");
+        assert!(insert_newline(&mut b));
+        assert_eq!(with_cursor(&b), "> - a\n> |");
+
+        let mut b = buffer("> a\n> |");
+        assert!(insert_newline(&mut b));
+        assert_eq!(with_cursor(&b), "> a\n|");
+    }
+
+    #[test]
+    fn test_indent_and_outdent() {
+        let mut b = buffer("1. a\n2. b|\n3. c");
+        assert!(indent_list_items(&mut b, false));
+        assert_eq!(with_cursor(&b), "1. a\n   1. b|\n2. c");
+        assert!(indent_list_items(&mut b, true));
+        assert_eq!(with_cursor(&b), "1. a\n2. b|\n3. c");
+
+        let mut b = buffer("text|");
+        assert!(!indent_list_items(&mut b, false));
+    }
+
+    #[test]
+    fn test_pairs() {
+        let mut b = TextBuffer::from_str("make bold");
+        b.set_selection(5, 9);
+        assert!(insert_pair(&mut b, '*'));
+        assert_eq!(b.content(), "make **bold**");
+        assert_eq!(b.selected_text(), "bold");
+        assert!(insert_pair(&mut b, '`'));
+        assert_eq!(b.content(), "make **`bold`**");
+
+        let mut b = buffer("call|");
+        assert!(insert_pair(&mut b, '('));
+        assert_eq!(with_cursor(&b), "call(|)");
+        assert!(insert_pair(&mut b, ')'));
+        assert_eq!(with_cursor(&b), "call()|");
+
+        let mut b = buffer("[|]");
+        assert!(delete_pair(&mut b));
+        assert_eq!(with_cursor(&b), "|");
+
+        let mut b = buffer("|word");
+        assert!(!insert_pair(&mut b, '('));
+        assert!(!insert_pair(&mut b, '`'));
+    }
+
+    #[test]
+    fn test_tasks() {
+        let text = "# Plan\n- [ ] write\n  * [x] read \n- [ ]\n- plain\n[ ] not a task";
+        let found = tasks(text);
+        assert_eq!(
+            found,
+            vec![
+                Task {
+                    line: 1,
+                    done: false,
+                    text: "write".to_string()
+                },
+                Task {
+                    line: 2,
+                    done: true,
+                    text: "read".to_string()
+                },
+                Task {
+                    line: 3,
+                    done: false,
+                    text: String::new()
+                },
+            ]
+        );
+
+        let mut b = buffer("- [ ] wr|ite");
+        assert!(toggle_task(&mut b, 0));
+        assert_eq!(with_cursor(&b), "- [x] wr|ite");
+        assert!(toggle_task(&mut b, 0));
+        assert_eq!(b.content(), "- [ ] write");
+        assert!(!toggle_task(&mut b, 1));
+    }
+
+    #[test]
+    fn test_headings_and_sections() {
+        let text = "# Title\nintro\n## A ##\n```\n# not a heading\n```\n### A.1\n#no\n## B\nend";
+        let found = headings(text);
+        let summary: Vec<(usize, usize, &str)> = found
+            .iter()
+            .map(|heading| (heading.line, heading.level, heading.text.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [(0, 1, "Title"), (2, 2, "A"), (6, 3, "A.1"), (8, 2, "B")]
+        );
+        assert_eq!(section(&found, 0, 10), (0, 9));
+        assert_eq!(section(&found, 1, 10), (2, 7));
+        assert_eq!(section(&found, 3, 10), (8, 9));
+    }
+}
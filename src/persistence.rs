@@ -1,7 +1,13 @@
+use crate::config;
+use crate::date;
+use crate::front_matter::{self, FrontMatter};
+use crate::links;
+use crate::snippets::Snippet;
+use crate::tags;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Get the data directory for storing notes
 /// - If running from source (binary path contains "target") or FIRE_NOTES_DEV is set: ./tmp/fire-notes
@@ -27,6 +33,13 @@ pub fn get_data_dir() -> PathBuf {
     }
 }
 
+/// Whether a file lives in the data directory, i.e. is a note managed by the app
+/// rather than a file opened from elsewhere
+pub fn is_in_data_dir(path: &Path) -> bool {
+    let canonical = |p: &Path| fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+    canonical(path).starts_with(canonical(&get_data_dir()))
+}
+
 fn is_internal_state_file(path: &PathBuf) -> bool {
     matches!(
         path.file_name().and_then(|name| name.to_str()),
@@ -35,38 +48,344 @@ fn is_internal_state_file(path: &PathBuf) -> bool {
             | Some("window_state.json")
             | Some("session_state.json")
             | Some("note_metadata.json")
+            | Some("note_index.json")
+            | Some("tag_index.json") // Left behind by versions before note_index.json
+            | Some("snippets.json")
+            | Some("dictionary.txt")
     )
 }
 
+/// Title from the note's front matter
+pub fn load_note_title(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    front_matter::parse(&content).0?.title
+}
+
+/// Write a title into the note's front matter, adding front matter if the
+/// note has none
+pub fn save_note_title(path: &Path, title: &str) -> std::io::Result<()> {
+    let content = fs::read_to_string(path)?;
+    let (front_matter, body) = front_matter::parse(&content);
+    let mut front_matter = front_matter.unwrap_or_default();
+    front_matter.title = Some(title.to_string());
+    fs::write(path, front_matter::join(Some(&front_matter), body))
+}
+
+/// Move or rename a note on disk. Fails with `AlreadyExists` rather than
+/// replace another file.
+pub fn move_note(from: &Path, to: &Path) -> std::io::Result<()> {
+    if to.exists() {
+        return Err(std::io::Error::from(std::io::ErrorKind::AlreadyExists));
+    }
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::rename(from, to).is_err() {
+        // rename() cannot cross filesystems; fall back to copy + delete
+        fs::copy(from, to)?;
+        fs::remove_file(from)?;
+    }
+    Ok(())
+}
+
+/// Side-car title store used by older versions, keyed by absolute path
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 struct NoteMetadata {
     titles: HashMap<String, String>,
 }
 
-fn note_metadata_path() -> PathBuf {
-    get_data_dir().join("note_metadata.json")
+/// Copy titles from `note_metadata.json` into the front matter of the notes
+/// in the data directory, then set the JSON file aside so this runs once
+pub fn migrate_note_metadata() -> std::io::Result<()> {
+    let json_path = get_data_dir().join("note_metadata.json");
+    let Ok(payload) = fs::read_to_string(&json_path) else {
+        return Ok(());
+    };
+    let metadata = serde_json::from_str::<NoteMetadata>(&payload).unwrap_or_default();
+    for (path, title) in &metadata.titles {
+        let path = Path::new(path);
+        if path.is_file() && is_in_data_dir(path) && load_note_title(path).is_none() {
+            save_note_title(path, title)?;
+        }
+    }
+    fs::rename(&json_path, get_data_dir().join("note_metadata.json.migrated"))
 }
 
-fn load_note_metadata() -> NoteMetadata {
-    let content = fs::read_to_string(note_metadata_path()).ok();
-    content
-        .and_then(|payload| serde_json::from_str::<NoteMetadata>(&payload).ok())
-        .unwrap_or_default()
+/// Title, tags and outgoing links of every note, cached in
+/// `note_index.json`. Notes are re-read only when their file changed since
+/// the last scan.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NoteIndex {
+    notes: HashMap<PathBuf, IndexedNote>,
 }
 
-pub fn load_note_title(path: &PathBuf) -> Option<String> {
-    let metadata = load_note_metadata();
-    metadata.titles.get(&path.to_string_lossy().to_string()).cloned()
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedNote {
+    /// Modification time in nanoseconds since the Unix epoch
+    modified: u128,
+    /// Title from the front matter
+    title: Option<String>,
+    tags: Vec<String>,
+    /// Normalized `[[link]]` targets
+    links: Vec<String>,
 }
 
-pub fn save_note_title(path: &PathBuf, title: &str) -> std::io::Result<()> {
-    let mut metadata = load_note_metadata();
-    metadata
-        .titles
-        .insert(path.to_string_lossy().to_string(), title.to_string());
-    let payload = serde_json::to_string_pretty(&metadata)
-        .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
-    fs::write(note_metadata_path(), payload)
+fn note_index_path() -> PathBuf {
+    get_data_dir().join("note_index.json")
+}
+
+fn modified_nanos(path: &Path) -> u128 {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_nanos())
+}
+
+/// A note's file name in the form link targets are compared in
+fn file_link_name(path: &Path) -> Option<String> {
+    path.file_stem()
+        .map(|stem| links::normalize(&stem.to_string_lossy()))
+}
+
+impl NoteIndex {
+    /// Load the cached index and bring it up to date with the notes on disk
+    pub fn load() -> Self {
+        let mut index = fs::read_to_string(note_index_path())
+            .ok()
+            .and_then(|payload| serde_json::from_str::<NoteIndex>(&payload).ok())
+            .unwrap_or_default();
+
+        // Templates are full of placeholder tags and links; leave them out
+        let paths: Vec<PathBuf> = list_notes()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|path| !is_template(path))
+            .collect();
+        let mut changed = index.notes.len() != paths.len();
+        index.notes.retain(|path, _| paths.contains(path));
+        for path in paths {
+            let modified = modified_nanos(&path);
+            if index.notes.get(&path).is_some_and(|note| note.modified == modified) {
+                continue;
+            }
+            let content = fs::read_to_string(&path).unwrap_or_default();
+            let (front_matter, body) = front_matter::parse(&content);
+            let note = IndexedNote {
+                modified,
+                title: front_matter.and_then(|front_matter| front_matter.title),
+                tags: tags::note_tags(&content),
+                links: links::note_links(body),
+            };
+            index.notes.insert(path, note);
+            changed = true;
+        }
+
+        if changed {
+            if let Ok(payload) = serde_json::to_string(&index) {
+                let _ = ensure_data_dir().and_then(|_| fs::write(note_index_path(), payload));
+            }
+        }
+        index
+    }
+
+    pub fn title_of(&self, path: &Path) -> Option<&str> {
+        self.notes.get(path).and_then(|note| note.title.as_deref())
+    }
+
+    pub fn tags_of(&self, path: &Path) -> &[String] {
+        self.notes.get(path).map_or(&[], |note| note.tags.as_slice())
+    }
+
+    /// Every tag with the number of notes using it, most used first
+    pub fn tag_counts(&self) -> Vec<(String, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for note in self.notes.values() {
+            for tag in &note.tags {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+        let mut counts: Vec<(String, usize)> = counts
+            .into_iter()
+            .map(|(tag, count)| (tag.to_string(), count))
+            .collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    }
+
+    /// Note a `[[target]]` link points at: a title match wins over a file
+    /// name match
+    pub fn resolve(&self, target: &str) -> Option<&Path> {
+        let target = links::normalize(target);
+        let mut paths: Vec<&PathBuf> = self.notes.keys().collect();
+        paths.sort();
+        let title_match = paths.iter().find(|path| {
+            self.notes[**path]
+                .title
+                .as_deref()
+                .is_some_and(|title| links::normalize(title) == target)
+        });
+        title_match
+            .or_else(|| {
+                paths
+                    .iter()
+                    .find(|path| file_link_name(path).as_ref() == Some(&target))
+            })
+            .map(|path| path.as_path())
+    }
+
+    /// Notes linking to a note by its title or file name, sorted by path.
+    /// The note itself is left out.
+    pub fn linking_to(&self, title: Option<&str>, path: Option<&Path>) -> Vec<&Path> {
+        let names: Vec<String> = title
+            .map(links::normalize)
+            .into_iter()
+            .chain(path.and_then(file_link_name))
+            .filter(|name| !name.is_empty())
+            .collect();
+        let mut linking: Vec<&Path> = self
+            .notes
+            .iter()
+            .filter(|(note_path, note)| {
+                Some(note_path.as_path()) != path
+                    && note.links.iter().any(|link| names.contains(link))
+            })
+            .map(|(note_path, _)| note_path.as_path())
+            .collect();
+        linking.sort();
+        linking
+    }
+
+    /// Notes linking to the note at `path`
+    pub fn backlinks(&self, path: &Path) -> Vec<&Path> {
+        self.linking_to(self.title_of(path), Some(path))
+    }
+}
+
+/// Point `[[from]]` links at `to` in every note on disk except `skip`
+/// (notes open in tabs, which are updated in their buffers instead)
+pub fn retarget_links_in_notes(from: &str, to: &str, skip: &[&PathBuf]) -> std::io::Result<()> {
+    for path in list_notes()? {
+        if skip.contains(&&path) {
+            continue;
+        }
+        let content = fs::read_to_string(&path)?;
+        let (front_matter, body) = front_matter::parse(&content);
+        if let Some(body) = links::retarget(body, from, to) {
+            fs::write(&path, front_matter::join(front_matter.as_ref(), &body))?;
+        }
+    }
+    Ok(())
+}
+
+/// Create an empty note called `title` in the data directory
+pub fn create_note(title: &str) -> std::io::Result<PathBuf> {
+    let dir = ensure_data_dir()?;
+    let path = unique_note_path(&dir, &slugify(title), None);
+    save_note(&path, &new_front_matter(title).render())?;
+    Ok(path)
+}
+
+/// Front matter of a note created now
+fn new_front_matter(title: &str) -> FrontMatter {
+    let mut front_matter = FrontMatter::default();
+    front_matter.title = Some(title.to_string());
+    front_matter.created = Some(date::now_timestamp());
+    front_matter
+}
+
+/// Folder of the data directory holding note templates
+pub fn templates_dir() -> PathBuf {
+    get_data_dir().join(config::templates::FOLDER)
+}
+
+/// Whether a note is a template rather than a regular note
+pub fn is_template(path: &Path) -> bool {
+    path.starts_with(templates_dir())
+}
+
+/// Template files, sorted by path
+pub fn list_templates() -> std::io::Result<Vec<PathBuf>> {
+    let dir = templates_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut templates = Vec::new();
+    collect_notes(&dir, &mut templates, true)?;
+    templates.sort();
+    Ok(templates)
+}
+
+/// Create a note with `title`, the given tags and an already expanded body
+pub fn create_note_from_template(
+    title: &str,
+    tags: Vec<String>,
+    body: &str,
+) -> std::io::Result<PathBuf> {
+    let dir = ensure_data_dir()?;
+    let path = unique_note_path(&dir, &slugify(title), None);
+    let mut front_matter = new_front_matter(title);
+    front_matter.tags = tags;
+    save_note(&path, &front_matter::join(Some(&front_matter), body))?;
+    Ok(path)
+}
+
+/// Folder of the data directory holding daily notes
+pub fn journal_dir() -> PathBuf {
+    get_data_dir().join(config::journal::FOLDER)
+}
+
+/// Where the daily note for `date` lives, whether or not it exists yet
+pub fn journal_note_path(date: date::Date) -> PathBuf {
+    journal_dir().join(format!("{}.md", date.format(config::journal::FILENAME_PATTERN)))
+}
+
+/// Day of a daily note, or None for any other file
+pub fn journal_date(path: &Path) -> Option<date::Date> {
+    if path.parent()? != journal_dir() || path.extension()? != "md" {
+        return None;
+    }
+    let stem = path.file_stem()?.to_str()?;
+    date::Date::parse(config::journal::FILENAME_PATTERN, stem)
+}
+
+/// Days that have a daily note, oldest first
+pub fn journal_dates() -> Vec<date::Date> {
+    let Ok(entries) = fs::read_dir(journal_dir()) else {
+        return Vec::new();
+    };
+    let mut dates: Vec<date::Date> = entries
+        .filter_map(|entry| journal_date(&entry.ok()?.path()))
+        .collect();
+    dates.sort();
+    dates
+}
+
+/// Path of the daily note for `date`, creating it from the journal
+/// template when it does not exist yet
+pub fn create_journal_note(date: date::Date) -> std::io::Result<PathBuf> {
+    let path = journal_note_path(date);
+    if !path.exists() {
+        let front_matter = new_front_matter(&date.format(config::journal::TITLE_PATTERN));
+        let body = date.format(config::journal::TEMPLATE);
+        save_note(&path, &front_matter::join(Some(&front_matter), &body))?;
+    }
+    Ok(path)
+}
+
+/// Rename a tag in every note on disk except `skip` (notes open in tabs,
+/// which are renamed in their buffers instead)
+pub fn rename_tag_in_notes(from: &str, to: &str, skip: &[&PathBuf]) -> std::io::Result<()> {
+    for path in list_notes()? {
+        if skip.contains(&&path) {
+            continue;
+        }
+        let content = fs::read_to_string(&path)?;
+        if let Some(content) = tags::rename_in_note(&content, from, to) {
+            fs::write(&path, content)?;
+        }
+    }
+    Ok(())
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -77,6 +396,56 @@ pub struct WindowState {
     pub height: u32,
 }
 
+fn snippets_path() -> PathBuf {
+    get_data_dir().join("snippets.json")
+}
+
+/// User snippets from `snippets.json`. The file is created with the
+/// default snippets when missing so there is something to edit.
+pub fn load_snippets() -> Vec<Snippet> {
+    if let Ok(payload) = fs::read_to_string(snippets_path()) {
+        return serde_json::from_str(&payload).unwrap_or_default();
+    }
+    let defaults: Vec<Snippet> = config::snippets::DEFAULTS
+        .iter()
+        .map(|(trigger, body)| Snippet {
+            trigger: trigger.to_string(),
+            body: body.to_string(),
+        })
+        .collect();
+    if let Ok(payload) = serde_json::to_string_pretty(&defaults) {
+        let _ = ensure_data_dir().and_then(|_| fs::write(snippets_path(), payload));
+    }
+    defaults
+}
+
+fn personal_dictionary_path() -> PathBuf {
+    get_data_dir().join("dictionary.txt")
+}
+
+/// Words the user added to the spell checker, one per line
+pub fn load_personal_dictionary() -> Vec<String> {
+    fs::read_to_string(personal_dictionary_path())
+        .map(|text| {
+            text.lines()
+                .map(str::trim)
+                .filter(|word| !word.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn add_to_personal_dictionary(word: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    ensure_data_dir()?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(personal_dictionary_path())?;
+    writeln!(file, "{word}")
+}
+
 fn window_state_path() -> PathBuf {
     get_data_dir().join("window_state.json")
 }
@@ -127,12 +496,18 @@ pub struct TabState {
     pub scroll_offset: usize,
     pub scroll_offset_x: f32,
     pub word_wrap: bool,
+    /// Per-tab auto-save override; None falls back to the default for the path
+    #[serde(default)]
+    pub auto_save: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionState {
     pub active_path: Option<PathBuf>,
     pub tabs: Vec<TabState>,
+    /// Whether spell checking (F7) was on
+    #[serde(default)]
+    pub spell_check: bool,
 }
 
 fn session_state_path() -> PathBuf {
@@ -181,6 +556,7 @@ pub fn load_session_state() -> Option<SessionState> {
                     scroll_offset,
                     scroll_offset_x,
                     word_wrap,
+                    auto_save: None,
                 });
             }
             _ => {}
@@ -191,7 +567,11 @@ pub fn load_session_state() -> Option<SessionState> {
         return None;
     }
 
-    Some(SessionState { active_path, tabs })
+    Some(SessionState {
+        active_path,
+        tabs,
+        spell_check: false,
+    })
 }
 
 pub fn save_session_state(state: &SessionState) -> std::io::Result<()> {
@@ -212,7 +592,7 @@ pub fn ensure_data_dir() -> std::io::Result<PathBuf> {
     Ok(dir)
 }
 
-/// List all note files in the data directory
+/// List all note files in the data directory and its subfolders
 pub fn list_notes() -> std::io::Result<Vec<PathBuf>> {
     let dir = get_data_dir();
     if !dir.exists() {
@@ -220,26 +600,97 @@ pub fn list_notes() -> std::io::Result<Vec<PathBuf>> {
     }
 
     let mut notes = Vec::new();
-    for entry in fs::read_dir(&dir)? {
+    collect_notes(&dir, &mut notes, true)?;
+    notes.sort();
+    Ok(notes)
+}
+
+/// List the note files directly in the data directory, leaving out folders
+pub fn list_top_level_notes() -> std::io::Result<Vec<PathBuf>> {
+    let dir = get_data_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut notes = Vec::new();
+    collect_notes(&dir, &mut notes, false)?;
+    notes.sort();
+    Ok(notes)
+}
+
+fn collect_notes(dir: &Path, notes: &mut Vec<PathBuf>, recursive: bool) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
         let entry = entry?;
         let path = entry.path();
-        if path.is_file()
+        if is_hidden(&path) {
+            continue;
+        }
+        // Symlinked folders are not followed, so a link loop or a link to
+        // somewhere large cannot run away
+        if entry.file_type()?.is_dir() {
+            if recursive {
+                collect_notes(&path, notes, true)?;
+            }
+        } else if path.is_file()
             && path.extension().map_or(false, |e| e == "md" || e == "txt")
             && !is_internal_state_file(&path)
         {
             notes.push(path);
         }
     }
-    notes.sort();
-    Ok(notes)
+    Ok(())
 }
 
-/// Save a note to the data directory
-pub fn save_note(filename: &str, content: &str) -> std::io::Result<PathBuf> {
-    let dir = ensure_data_dir()?;
-    let path = dir.join(filename);
-    fs::write(&path, content)?;
-    Ok(path)
+/// List all folders below the data directory (not including the root itself)
+pub fn list_folders() -> std::io::Result<Vec<PathBuf>> {
+    let dir = get_data_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut folders = Vec::new();
+    collect_folders(&dir, &mut folders)?;
+    folders.sort();
+    Ok(folders)
+}
+
+fn collect_folders(dir: &Path, folders: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        // As in `collect_notes`, symlinked folders are not followed
+        if entry.file_type()?.is_dir() && !is_hidden(&path) {
+            folders.push(path.clone());
+            collect_folders(&path, folders)?;
+        }
+    }
+    Ok(())
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.'))
+}
+
+/// Folder of a note relative to the data directory ("work/projects"), or
+/// None for notes at the top level and files outside the data directory
+pub fn note_folder_label(path: &Path) -> Option<String> {
+    let folder = path.parent()?.strip_prefix(get_data_dir()).ok()?;
+    let label = folder
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    (!label.is_empty()).then_some(label)
+}
+
+/// Write a note, creating its folder if needed
+pub fn save_note(path: &Path, content: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, content)
 }
 
 /// Load a note from the data directory
@@ -248,11 +699,181 @@ pub fn load_note(path: &PathBuf) -> std::io::Result<String> {
     fs::read_to_string(path)
 }
 
-/// Generate a unique filename for a new note
-pub fn generate_note_filename() -> String {
-    let timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0);
-    format!("note_{}.md", timestamp)
+/// Titles new tabs start with until the user names them
+pub fn is_placeholder_title(title: &str) -> bool {
+    title.is_empty() || title.starts_with("Untitled-")
+}
+
+/// Turn text into a file name stem: lowercase words joined by dashes
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for ch in text.chars().flat_map(char::to_lowercase) {
+        if ch.is_alphanumeric() {
+            slug.push(ch);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug.chars().take(config::notes::MAX_SLUG_CHARS).collect();
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "note".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// File name stem for a note: its title, or its first heading while the
+/// title is still a placeholder
+pub fn note_slug(title: &str, content: &str) -> String {
+    if !is_placeholder_title(title) {
+        return slugify(title);
+    }
+    let heading = content
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with('#'))
+        .map(|line| line.trim_start_matches('#').trim())
+        .filter(|heading| !heading.is_empty());
+    slugify(heading.unwrap_or(title))
+}
+
+/// First free `<slug>.md`, `<slug>-2.md`, ... in `dir`. `current` is the
+/// note's own path, which counts as free so renaming to the same name is a
+/// no-op.
+pub fn unique_note_path(dir: &Path, slug: &str, current: Option<&Path>) -> PathBuf {
+    let mut n = 1;
+    loop {
+        let name = if n == 1 {
+            format!("{slug}.md")
+        } else {
+            format!("{slug}-{n}.md")
+        };
+        let candidate = dir.join(name);
+        if current == Some(candidate.as_path()) || !candidate.exists() {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
+/// Names produced by older versions: `note_<unix seconds>.md`
+fn is_timestamp_note_name(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_prefix("note_"))
+        .and_then(|rest| rest.strip_suffix(".md"))
+        .is_some_and(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Rename `note_<timestamp>.md` files left by older versions after their
+/// title or first heading. The saved session follows the renames. A file
+/// that cannot be renamed keeps its name and the others are still renamed;
+/// the first such error is returned.
+pub fn migrate_timestamp_note_names() -> std::io::Result<()> {
+    let mut renames = Vec::new();
+    let mut failure = None;
+    for path in list_notes()? {
+        if !is_timestamp_note_name(&path) {
+            continue;
+        }
+        let Some(dir) = path.parent() else {
+            continue;
+        };
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        let (front_matter, body) = front_matter::parse(&content);
+        let title = front_matter.and_then(|front_matter| front_matter.title).unwrap_or_default();
+        let new = unique_note_path(dir, &note_slug(&title, body), None);
+        match move_note(&path, &new) {
+            Ok(()) => renames.push((path, new)),
+            Err(err) => {
+                failure.get_or_insert(err);
+            }
+        }
+    }
+
+    follow_renames_in_session(&renames)?;
+    failure.map_or(Ok(()), Err)
+}
+
+/// Point the saved session at the new paths of renamed notes
+fn follow_renames_in_session(renames: &[(PathBuf, PathBuf)]) -> std::io::Result<()> {
+    if renames.is_empty() {
+        return Ok(());
+    }
+    let Some(mut session) = load_session_state() else {
+        return Ok(());
+    };
+    let renamed = |path: &PathBuf| {
+        renames
+            .iter()
+            .find(|(old, _)| old == path)
+            .map(|(_, new)| new.clone())
+    };
+    for tab in &mut session.tabs {
+        if let Some(new) = renamed(&tab.path) {
+            tab.path = new;
+        }
+    }
+    if let Some(new) = session.active_path.as_ref().and_then(renamed) {
+        session.active_path = Some(new);
+    }
+    save_session_state(&session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Meeting Notes: Q3 / 2024"), "meeting-notes-q3-2024");
+        assert_eq!(slugify("  Café déjà vu!  "), "café-déjà-vu");
+        assert_eq!(slugify("???"), "note");
+        assert_eq!(slugify(&"a".repeat(200)).len(), config::notes::MAX_SLUG_CHARS);
+    }
+
+    #[test]
+    fn test_note_slug_prefers_title_then_heading() {
+        assert_eq!(note_slug("Groceries", "# Shopping"), "groceries");
+        assert_eq!(note_slug("Untitled-3", "intro\n## Shopping list\n"), "shopping-list");
+        assert_eq!(note_slug("Untitled-3", "no heading"), "untitled-3");
+    }
+
+    #[test]
+    fn test_timestamp_note_names() {
+        assert!(is_timestamp_note_name(Path::new("/d/note_1700000000.md")));
+        assert!(!is_timestamp_note_name(Path::new("/d/note_ideas.md")));
+        assert!(!is_timestamp_note_name(Path::new("/d/note_.md")));
+        assert!(!is_timestamp_note_name(Path::new("/d/note_123.txt")));
+    }
+
+    #[test]
+    fn test_move_note_keeps_existing_target() {
+        let dir = std::env::temp_dir().join(format!("fire-notes-move-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (from, to) = (dir.join("a.md"), dir.join("b.md"));
+        fs::write(&from, "a").unwrap();
+        fs::write(&to, "b").unwrap();
+        let result = move_note(&from, &to);
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&to).unwrap(), "b");
+        assert!(from.exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_collect_notes_top_level_only() {
+        let dir = std::env::temp_dir().join(format!("fire-notes-list-{}", std::process::id()));
+        fs::create_dir_all(dir.join("work")).unwrap();
+        fs::write(dir.join("a.md"), "a").unwrap();
+        fs::write(dir.join("work").join("b.md"), "b").unwrap();
+        let mut notes = Vec::new();
+        collect_notes(&dir, &mut notes, false).unwrap();
+        assert_eq!(notes, [dir.join("a.md")]);
+        notes.clear();
+        collect_notes(&dir, &mut notes, true).unwrap();
+        assert_eq!(notes.len(), 2);
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
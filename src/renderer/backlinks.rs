@@ -0,0 +1,137 @@
+//! Backlinks panel rendering
+
+use crate::config::layout;
+use crate::theme::Theme;
+use crate::ui::{backlinks_list_top, backlinks_rect, BacklinksPanel};
+use femtovg::{renderer::OpenGl, Canvas, Color, FontId, Paint, Path};
+
+pub struct BacklinksRenderer<'a> {
+    canvas: &'a mut Canvas<OpenGl>,
+    fonts: &'a [FontId],
+    theme: &'a Theme,
+    width: f32,
+    height: f32,
+    scale: f32,
+}
+
+impl<'a> BacklinksRenderer<'a> {
+    pub fn new(
+        canvas: &'a mut Canvas<OpenGl>,
+        fonts: &'a [FontId],
+        theme: &'a Theme,
+        width: f32,
+        height: f32,
+        scale: f32,
+    ) -> Self {
+        Self {
+            canvas,
+            fonts,
+            theme,
+            width,
+            height,
+            scale,
+        }
+    }
+
+    pub fn draw(&mut self, panel: &BacklinksPanel) {
+        let scale = self.scale;
+        let rect = backlinks_rect(self.width, self.height, scale);
+
+        // Background and divider
+        let mut bg = Path::new();
+        bg.rect(rect.x, rect.y, rect.width, rect.height);
+        self.canvas.fill_path(
+            &bg,
+            &Paint::color(Color::rgbf(
+                self.theme.tab_inactive.0,
+                self.theme.tab_inactive.1,
+                self.theme.tab_inactive.2,
+            )),
+        );
+        let mut divider = Path::new();
+        divider.move_to(rect.x + 0.5 * scale, rect.y);
+        divider.line_to(rect.x + 0.5 * scale, rect.y + rect.height);
+        self.canvas.stroke_path(
+            &divider,
+            &Paint::color(Color::rgbf(
+                self.theme.border.0,
+                self.theme.border.1,
+                self.theme.border.2,
+            ))
+            .with_line_width(1.0 * scale),
+        );
+
+        self.canvas.save();
+        self.canvas.scissor(rect.x, rect.y, rect.width, rect.height);
+
+        let row_height = layout::SIDEBAR_ROW_HEIGHT * scale;
+        let text_padding = 10.0 * scale;
+        let font_size = 13.0 * scale;
+        let text_x = rect.x + text_padding;
+
+        // Header with the number of linking notes
+        let mut header_paint = Paint::color(Color::rgba(150, 150, 150, 200));
+        header_paint.set_font(self.fonts);
+        header_paint.set_font_size(font_size * 0.9);
+        let header_y = rect.y + layout::PADDING * 0.5 * scale + row_height / 2.0 + font_size * 0.35;
+        let header = format!("BACKLINKS ({})", panel.entries().len());
+        let _ = self
+            .canvas
+            .fill_text(text_x, header_y, &header, &header_paint);
+
+        let list_top = backlinks_list_top(rect, scale);
+        if panel.entries().is_empty() {
+            let message = if panel.note().is_some() {
+                "No notes link here"
+            } else {
+                "Save the note to see backlinks"
+            };
+            let text_y = list_top + row_height / 2.0 + font_size * 0.35;
+            let _ = self
+                .canvas
+                .fill_text(text_x, text_y, message, &header_paint);
+        }
+
+        let visible_rows = ((rect.y + rect.height - list_top) / row_height).ceil() as usize;
+        for (index, (_, title)) in panel
+            .entries()
+            .iter()
+            .enumerate()
+            .skip(panel.scroll_offset())
+            .take(visible_rows)
+        {
+            let row_y = list_top + (index - panel.scroll_offset()) as f32 * row_height;
+
+            if panel.hovered() == Some(index) {
+                let mut highlight = Path::new();
+                highlight.rounded_rect(
+                    rect.x + 4.0 * scale,
+                    row_y,
+                    rect.width - 8.0 * scale,
+                    row_height - 2.0 * scale,
+                    4.0 * scale,
+                );
+                self.canvas.fill_path(
+                    &highlight,
+                    &Paint::color(Color::rgbf(
+                        self.theme.tab_active_border.0 * 0.15,
+                        self.theme.tab_active_border.1 * 0.15,
+                        self.theme.tab_active_border.2 * 0.15,
+                    )),
+                );
+            }
+
+            let mut paint = Paint::color(Color::rgbf(
+                self.theme.link.0,
+                self.theme.link.1,
+                self.theme.link.2,
+            ));
+            paint.set_font(self.fonts);
+            paint.set_font_size(font_size);
+            let text_y = row_y + row_height / 2.0 + font_size * 0.35;
+            let _ = self.canvas.fill_text(text_x, text_y, title, &paint);
+        }
+
+        self.canvas.restore();
+    }
+}
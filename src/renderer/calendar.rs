@@ -0,0 +1,151 @@
+//! Journal calendar overlay rendering
+
+use crate::config::layout;
+use crate::date;
+use crate::theme::Theme;
+use crate::ui::{calendar_grid_left, calendar_grid_top, calendar_rect, Calendar};
+use femtovg::{renderer::OpenGl, Align, Canvas, Color, FontId, Paint, Path};
+
+const WEEKDAY_LABELS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+pub struct CalendarRenderer<'a> {
+    canvas: &'a mut Canvas<OpenGl>,
+    fonts: &'a [FontId],
+    theme: &'a Theme,
+    width: f32,
+    height: f32,
+    scale: f32,
+}
+
+impl<'a> CalendarRenderer<'a> {
+    pub fn new(
+        canvas: &'a mut Canvas<OpenGl>,
+        fonts: &'a [FontId],
+        theme: &'a Theme,
+        width: f32,
+        height: f32,
+        scale: f32,
+    ) -> Self {
+        Self {
+            canvas,
+            fonts,
+            theme,
+            width,
+            height,
+            scale,
+        }
+    }
+
+    pub fn draw(&mut self, calendar: &Calendar) {
+        let scale = self.scale;
+        let rect = calendar_rect(self.width, scale);
+        let cell = layout::CALENDAR_CELL_SIZE * scale;
+        let font_size = 14.0 * scale;
+        let accent = self.theme.tab_active_border;
+
+        // Backdrop, background and border, as for the notes picker
+        let mut backdrop = Path::new();
+        backdrop.rect(0.0, 0.0, self.width, self.height);
+        self.canvas
+            .fill_path(&backdrop, &Paint::color(Color::rgba(0, 0, 0, 120)));
+
+        let mut bg = Path::new();
+        bg.rounded_rect(rect.x, rect.y, rect.width, rect.height, 8.0 * scale);
+        self.canvas.fill_path(
+            &bg,
+            &Paint::color(Color::rgbf(
+                self.theme.tab_inactive.0,
+                self.theme.tab_inactive.1,
+                self.theme.tab_inactive.2,
+            )),
+        );
+        self.canvas.stroke_path(
+            &bg,
+            &Paint::color(Color::rgbf(accent.0, accent.1, accent.2)).with_line_width(2.0),
+        );
+
+        let mut text_paint = Paint::color(Color::rgbf(
+            self.theme.fg.0,
+            self.theme.fg.1,
+            self.theme.fg.2,
+        ));
+        text_paint.set_font(self.fonts);
+        text_paint.set_font_size(font_size);
+        text_paint.set_text_align(Align::Center);
+        let mut dim_paint = Paint::color(Color::rgba(150, 150, 150, 200));
+        dim_paint.set_font(self.fonts);
+        dim_paint.set_font_size(font_size * 0.9);
+        dim_paint.set_text_align(Align::Center);
+
+        let grid_left = calendar_grid_left(rect, scale);
+        let grid_top = calendar_grid_top(rect, scale);
+        let center_y = |top: f32| top + cell / 2.0 + font_size * 0.35;
+
+        // Month title and weekday names
+        let month = calendar.month();
+        let title = format!("{} {}", date::month_name(month.month), month.year);
+        let _ = self.canvas.fill_text(
+            rect.x + rect.width / 2.0,
+            center_y(grid_top - 2.0 * cell),
+            &title,
+            &text_paint,
+        );
+        for (column, label) in WEEKDAY_LABELS.iter().enumerate() {
+            let x = grid_left + (column as f32 + 0.5) * cell;
+            let _ = self
+                .canvas
+                .fill_text(x, center_y(grid_top - cell), label, &dim_paint);
+        }
+
+        // Days: the selection is filled, today is outlined and days with a
+        // note are drawn in the link colour with a dot
+        for (index, day, is_selected) in calendar.days().visible_items() {
+            let slot = calendar.slot_of(index);
+            let x = grid_left + (slot % 7) as f32 * cell;
+            let y = grid_top + (slot / 7) as f32 * cell;
+            let inset = 3.0 * scale;
+
+            if is_selected || day.date == calendar.today() {
+                let mut highlight = Path::new();
+                highlight.rounded_rect(
+                    x + inset,
+                    y + inset,
+                    cell - 2.0 * inset,
+                    cell - 2.0 * inset,
+                    4.0 * scale,
+                );
+                if is_selected {
+                    self.canvas.fill_path(
+                        &highlight,
+                        &Paint::color(Color::rgbf(accent.0 * 0.3, accent.1 * 0.3, accent.2 * 0.3)),
+                    );
+                }
+                if day.date == calendar.today() {
+                    self.canvas.stroke_path(
+                        &highlight,
+                        &Paint::color(Color::rgbf(accent.0, accent.1, accent.2))
+                            .with_line_width(1.0 * scale),
+                    );
+                }
+            }
+
+            let mut paint = text_paint.clone();
+            if day.has_note {
+                paint.set_color(Color::rgbf(
+                    self.theme.link.0,
+                    self.theme.link.1,
+                    self.theme.link.2,
+                ));
+                let mut dot = Path::new();
+                dot.circle(x + cell / 2.0, y + cell - 8.0 * scale, 2.0 * scale);
+                self.canvas.fill_path(&dot, &paint);
+            }
+            let _ = self.canvas.fill_text(
+                x + cell / 2.0,
+                center_y(y) - 2.0 * scale,
+                day.date.day.to_string(),
+                &paint,
+            );
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Context menu popup rendering
+
+use crate::theme::Theme;
+use crate::ui::ContextMenu;
+use femtovg::{renderer::OpenGl, Canvas, Color, FontId, Paint, Path};
+
+pub struct ContextMenuRenderer<'a> {
+    canvas: &'a mut Canvas<OpenGl>,
+    fonts: &'a [FontId],
+    theme: &'a Theme,
+    scale: f32,
+}
+
+impl<'a> ContextMenuRenderer<'a> {
+    pub fn new(
+        canvas: &'a mut Canvas<OpenGl>,
+        fonts: &'a [FontId],
+        theme: &'a Theme,
+        scale: f32,
+    ) -> Self {
+        Self {
+            canvas,
+            fonts,
+            theme,
+            scale,
+        }
+    }
+
+    pub fn draw<A>(&mut self, menu: &ContextMenu<A>) {
+        let scale = self.scale;
+        let geometry = menu.geometry();
+        let rect = geometry.rect;
+
+        // Background and border
+        let mut bg = Path::new();
+        bg.rounded_rect(rect.x, rect.y, rect.width, rect.height, 6.0 * scale);
+        self.canvas.fill_path(
+            &bg,
+            &Paint::color(Color::rgbf(
+                self.theme.tab_inactive.0,
+                self.theme.tab_inactive.1,
+                self.theme.tab_inactive.2,
+            )),
+        );
+        self.canvas.stroke_path(
+            &bg,
+            &Paint::color(Color::rgbf(
+                self.theme.tab_active_border.0,
+                self.theme.tab_active_border.1,
+                self.theme.tab_active_border.2,
+            ))
+            .with_line_width(1.0 * scale),
+        );
+
+        let font_size = 13.0 * scale;
+        let text_padding = 12.0 * scale;
+
+        for (index, item) in menu.items().iter().enumerate() {
+            let item_rect = geometry.item_rect(index);
+
+            // Highlight the hovered / keyboard-selected item
+            if menu.selected_index() == Some(index) {
+                let mut highlight = Path::new();
+                highlight.rounded_rect(
+                    item_rect.x + 4.0 * scale,
+                    item_rect.y,
+                    item_rect.width - 8.0 * scale,
+                    item_rect.height,
+                    4.0 * scale,
+                );
+                self.canvas.fill_path(
+                    &highlight,
+                    &Paint::color(Color::rgbf(
+                        self.theme.tab_active_border.0 * 0.3,
+                        self.theme.tab_active_border.1 * 0.3,
+                        self.theme.tab_active_border.2 * 0.3,
+                    )),
+                );
+            }
+
+            let label_color = if item.enabled {
+                Color::rgbf(self.theme.fg.0, self.theme.fg.1, self.theme.fg.2)
+            } else {
+                Color::rgba(150, 150, 150, 120)
+            };
+            let mut label_paint = Paint::color(label_color);
+            label_paint.set_font(self.fonts);
+            label_paint.set_font_size(font_size);
+
+            let text_y = item_rect.y + item_rect.height / 2.0 + font_size * 0.35;
+            let _ = self.canvas.fill_text(
+                item_rect.x + text_padding,
+                text_y,
+                &item.label,
+                &label_paint,
+            );
+
+            // Shortcut hint, right-aligned and dimmed
+            if let Some(shortcut) = item.shortcut {
+                let mut shortcut_paint = Paint::color(Color::rgba(150, 150, 150, 180));
+                shortcut_paint.set_font(self.fonts);
+                shortcut_paint.set_font_size(font_size * 0.9);
+                let shortcut_width = self
+                    .canvas
+                    .measure_text(0.0, 0.0, shortcut, &shortcut_paint)
+                    .map(|metrics| metrics.width())
+                    .unwrap_or(0.0);
+                let _ = self.canvas.fill_text(
+                    item_rect.x + item_rect.width - text_padding - shortcut_width,
+                    text_y,
+                    shortcut,
+                    &shortcut_paint,
+                );
+            }
+        }
+    }
+}
@@ -1,70 +1,167 @@
 //! Font loading and discovery
+//!
+//! Font families are resolved through fontconfig (`fc-match`), so the editor
+//! picks up whatever the system maps a family name to. The primary family and
+//! fallback chain come from `config::fonts`, and can be overridden with the
+//! `FIRE_NOTES_FONT` environment variable (comma-separated family names).
+//! A fixed list of well-known paths is kept as a last resort for systems
+//! without fontconfig.
 
+use crate::config::fonts as cfg;
 use femtovg::{Canvas, FontId, renderer::OpenGl};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Environment variable overriding the configured font family chain
+const FONT_ENV_VAR: &str = "FIRE_NOTES_FONT";
+
+/// Paths tried when fontconfig is unavailable or resolves nothing loadable
+const LAST_RESORT_PATHS: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf",
+    "/usr/share/fonts/truetype/liberation/LiberationMono-Regular.ttf",
+    "/usr/share/fonts/truetype/ubuntu/UbuntuMono-R.ttf",
+    "/usr/share/fonts/TTF/DejaVuSansMono.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSansMono.ttf",
+    "/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+];
 
 /// Load fonts with fallbacks for the editor
+///
+/// The first returned font is the primary (content) font; the rest are used
+/// by femtovg for glyphs the primary font does not cover.
 pub fn load_fonts(canvas: &mut Canvas<OpenGl>) -> Vec<FontId> {
     let mut fonts = Vec::new();
+    let mut loaded_paths: Vec<PathBuf> = Vec::new();
 
-    // 1. Try common monospace font paths on Linux
-    let mono_paths = [
-        "/usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf",
-        "/usr/share/fonts/truetype/liberation/LiberationMono-Regular.ttf",
-        "/usr/share/fonts/truetype/ubuntu/UbuntuMono-R.ttf",
-        "/usr/share/fonts/TTF/DejaVuSansMono.ttf",
-        "/usr/share/fonts/dejavu/DejaVuSansMono.ttf",
-    ];
-
-    for path in &mono_paths {
-        if let Ok(font) = canvas.add_font(path) {
+    // 1. Resolve the configured family chain through fontconfig
+    for family in family_chain() {
+        let Some(path) = resolve_family(&family) else {
+            continue;
+        };
+        // fontconfig maps unknown families to its default font, so several
+        // entries of the chain may resolve to the same file
+        if loaded_paths.contains(&path) {
+            continue;
+        }
+        if let Ok(font) = canvas.add_font(&path) {
             fonts.push(font);
-            break; // Use the first available monospace font
+            loaded_paths.push(path);
         }
     }
 
-    // 2. Add fallback fonts for extended coverage (Cyrillic, CJK, etc.)
-    // These might not be monospace, but better than a box.
-    let fallback_paths = [
-        "/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf", // Excellent fallback
-        "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",           // Good generic coverage
-        "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
-        "/usr/share/fonts/TTF/DejaVuSans.ttf",
-    ];
-
-    for path in &fallback_paths {
-        if let Ok(font) = canvas.add_font(path) {
-            // Avoid adding duplicates if we somehow loaded the same file?
-            // FontId is unique per add_font call usually.
+    // 2. Well-known paths, in case fontconfig is missing
+    for path in LAST_RESORT_PATHS.iter().map(PathBuf::from) {
+        if loaded_paths.contains(&path) {
+            continue;
+        }
+        if let Ok(font) = canvas.add_font(&path) {
             fonts.push(font);
+            loaded_paths.push(path);
         }
     }
 
     // 3. Fallback: if no fonts loaded at all, try to find any TTF
     if fonts.is_empty() {
-        if let Ok(entries) = std::fs::read_dir("/usr/share/fonts/truetype") {
-            for entry in entries.flatten() {
-                if entry.path().is_dir() {
-                    if let Ok(sub_entries) = std::fs::read_dir(entry.path()) {
-                        for sub_entry in sub_entries.flatten() {
-                            let path = sub_entry.path();
-                            if path.extension().map(|e| e == "ttf").unwrap_or(false) {
-                                if let Ok(font) = canvas.add_font(path) {
-                                    fonts.push(font);
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+        if let Some(font) = load_any_ttf(canvas, Path::new("/usr/share/fonts/truetype")) {
+            fonts.push(font);
         }
     }
 
     if fonts.is_empty() {
         panic!(
-            "No suitable font found! Please install dejavu-fonts, liberation-fonts, or fonts-droid-fallback."
+            "No suitable font found! Please install fontconfig with dejavu-fonts, liberation-fonts, or fonts-droid-fallback."
         );
     }
 
     fonts
 }
+
+/// The ordered list of font families to load (primary first)
+fn family_chain() -> Vec<String> {
+    match std::env::var(FONT_ENV_VAR) {
+        Ok(value) if !value.trim().is_empty() => parse_family_list(&value),
+        _ => std::iter::once(cfg::FAMILY)
+            .chain(cfg::FALLBACK_FAMILIES.iter().copied())
+            .map(str::to_string)
+            .collect(),
+    }
+}
+
+/// Parse a comma-separated list of family names
+fn parse_family_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|family| !family.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Ask fontconfig for the font file that best matches a family name
+fn resolve_family(family: &str) -> Option<PathBuf> {
+    let output = Command::new("fc-match")
+        .arg("--format=%{file}")
+        .arg(family)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_fc_match_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Extract the font path from `fc-match --format=%{file}` output
+fn parse_fc_match_output(output: &str) -> Option<PathBuf> {
+    let path = output.lines().next()?.trim();
+    if path.is_empty() {
+        None
+    } else {
+        Some(PathBuf::from(path))
+    }
+}
+
+/// Load the first TTF file found one level below `dir`
+fn load_any_ttf(canvas: &mut Canvas<OpenGl>, dir: &Path) -> Option<FontId> {
+    for entry in std::fs::read_dir(dir).ok()?.flatten() {
+        if !entry.path().is_dir() {
+            continue;
+        }
+        let Ok(sub_entries) = std::fs::read_dir(entry.path()) else {
+            continue;
+        };
+        for sub_entry in sub_entries.flatten() {
+            let path = sub_entry.path();
+            if path.extension().map(|e| e == "ttf").unwrap_or(false) {
+                if let Ok(font) = canvas.add_font(path) {
+                    return Some(font);
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_family_list() {
+        assert_eq!(
+            parse_family_list(" Fira Code , ,Noto Sans CJK JP"),
+            vec!["Fira Code".to_string(), "Noto Sans CJK JP".to_string()]
+        );
+        assert!(parse_family_list("  ").is_empty());
+    }
+
+    #[test]
+    fn test_parse_fc_match_output() {
+        assert_eq!(
+            parse_fc_match_output("/usr/share/fonts/DejaVuSansMono.ttf"),
+            Some(PathBuf::from("/usr/share/fonts/DejaVuSansMono.ttf"))
+        );
+        assert_eq!(parse_fc_match_output(""), None);
+    }
+}
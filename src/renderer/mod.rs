@@ -1,24 +1,74 @@
 //! GPU-accelerated rendering with femtovg
 
+mod backlinks;
+mod calendar;
+mod context_menu;
 mod flame;
 mod fonts;
 mod notes_picker;
+mod sidebar;
+mod stats;
+mod status_bar;
 mod tab_bar;
 mod text_content;
 pub mod viewport;
 
-use crate::app::NoteEntry;
+use crate::app::{Action, ImePreedit, NotesPickerView};
+use crate::config::{rendering, zoom};
+use crate::spelling::SpellChecker;
+use crate::stats::Stats;
 use crate::tab::Tab;
+use crate::text_layout::{LayoutMetrics, LineLayout, LineLayoutCache};
 use crate::theme::Theme;
-use crate::ui::{ListWidget, TextInput};
+use crate::ui::{
+    backlinks_rect, sidebar_rect, status_bar_rect, BacklinksPanel, Calendar, ContextMenu,
+    NotebookTree, Rect, StatusInfo, TextInput,
+};
 use femtovg::{Canvas, Color, FontId, Paint, renderer::OpenGl};
+use std::rc::Rc;
 use std::time::Instant;
 
+use backlinks::BacklinksRenderer;
+use calendar::CalendarRenderer;
+use context_menu::ContextMenuRenderer;
 use flame::FlameSystem;
 use notes_picker::NotesPickerRenderer;
+use sidebar::SidebarRenderer;
+use stats::StatsRenderer;
+use status_bar::StatusBarRenderer;
 use tab_bar::TabBarRenderer;
 use text_content::TextContentRenderer;
 
+/// Everything the app shows in one frame, gathered so the renderers take a
+/// single argument instead of one per hover flag and panel
+pub struct RenderState<'a> {
+    pub tabs: &'a [(&'a str, bool)],
+    pub modified_tabs: &'a [bool],
+    pub current_tab: &'a Tab,
+    pub cursor_visible: bool,
+    pub hovered_tab_index: Option<usize>,
+    pub hovered_tab_close: Option<usize>,
+    pub hovered_plus: bool,
+    pub hovered_scrollbar: bool,
+    pub dragging_scrollbar: bool,
+    pub renaming_tab: Option<usize>,
+    pub rename_input: Option<&'a TextInput>,
+    pub typing_flame_positions: &'a [(usize, usize, Instant)],
+    pub hovered_window_minimize: bool,
+    pub hovered_window_maximize: bool,
+    pub hovered_window_close: bool,
+    pub notes_picker: Option<NotesPickerView<'a>>,
+    pub ime_preedit: Option<&'a ImePreedit>,
+    pub context_menu: Option<&'a ContextMenu<Action>>,
+    pub sidebar: Option<&'a NotebookTree>,
+    pub backlinks: Option<&'a BacklinksPanel>,
+    pub journal_calendar: Option<&'a Calendar>,
+    pub statistics: Option<(&'a Stats, bool)>,
+    pub spell_checker: Option<&'a SpellChecker>,
+    /// Spell checking is on but no system dictionary was found
+    pub no_dictionary: bool,
+}
+
 pub struct Renderer {
     canvas: Canvas<OpenGl>,
     fonts: Vec<FontId>,
@@ -26,9 +76,11 @@ pub struct Renderer {
     width: f32,
     height: f32,
     scale: f32,
+    zoom: f32,
     tab_scroll_x: f32,
     flame_system: FlameSystem,
     animation_start: Instant,
+    layout_cache: LineLayoutCache,
 }
 
 impl Renderer {
@@ -41,23 +93,38 @@ impl Renderer {
         let theme = Theme::dark();
 
         let now = Instant::now();
-        Self {
+        let layout_metrics = LayoutMetrics {
+            char_width: rendering::FALLBACK_CHAR_WIDTH * scale,
+            monospace: true,
+        };
+        let mut renderer = Self {
             canvas,
             fonts,
             theme,
             width,
             height,
             scale,
+            zoom: zoom::DEFAULT,
             tab_scroll_x: 0.0,
             flame_system: FlameSystem::new(),
             animation_start: now,
-        }
+            layout_cache: LineLayoutCache::new(layout_metrics),
+        };
+        renderer.refresh_layout_metrics();
+        renderer
     }
 
     pub fn resize(&mut self, width: f32, height: f32, scale: f32) {
         self.width = width;
         self.height = height;
         self.scale = scale;
+        self.refresh_layout_metrics();
+    }
+
+    /// Set the content zoom factor (affects editor font size and line height)
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.zoom = zoom;
+        self.refresh_layout_metrics();
     }
 
     pub fn set_tab_scroll_x(&mut self, scroll: f32) {
@@ -68,23 +135,7 @@ impl Renderer {
         self.flame_system.has_active_flames()
     }
 
-    pub fn render(
-        &mut self,
-        tabs: &[(&str, bool)],
-        current_tab: &Tab,
-        cursor_visible: bool,
-        hovered_tab_index: Option<usize>,
-        hovered_plus: bool,
-        hovered_scrollbar: bool,
-        dragging_scrollbar: bool,
-        renaming_tab: Option<usize>,
-        rename_input: Option<&TextInput>,
-        typing_flame_positions: &[(usize, usize, Instant)],
-        hovered_window_minimize: bool,
-        hovered_window_maximize: bool,
-        hovered_window_close: bool,
-        notes_picker_state: Option<(&TextInput, &ListWidget<NoteEntry>)>,
-    ) {
+    pub fn render(&mut self, frame: &RenderState) {
         let (width, height) = (self.width, self.height);
 
         // Use DPI=1.0, but we compensate by using larger font sizes in physical pixels
@@ -108,42 +159,61 @@ impl Renderer {
                 self.scale,
                 self.tab_scroll_x,
             );
-            tab_bar.draw(
-                tabs,
-                hovered_tab_index,
-                hovered_plus,
-                renaming_tab,
-                rename_input,
-                cursor_visible,
-                hovered_window_minimize,
-                hovered_window_maximize,
-                hovered_window_close,
-            );
+            tab_bar.draw(frame);
         }
 
-        // Draw text content
-        {
-            let mut text_content = TextContentRenderer::new(
+        // Draw text content, shifted right of the sidebar and narrowed to
+        // end at the backlinks panel when they are shown. It stops above the
+        // status bar, which runs under both panels.
+        let content_left = frame
+            .sidebar
+            .map_or(0.0, |_| sidebar_rect(self.height, self.scale).width);
+        let content_right = frame.backlinks.map_or(self.width, |_| {
+            backlinks_rect(self.width, self.height, self.scale).x
+        });
+        let status_bar = status_bar_rect(self.width, self.height, self.scale);
+        let content_area = Rect {
+            x: content_left,
+            y: 0.0,
+            width: content_right - content_left,
+            height: status_bar.y,
+        };
+        TextContentRenderer::new(
+            &mut self.canvas,
+            &self.fonts,
+            &self.theme,
+            content_area,
+            self.scale,
+            self.zoom,
+            &mut self.layout_cache,
+        )
+        .draw(frame, &mut self.flame_system, self.animation_start);
+
+        StatusBarRenderer::new(&mut self.canvas, &self.fonts, &self.theme, self.scale)
+            .draw(status_bar, &StatusInfo {
+                no_dictionary: frame.no_dictionary,
+                ..frame.current_tab.status_info()
+            });
+
+        if let Some(tree) = frame.sidebar {
+            SidebarRenderer::new(&mut self.canvas, &self.fonts, &self.theme, self.height, self.scale)
+                .draw(tree, frame.current_tab.path());
+        }
+
+        if let Some(panel) = frame.backlinks {
+            BacklinksRenderer::new(
                 &mut self.canvas,
                 &self.fonts,
                 &self.theme,
                 self.width,
                 self.height,
                 self.scale,
-                self.animation_start,
-            );
-            text_content.draw(
-                current_tab,
-                cursor_visible,
-                hovered_scrollbar,
-                dragging_scrollbar,
-                &mut self.flame_system,
-                typing_flame_positions,
-            );
+            )
+            .draw(panel);
         }
 
         // Draw notes picker overlay if active
-        if let Some((input, list)) = notes_picker_state {
+        if let Some(view) = &frame.notes_picker {
             let mut picker = NotesPickerRenderer::new(
                 &mut self.canvas,
                 &self.fonts,
@@ -152,25 +222,88 @@ impl Renderer {
                 self.height,
                 self.scale,
             );
-            picker.draw(input, list, cursor_visible);
+            picker.draw(view.input, view.list, &view.placeholder, frame.cursor_visible);
+        }
+
+        if let Some(calendar) = frame.journal_calendar {
+            CalendarRenderer::new(
+                &mut self.canvas,
+                &self.fonts,
+                &self.theme,
+                self.width,
+                self.height,
+                self.scale,
+            )
+            .draw(calendar);
+        }
+
+        if let Some((stats, selection)) = frame.statistics {
+            StatsRenderer::new(
+                &mut self.canvas,
+                &self.fonts,
+                &self.theme,
+                self.width,
+                self.height,
+                self.scale,
+            )
+            .draw(stats, selection);
+        }
+
+        // Context menu is drawn last so it stays above everything else
+        if let Some(menu) = frame.context_menu {
+            ContextMenuRenderer::new(&mut self.canvas, &self.fonts, &self.theme, self.scale)
+                .draw(menu);
         }
 
         self.canvas.flush();
     }
 
     pub fn get_char_width(&self) -> f32 {
+        self.layout_cache.metrics().char_width
+    }
+
+    /// Glyph layout of a line of editor content (used for cursor x and hit testing)
+    pub fn line_layout(&mut self, line: &str) -> Rc<LineLayout> {
+        let paint = self.content_paint();
+        let canvas = &self.canvas;
+        self.layout_cache
+            .get(line, |ch| measure_char_advance(canvas, &paint, ch))
+    }
+
+    fn content_paint(&self) -> Paint {
         let mut text_paint = Paint::color(Color::rgb(255, 255, 255));
         text_paint.set_font(&self.fonts);
-        text_paint.set_font_size(16.0 * self.scale);
-        self.measure_char_width(&text_paint)
+        text_paint.set_font_size(rendering::CONTENT_FONT_SIZE * self.scale * self.zoom);
+        text_paint
     }
 
-    fn measure_char_width(&self, paint: &Paint) -> f32 {
-        if let Ok(metrics) = self.canvas.measure_text(0.0, 0.0, "M", paint) {
-            metrics.width()
-        } else {
-            9.6 * self.scale // Fallback approximate width
-        }
+    /// Re-measure the content font; cached line layouts are dropped if it changed
+    fn refresh_layout_metrics(&mut self) {
+        let paint = self.content_paint();
+        let measure = |text: &str| {
+            self.canvas
+                .measure_text(0.0, 0.0, text, &paint)
+                .map(|metrics| metrics.width())
+                .ok()
+        };
+        let char_width = measure("M")
+            .unwrap_or(rendering::FALLBACK_CHAR_WIDTH * self.scale * self.zoom); // Fallback approximate width
+        // A narrow glyph as wide as a wide one means the primary font is monospace
+        let monospace = measure("i")
+            .map(|narrow| (narrow - char_width).abs() < 0.5)
+            .unwrap_or(true);
+        self.layout_cache.set_metrics(LayoutMetrics {
+            char_width,
+            monospace,
+        });
     }
+}
 
+/// Measure the advance width of a single character with the given paint
+fn measure_char_advance(canvas: &Canvas<OpenGl>, paint: &Paint, ch: char) -> f32 {
+    let mut buf = [0u8; 4];
+    canvas
+        .measure_text(0.0, 0.0, ch.encode_utf8(&mut buf), paint)
+        .map(|metrics| metrics.width())
+        .unwrap_or(0.0)
 }
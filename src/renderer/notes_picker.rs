@@ -1,9 +1,9 @@
 //! Notes picker overlay rendering
 
-use crate::app::NoteEntry;
+use crate::app::PickerItem;
 use crate::theme::Theme;
 use crate::ui::{ListWidget, TextInput};
-use femtovg::{Canvas, Color, Paint, Path, FontId, renderer::OpenGl};
+use femtovg::{Align, Canvas, Color, Paint, Path, FontId, renderer::OpenGl};
 
 pub struct NotesPickerRenderer<'a> {
     canvas: &'a mut Canvas<OpenGl>,
@@ -36,7 +36,8 @@ impl<'a> NotesPickerRenderer<'a> {
     pub fn draw(
         &mut self,
         input: &TextInput,
-        list: &ListWidget<NoteEntry>,
+        list: Option<&ListWidget<PickerItem>>,
+        placeholder: &str,
         cursor_visible: bool,
     ) {
         let scale = self.scale;
@@ -49,7 +50,7 @@ impl<'a> NotesPickerRenderer<'a> {
         let input_height = 36.0 * scale;
         let item_height = 32.0 * scale;
         let max_visible_items = 8;
-        let visible_items = list.len().min(max_visible_items);
+        let visible_items = list.map_or(0, |list| list.len().min(max_visible_items));
         let list_height = visible_items as f32 * item_height;
         let overlay_height = input_height + list_height + 16.0 * scale;
         
@@ -131,7 +132,7 @@ impl<'a> NotesPickerRenderer<'a> {
             let mut placeholder_paint = Paint::color(Color::rgba(150, 150, 150, 180));
             placeholder_paint.set_font(&self.fonts);
             placeholder_paint.set_font_size(font_size);
-            let _ = self.canvas.fill_text(text_x, text_y, "Search notes...", &placeholder_paint);
+            let _ = self.canvas.fill_text(text_x, text_y, placeholder, &placeholder_paint);
         } else {
             let _ = self.canvas.fill_text(text_x, text_y, input.text(), &text_paint);
         }
@@ -156,6 +157,9 @@ impl<'a> NotesPickerRenderer<'a> {
         
         // Draw list items using ListWidget's visible_items iterator
         let list_y = input_y + input_height + 4.0 * scale;
+        let Some(list) = list else {
+            return;
+        };
         
         // Use scroll offset from the list widget
         let scroll_offset = list.scroll_offset();
@@ -185,20 +189,93 @@ impl<'a> NotesPickerRenderer<'a> {
                 );
             }
             
-            if let Some(note) = list.items().get(*filtered_idx) {
-                // Draw note title
-                let title_color = if is_selected {
-                    Color::rgbf(self.theme.fg.0, self.theme.fg.1, self.theme.fg.2)
+            let title_color = if is_selected {
+                Color::rgbf(self.theme.fg.0, self.theme.fg.1, self.theme.fg.2)
+            } else {
+                Color::rgba(200, 200, 200, 220)
+            };
+            let mut title_paint = Paint::color(title_color);
+            title_paint.set_font(self.fonts);
+            title_paint.set_font_size(font_size);
+            let title_y = item_y + item_height / 2.0 + font_size * 0.35;
+
+            if let Some(PickerItem::Tag(tag)) = list.items().get(*filtered_idx) {
+                let _ = self.canvas.fill_text(text_x, title_y, format!("#{}", tag.name), &title_paint);
+
+                // Draw the note count right-aligned and dimmed
+                let mut count_paint = Paint::color(Color::rgba(150, 150, 150, 160));
+                count_paint.set_font(self.fonts);
+                count_paint.set_font_size(font_size * 0.85);
+                count_paint.set_text_align(Align::Right);
+                let count_x = input_x + input_width - 8.0 * scale;
+                let _ = self.canvas.fill_text(count_x, title_y, tag.count.to_string(), &count_paint);
+            }
+
+            if let Some(PickerItem::Template(template)) = list.items().get(*filtered_idx) {
+                let _ = self.canvas.fill_text(text_x, title_y, &template.name, &title_paint);
+            }
+
+            if let Some(PickerItem::TaskNote(note)) = list.items().get(*filtered_idx) {
+                // Note heading in the accent color above its tasks
+                let mut heading_paint = Paint::color(Color::rgbf(
+                    self.theme.tab_active_border.0,
+                    self.theme.tab_active_border.1,
+                    self.theme.tab_active_border.2,
+                ));
+                heading_paint.set_font(self.fonts);
+                heading_paint.set_font_size(font_size * 0.9);
+                let _ = self.canvas.fill_text(text_x, title_y, &note.title, &heading_paint);
+            }
+
+            if let Some(PickerItem::Task(task)) = list.items().get(*filtered_idx) {
+                // Tasks are indented under their note; done ones are dimmed
+                let task_x = text_x + 16.0 * scale;
+                let (checkbox, paint) = if task.done {
+                    let mut done_paint = Paint::color(Color::rgba(150, 150, 150, 160));
+                    done_paint.set_font(self.fonts);
+                    done_paint.set_font_size(font_size);
+                    ("[x]", done_paint)
                 } else {
-                    Color::rgba(200, 200, 200, 220)
+                    ("[ ]", title_paint.clone())
                 };
-                
-                let mut title_paint = Paint::color(title_color);
-                title_paint.set_font(&self.fonts);
-                title_paint.set_font_size(font_size);
-                
-                let title_y = item_y + item_height / 2.0 + font_size * 0.35;
+                let _ = self.canvas.fill_text(task_x, title_y, checkbox, &paint);
+                let checkbox_width = self
+                    .canvas
+                    .measure_text(0.0, 0.0, "[ ] ", &paint)
+                    .map(|m| m.width())
+                    .unwrap_or(0.0);
+                let _ = self.canvas.fill_text(task_x + checkbox_width, title_y, &task.text, &paint);
+            }
+
+            if let Some(PickerItem::Heading(heading)) = list.items().get(*filtered_idx) {
+                // Headings are indented by level, with the line number dimmed
+                let heading_x = text_x + (heading.level - 1) as f32 * 16.0 * scale;
+                let _ = self.canvas.fill_text(heading_x, title_y, &heading.text, &title_paint);
+                let mut line_paint = Paint::color(Color::rgba(150, 150, 150, 160));
+                line_paint.set_font(self.fonts);
+                line_paint.set_font_size(font_size * 0.85);
+                line_paint.set_text_align(Align::Right);
+                let line_x = input_x + input_width - 8.0 * scale;
+                let _ = self.canvas.fill_text(line_x, title_y, (heading.line + 1).to_string(), &line_paint);
+            }
+
+            if let Some(PickerItem::Note(note)) = list.items().get(*filtered_idx) {
+                // Draw note title
                 let _ = self.canvas.fill_text(text_x, title_y, &note.title, &title_paint);
+
+                // Draw the folder path dimmed after the title
+                if let Some(folder) = &note.folder {
+                    let title_width = self
+                        .canvas
+                        .measure_text(0.0, 0.0, &note.title, &title_paint)
+                        .map(|m| m.width())
+                        .unwrap_or(0.0);
+                    let mut folder_paint = Paint::color(Color::rgba(150, 150, 150, 160));
+                    folder_paint.set_font(self.fonts);
+                    folder_paint.set_font_size(font_size * 0.85);
+                    let folder_x = text_x + title_width + 12.0 * scale;
+                    let _ = self.canvas.fill_text(folder_x, title_y, folder, &folder_paint);
+                }
                 
                 // Draw "open" indicator if the note is already open
                 if note.is_open {
@@ -217,14 +294,30 @@ impl<'a> NotesPickerRenderer<'a> {
             }
         }
         
-        // Draw "no results" message if empty
-        if list.is_empty() && !input.text().is_empty() {
+        // Draw "no results" message if empty. The task list says so even
+        // before anything is typed, since its filter can hide every task.
+        let listing_tasks = matches!(list.items().first(), Some(PickerItem::TaskNote(_)));
+        if list.is_empty() && (!input.text().is_empty() || listing_tasks) {
             let mut no_results_paint = Paint::color(Color::rgba(150, 150, 150, 180));
             no_results_paint.set_font(&self.fonts);
             no_results_paint.set_font_size(font_size);
             
             let msg_y = list_y + item_height / 2.0 + font_size * 0.35;
-            let _ = self.canvas.fill_text(text_x, msg_y, "No matching notes", &no_results_paint);
+            let listing_tags = input.text().starts_with('#') && !input.text().contains(char::is_whitespace);
+            let listing_templates = matches!(list.items().first(), Some(PickerItem::Template(_)));
+            let listing_headings = matches!(list.items().first(), Some(PickerItem::Heading(_)));
+            let message = if listing_tasks {
+                "No matching tasks"
+            } else if listing_headings {
+                "No matching headings"
+            } else if listing_templates {
+                "No matching templates"
+            } else if listing_tags {
+                "No matching tags"
+            } else {
+                "No matching notes"
+            };
+            let _ = self.canvas.fill_text(text_x, msg_y, message, &no_results_paint);
         }
     }
     
@@ -0,0 +1,129 @@
+//! Notebook sidebar rendering
+
+use std::path::PathBuf;
+
+use crate::config::layout;
+use crate::theme::Theme;
+use crate::ui::{sidebar_rect, NotebookTree, SidebarRowKind};
+use femtovg::{renderer::OpenGl, Canvas, Color, FontId, Paint, Path};
+
+pub struct SidebarRenderer<'a> {
+    canvas: &'a mut Canvas<OpenGl>,
+    fonts: &'a [FontId],
+    theme: &'a Theme,
+    height: f32,
+    scale: f32,
+}
+
+impl<'a> SidebarRenderer<'a> {
+    pub fn new(
+        canvas: &'a mut Canvas<OpenGl>,
+        fonts: &'a [FontId],
+        theme: &'a Theme,
+        height: f32,
+        scale: f32,
+    ) -> Self {
+        Self {
+            canvas,
+            fonts,
+            theme,
+            height,
+            scale,
+        }
+    }
+
+    /// Draw the tree; the note at `active_path` is highlighted
+    pub fn draw(&mut self, tree: &NotebookTree, active_path: Option<&PathBuf>) {
+        let scale = self.scale;
+        let rect = sidebar_rect(self.height, scale);
+
+        // Background and divider
+        let mut bg = Path::new();
+        bg.rect(rect.x, rect.y, rect.width, rect.height);
+        self.canvas.fill_path(
+            &bg,
+            &Paint::color(Color::rgbf(
+                self.theme.tab_inactive.0,
+                self.theme.tab_inactive.1,
+                self.theme.tab_inactive.2,
+            )),
+        );
+        let mut divider = Path::new();
+        divider.move_to(rect.x + rect.width - 0.5 * scale, rect.y);
+        divider.line_to(rect.x + rect.width - 0.5 * scale, rect.y + rect.height);
+        self.canvas.stroke_path(
+            &divider,
+            &Paint::color(Color::rgbf(
+                self.theme.border.0,
+                self.theme.border.1,
+                self.theme.border.2,
+            ))
+            .with_line_width(1.0 * scale),
+        );
+
+        self.canvas.save();
+        self.canvas.scissor(rect.x, rect.y, rect.width, rect.height);
+
+        let row_height = layout::SIDEBAR_ROW_HEIGHT * scale;
+        let indent = layout::SIDEBAR_INDENT * scale;
+        let list_top = rect.y + layout::PADDING * 0.5 * scale;
+        let text_padding = 10.0 * scale;
+        let font_size = 13.0 * scale;
+        let visible_rows = (rect.height / row_height).ceil() as usize;
+
+        for (index, row) in tree
+            .rows()
+            .iter()
+            .enumerate()
+            .skip(tree.scroll_offset())
+            .take(visible_rows)
+        {
+            let row_y = list_top + (index - tree.scroll_offset()) as f32 * row_height;
+            let is_active = row.kind == SidebarRowKind::Note && active_path == Some(&row.path);
+
+            // Highlight the active note and the hovered row
+            if is_active || tree.hovered() == Some(index) {
+                let strength = if is_active { 0.3 } else { 0.15 };
+                let mut highlight = Path::new();
+                highlight.rounded_rect(
+                    rect.x + 4.0 * scale,
+                    row_y,
+                    rect.width - 8.0 * scale,
+                    row_height - 2.0 * scale,
+                    4.0 * scale,
+                );
+                self.canvas.fill_path(
+                    &highlight,
+                    &Paint::color(Color::rgbf(
+                        self.theme.tab_active_border.0 * strength,
+                        self.theme.tab_active_border.1 * strength,
+                        self.theme.tab_active_border.2 * strength,
+                    )),
+                );
+            }
+
+            let (marker, color) = match row.kind {
+                SidebarRowKind::Folder { expanded: true } => {
+                    ("▾ ", Color::rgba(200, 200, 200, 220))
+                }
+                SidebarRowKind::Folder { expanded: false } => {
+                    ("▸ ", Color::rgba(200, 200, 200, 220))
+                }
+                SidebarRowKind::Note => (
+                    "",
+                    Color::rgbf(self.theme.fg.0, self.theme.fg.1, self.theme.fg.2),
+                ),
+            };
+            let mut paint = Paint::color(color);
+            paint.set_font(self.fonts);
+            paint.set_font_size(font_size);
+
+            let text_x = rect.x + text_padding + row.depth as f32 * indent;
+            let text_y = row_y + row_height / 2.0 + font_size * 0.35;
+            let label = format!("{marker}{}", row.label);
+            let _ = self.canvas.fill_text(text_x, text_y, &label, &paint);
+        }
+
+        self.canvas.restore();
+    }
+}
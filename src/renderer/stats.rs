@@ -0,0 +1,123 @@
+//! Statistics overlay rendering
+
+use crate::config::layout;
+use crate::stats::Stats;
+use crate::theme::Theme;
+use femtovg::{renderer::OpenGl, Align, Canvas, Color, FontId, Paint, Path};
+
+const PANEL_WIDTH: f32 = 300.0;
+const ROW_HEIGHT: f32 = 28.0;
+
+pub struct StatsRenderer<'a> {
+    canvas: &'a mut Canvas<OpenGl>,
+    fonts: &'a [FontId],
+    theme: &'a Theme,
+    width: f32,
+    height: f32,
+    scale: f32,
+}
+
+impl<'a> StatsRenderer<'a> {
+    pub fn new(
+        canvas: &'a mut Canvas<OpenGl>,
+        fonts: &'a [FontId],
+        theme: &'a Theme,
+        width: f32,
+        height: f32,
+        scale: f32,
+    ) -> Self {
+        Self {
+            canvas,
+            fonts,
+            theme,
+            width,
+            height,
+            scale,
+        }
+    }
+
+    pub fn draw(&mut self, stats: &Stats, selection: bool) {
+        let scale = self.scale;
+        let padding = layout::PADDING * scale;
+        let row = ROW_HEIGHT * scale;
+        let font_size = 14.0 * scale;
+        let accent = self.theme.tab_active_border;
+
+        let rows = [
+            ("Words", stats.words.to_string()),
+            ("Characters", stats.chars.to_string()),
+            ("Characters (no spaces)", stats.chars_no_spaces.to_string()),
+            ("Sentences", stats.sentences.to_string()),
+            ("Paragraphs", stats.paragraphs.to_string()),
+            ("Headings", stats.headings.to_string()),
+            ("Reading time", format!("{} min", stats.reading_minutes())),
+        ];
+        let title = if selection {
+            "Selection statistics"
+        } else {
+            "Document statistics"
+        };
+
+        let panel_width = PANEL_WIDTH * scale;
+        let panel_height = (rows.len() as f32 + 1.5) * row + 2.0 * padding;
+        let x = ((self.width - panel_width) / 2.0).max(0.0);
+        let y = 60.0 * scale;
+
+        // Backdrop, background and border, as for the journal calendar
+        let mut backdrop = Path::new();
+        backdrop.rect(0.0, 0.0, self.width, self.height);
+        self.canvas
+            .fill_path(&backdrop, &Paint::color(Color::rgba(0, 0, 0, 120)));
+
+        let mut bg = Path::new();
+        bg.rounded_rect(x, y, panel_width, panel_height, 8.0 * scale);
+        self.canvas.fill_path(
+            &bg,
+            &Paint::color(Color::rgbf(
+                self.theme.tab_inactive.0,
+                self.theme.tab_inactive.1,
+                self.theme.tab_inactive.2,
+            )),
+        );
+        self.canvas.stroke_path(
+            &bg,
+            &Paint::color(Color::rgbf(accent.0, accent.1, accent.2)).with_line_width(2.0),
+        );
+
+        let mut text_paint = Paint::color(Color::rgbf(
+            self.theme.fg.0,
+            self.theme.fg.1,
+            self.theme.fg.2,
+        ));
+        text_paint.set_font(self.fonts);
+        text_paint.set_font_size(font_size);
+        let mut dim_paint = Paint::color(Color::rgba(150, 150, 150, 200));
+        dim_paint.set_font(self.fonts);
+        dim_paint.set_font_size(font_size * 0.9);
+
+        let center_y = |top: f32| top + row / 2.0 + font_size * 0.35;
+        let left = x + padding;
+        let right = x + panel_width - padding;
+
+        text_paint.set_text_align(Align::Center);
+        let _ = self.canvas.fill_text(
+            x + panel_width / 2.0,
+            center_y(y + padding),
+            title,
+            &text_paint,
+        );
+
+        // Labels on the left, values right-aligned
+        for (index, (label, value)) in rows.iter().enumerate() {
+            let top = y + padding + (index as f32 + 1.5) * row;
+            dim_paint.set_text_align(Align::Left);
+            let _ = self
+                .canvas
+                .fill_text(left, center_y(top), label, &dim_paint);
+            text_paint.set_text_align(Align::Right);
+            let _ = self
+                .canvas
+                .fill_text(right, center_y(top), value, &text_paint);
+        }
+    }
+}
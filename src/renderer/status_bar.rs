@@ -0,0 +1,88 @@
+//! Status bar rendering
+
+use crate::theme::Theme;
+use crate::ui::{Rect, StatusInfo};
+use femtovg::{renderer::OpenGl, Align, Canvas, Color, FontId, Paint, Path};
+
+/// Space between segments
+const SEGMENT_GAP: f32 = 20.0;
+
+pub struct StatusBarRenderer<'a> {
+    canvas: &'a mut Canvas<OpenGl>,
+    fonts: &'a [FontId],
+    theme: &'a Theme,
+    scale: f32,
+}
+
+impl<'a> StatusBarRenderer<'a> {
+    pub fn new(
+        canvas: &'a mut Canvas<OpenGl>,
+        fonts: &'a [FontId],
+        theme: &'a Theme,
+        scale: f32,
+    ) -> Self {
+        Self {
+            canvas,
+            fonts,
+            theme,
+            scale,
+        }
+    }
+
+    /// Draw the bar in `rect`: cursor segments from the left, document
+    /// segments from the right
+    pub fn draw(&mut self, rect: Rect, info: &StatusInfo) {
+        let scale = self.scale;
+
+        // Background and divider
+        let mut bg = Path::new();
+        bg.rect(rect.x, rect.y, rect.width, rect.height);
+        self.canvas.fill_path(
+            &bg,
+            &Paint::color(Color::rgbf(
+                self.theme.tab_inactive.0,
+                self.theme.tab_inactive.1,
+                self.theme.tab_inactive.2,
+            )),
+        );
+        let mut divider = Path::new();
+        divider.move_to(rect.x, rect.y + 0.5 * scale);
+        divider.line_to(rect.x + rect.width, rect.y + 0.5 * scale);
+        self.canvas.stroke_path(
+            &divider,
+            &Paint::color(Color::rgbf(
+                self.theme.border.0,
+                self.theme.border.1,
+                self.theme.border.2,
+            )),
+        );
+
+        let font_size = 12.0 * scale;
+        let mut paint = Paint::color(Color::rgba(
+            (self.theme.fg.0 * 255.0) as u8,
+            (self.theme.fg.1 * 255.0) as u8,
+            (self.theme.fg.2 * 255.0) as u8,
+            180,
+        ));
+        paint.set_font(self.fonts);
+        paint.set_font_size(font_size);
+        let text_y = (rect.y + rect.height / 2.0 + font_size * 0.35).round();
+        let gap = SEGMENT_GAP * scale;
+        let (left, right) = info.segments();
+
+        let mut x = rect.x + 12.0 * scale;
+        for segment in &left {
+            if let Ok(metrics) = self.canvas.fill_text(x.round(), text_y, segment, &paint) {
+                x += metrics.width() + gap;
+            }
+        }
+
+        paint.set_text_align(Align::Right);
+        let mut x = rect.x + rect.width - 12.0 * scale;
+        for segment in right.iter().rev() {
+            if let Ok(metrics) = self.canvas.fill_text(x.round(), text_y, segment, &paint) {
+                x -= metrics.width() + gap;
+            }
+        }
+    }
+}
@@ -1,9 +1,12 @@
 //! Tab bar rendering
 
+use crate::config::layout;
 use crate::theme::Theme;
-use crate::ui::TextInput;
+use crate::ui::{tab_close_rect, tab_width, Rect};
 use femtovg::{Canvas, Color, FontId, Paint, Path, renderer::OpenGl};
 
+use super::RenderState;
+
 /// Snap a coordinate to the pixel grid to prevent blurry text rendering.
 #[inline]
 fn snap_to_pixel(coord: f32) -> f32 {
@@ -38,20 +41,25 @@ impl<'a> TabBarRenderer<'a> {
         }
     }
 
-    pub fn draw(
-        &mut self,
-        tabs: &[(&str, bool)],
-        hovered_tab_index: Option<usize>,
-        hovered_plus: bool,
-        renaming_tab: Option<usize>,
-        rename_input: Option<&TextInput>,
-        cursor_visible: bool,
-        hovered_minimize: bool,
-        hovered_maximize: bool,
-        hovered_close: bool,
-    ) {
+    pub fn draw(&mut self, frame: &RenderState) {
+        let RenderState {
+            tabs,
+            modified_tabs,
+            hovered_tab_index,
+            hovered_tab_close,
+            hovered_plus,
+            renaming_tab,
+            rename_input,
+            cursor_visible,
+            hovered_window_minimize: hovered_minimize,
+            hovered_window_maximize: hovered_maximize,
+            hovered_window_close: hovered_close,
+            ..
+        } = *frame;
         let tab_height = 40.0 * self.scale;
-        let tab_padding = 16.0 * self.scale;
+        // Titles are centered in the part of the tab left of the close button
+        let close_space =
+            (layout::TAB_CLOSE_BUTTON_SIZE + layout::TAB_CLOSE_BUTTON_MARGIN) * self.scale;
 
         // Save state for clipping
         self.canvas.save();
@@ -63,8 +71,7 @@ impl<'a> TabBarRenderer<'a> {
         let mut x = -self.tab_scroll_x;
 
         for (i, (title, is_active)) in tabs.iter().enumerate() {
-            let tab_width =
-                (title.len() as f32 * 9.0 * self.scale + tab_padding * 2.0).max(100.0 * self.scale);
+            let tab_width = tab_width(title, self.scale);
 
             // Optimization: skip drawing off-screen tabs
             if x + tab_width < 0.0 {
@@ -128,7 +135,7 @@ impl<'a> TabBarRenderer<'a> {
                 title.len() as f32 * 9.0 * self.scale // fallback
             };
 
-            let text_x = snap_to_pixel(x + (tab_width - text_width) / 2.0);
+            let text_x = snap_to_pixel(x + (tab_width - close_space - text_width) / 2.0);
             let text_y = snap_to_pixel(tab_height / 2.0 + 5.0 * self.scale);
             let _ = self.canvas.fill_text(text_x, text_y, title, &text_paint);
 
@@ -199,6 +206,15 @@ impl<'a> TabBarRenderer<'a> {
                 self.canvas.stroke_path(&underline_path, &underline_paint);
             }
 
+            // Unsaved tabs show a dot in place of the close button until it is
+            // hovered; otherwise the button appears on the active and hovered tab
+            let close_hovered = Some(i) == hovered_tab_close;
+            if modified_tabs.get(i).copied().unwrap_or(false) && !close_hovered {
+                self.draw_modified_dot(x, tab_width, tab_height);
+            } else if *is_active || Some(i) == hovered_tab_index {
+                self.draw_tab_close_button(x, tab_width, tab_height, close_hovered);
+            }
+
             x += tab_width + 1.0;
         }
 
@@ -264,6 +280,56 @@ impl<'a> TabBarRenderer<'a> {
         let _ = self.canvas.fill_text(plus_x, plus_y, "+", &plus_paint);
     }
 
+    fn draw_tab_close_button(&mut self, x: f32, tab_width: f32, tab_height: f32, hovered: bool) {
+        let tab_rect = Rect {
+            x,
+            y: 0.0,
+            width: tab_width,
+            height: tab_height,
+        };
+        let rect = tab_close_rect(tab_rect, self.scale);
+
+        if hovered {
+            let mut bg = Path::new();
+            bg.rounded_rect(rect.x, rect.y, rect.width, rect.height, 3.0 * self.scale);
+            self.canvas.fill_path(
+                &bg,
+                &Paint::color(Color::rgbf(
+                    self.theme.button_hover.0,
+                    self.theme.button_hover.1,
+                    self.theme.button_hover.2,
+                )),
+            );
+        }
+
+        self.draw_close_icon(rect.x, rect.y, rect.width, 7.0 * self.scale);
+    }
+
+    fn draw_modified_dot(&mut self, x: f32, tab_width: f32, tab_height: f32) {
+        let tab_rect = Rect {
+            x,
+            y: 0.0,
+            width: tab_width,
+            height: tab_height,
+        };
+        let rect = tab_close_rect(tab_rect, self.scale);
+
+        let mut dot = Path::new();
+        dot.circle(
+            rect.x + rect.width / 2.0,
+            rect.y + rect.height / 2.0,
+            4.0 * self.scale,
+        );
+        self.canvas.fill_path(
+            &dot,
+            &Paint::color(Color::rgbf(
+                self.theme.fg.0,
+                self.theme.fg.1,
+                self.theme.fg.2,
+            )),
+        );
+    }
+
     fn draw_window_controls(
         &mut self,
         tab_height: f32,
@@ -1,10 +1,17 @@
 //! Text content and editor area rendering
 
+use crate::app::ImePreedit;
+use crate::config::{layout, rendering};
+use crate::folding::FoldMap;
+use crate::links;
+use crate::spelling::SpellChecker;
 use crate::tab::Tab;
+use crate::text_layout::{LineLayout, LineLayoutCache};
 use crate::theme::Theme;
-use crate::ui::ScrollbarWidget;
-use femtovg::{Canvas, Color, FontId, Paint, Path, renderer::OpenGl};
+use crate::ui::{Rect, ScrollbarWidget};
+use femtovg::{Align, Canvas, Color, FontId, Paint, Path, renderer::OpenGl};
 use std::collections::HashMap;
+use std::rc::Rc;
 use std::time::Instant;
 
 /// Result of checking if a character position is in a flame zone
@@ -16,6 +23,22 @@ enum FlameHit {
 }
 
 use super::flame::FlameSystem;
+use super::RenderState;
+
+/// Scroll position and line geometry of the frame being drawn
+struct View {
+    folds: Rc<FoldMap>,
+    scroll_offset: usize,
+    scroll_row: usize,
+    scroll_x: f32,
+    do_wrap: bool,
+    start_y: f32,
+    line_height: f32,
+    padding: f32,
+    char_width: f32,
+    /// Seconds since the renderer started, driving the burning selection
+    time: f32,
+}
 
 /// Snap a coordinate to the pixel grid to prevent blurry text rendering.
 #[inline]
@@ -27,10 +50,10 @@ pub struct TextContentRenderer<'a> {
     canvas: &'a mut Canvas<OpenGl>,
     fonts: &'a [FontId],
     theme: &'a Theme,
-    width: f32,
-    height: f32,
+    area: Rect,
     scale: f32,
-    animation_start: Instant,
+    zoom: f32,
+    layout_cache: &'a mut LineLayoutCache,
 }
 
 impl<'a> TextContentRenderer<'a> {
@@ -38,92 +61,82 @@ impl<'a> TextContentRenderer<'a> {
         canvas: &'a mut Canvas<OpenGl>,
         fonts: &'a [FontId],
         theme: &'a Theme,
-        width: f32,
-        height: f32,
+        area: Rect,
         scale: f32,
-        animation_start: Instant,
+        zoom: f32,
+        layout_cache: &'a mut LineLayoutCache,
     ) -> Self {
         Self {
             canvas,
             fonts,
             theme,
-            width,
-            height,
+            area,
             scale,
-            animation_start,
+            zoom,
+            layout_cache,
         }
     }
 
     pub fn draw(
         &mut self,
-        tab: &Tab,
-        cursor_visible: bool,
-        hovered_scrollbar: bool,
-        dragging_scrollbar: bool,
+        frame: &RenderState,
         flame_system: &mut FlameSystem,
-        typing_flame_positions: &[(usize, usize, std::time::Instant)],
+        animation_start: Instant,
     ) {
-        let tab_height = 40.0 * self.scale;
-        let padding = 16.0 * self.scale;
-        let line_height = 24.0 * self.scale;
-        let start_y = tab_height + padding;
-        let scroll_offset = tab.scroll_offset();
-        let scroll_x = tab.scroll_offset_x();
-        let do_wrap = tab.word_wrap();
+        let tab = frame.current_tab;
+        let padding = layout::PADDING * self.scale;
+        let folds = tab.fold_map();
+        let view = View {
+            scroll_offset: tab.scroll_offset(),
+            scroll_row: folds.row_of(tab.scroll_offset()),
+            scroll_x: tab.scroll_offset_x(),
+            do_wrap: tab.word_wrap(),
+            start_y: layout::TAB_HEIGHT * self.scale + padding,
+            line_height: layout::LINE_HEIGHT * self.scale * self.zoom,
+            padding,
+            char_width: self.layout_cache.metrics().char_width,
+            time: animation_start.elapsed().as_secs_f32(),
+            folds,
+        };
+
+        self.canvas.save();
+        self.canvas.translate(self.area.x, self.area.y);
 
         let text = tab.content();
         let cursor_pos = tab.cursor_position();
 
-        // Setup text paint
-        let mut text_paint = Paint::color(Color::rgbf(
-            self.theme.fg.0,
-            self.theme.fg.1,
-            self.theme.fg.2,
-        ));
-        text_paint.set_font(self.fonts);
-        text_paint.set_font_size(16.0 * self.scale);
-        let char_width = self.measure_char_width(&text_paint);
+        let text_paint = self.content_paint(self.theme.fg);
 
         // Collect character positions for flame spawning (selection + typing)
-        let mut char_positions = self.collect_selection_positions(
-            tab,
-            text,
-            scroll_offset,
-            scroll_x,
-            do_wrap,
-            start_y,
-            line_height,
-            padding,
-            char_width,
-        );
+        let mut char_positions = self.collect_selection_positions(tab, text, &view);
         
         // Add typing flame positions with age factor
         let now = std::time::Instant::now();
         let text_lines: Vec<&str> = text.lines().collect();
-        for &(line, col, timestamp) in typing_flame_positions {
-            if line < scroll_offset {
+        for &(line, col, timestamp) in frame.typing_flame_positions {
+            if line < view.scroll_offset || view.folds.is_hidden(line) {
                 continue;
             }
-            let visible_idx = line - scroll_offset;
-            let y = start_y + (visible_idx as f32 * line_height);
-            if y > self.height {
+            let visible_idx = view.folds.row_of(line) - view.scroll_row;
+            let y = view.start_y + (visible_idx as f32 * view.line_height);
+            if y > self.area.height {
                 continue;
             }
             
             // Calculate age factor (0.0 = just typed, 1.0 = 1 second old)
             let age = now.duration_since(timestamp).as_secs_f32().min(1.0);
             
-            let line_bottom_y = y + line_height;
+            let line_bottom_y = y + view.line_height;
             
-            // Calculate center visual x position accounting for tabs
+            // Calculate center x position from the line's glyph layout
             let char_x = if line < text_lines.len() {
-                let visual_line = crate::visual_position::VisualLine::new(text_lines[line]);
-                visual_line.char_col_to_visual_center_x(col, padding - scroll_x, char_width)
+                let layout = self.line_layout(text_lines[line]);
+                view.padding - view.scroll_x + layout.center_of(col)
             } else {
-                padding - scroll_x + char_width * 0.5
+                view.padding - view.scroll_x + view.char_width * 0.5
             };
             
-            let char_y = y + line_height * 0.5;
+            let char_y = y + view.line_height * 0.5;
             char_positions.push((char_x, char_y, line_bottom_y, age));
         }
 
@@ -140,36 +153,31 @@ impl<'a> TextContentRenderer<'a> {
         }
 
         // Calculate cursor position (single source of truth)
-        let cursor_rect = self.calculate_cursor_position(
-            text,
-            cursor_pos,
-            scroll_offset,
-            scroll_x,
-            start_y,
-            line_height,
-            padding,
-            char_width,
-        );
+        let cursor_rect = self.calculate_cursor_position(text, cursor_pos, &view);
+
+        // Links and spelling only count outside fenced code, and only notes
+        // are spell checked
+        let prose_lines = tab.prose_lines();
+        let spell_checker = frame.spell_checker.filter(|_| tab.is_markdown());
 
         // Draw text (no cursor logic mixed in)
-        self.draw_text_lines(
-            text,
-            scroll_offset,
-            scroll_x,
-            do_wrap,
-            start_y,
-            line_height,
-            padding,
-            char_width,
-            &text_paint,
-            &char_positions,
-        );
+        self.draw_text_lines(text, &view, &char_positions, &prose_lines, spell_checker);
+
+        self.draw_fold_markers(tab, &view);
+
+        // Draw IME composition inline at the cursor; the caret moves inside it
+        let cursor_rect = match (cursor_rect, frame.ime_preedit) {
+            (Some((cx, cy)), Some(preedit)) => {
+                Some(self.draw_ime_preedit(preedit, cx, cy, view.line_height, &text_paint))
+            }
+            _ => cursor_rect,
+        };
 
         // Draw Cursor
-        if cursor_visible {
+        if frame.cursor_visible {
             if let Some((cx, cy)) = cursor_rect {
                 let mut cursor_path = Path::new();
-                cursor_path.rect(cx, cy, 2.0 * self.scale, line_height);
+                cursor_path.rect(cx, cy, 2.0 * self.scale, view.line_height);
                 self.canvas.fill_path(
                     &cursor_path,
                     &Paint::color(Color::rgbf(
@@ -189,27 +197,31 @@ impl<'a> TextContentRenderer<'a> {
         // Draw scrollbar
         self.draw_scrollbar(
             tab,
-            start_y,
-            padding,
-            line_height,
-            scroll_offset,
-            hovered_scrollbar,
-            dragging_scrollbar,
+            &view,
+            frame.hovered_scrollbar,
+            frame.dragging_scrollbar,
         );
+
+        self.canvas.restore();
     }
 
     fn collect_selection_positions(
-        &self,
+        &mut self,
         tab: &Tab,
         text: &str,
-        scroll_offset: usize,
-        scroll_x: f32,
-        do_wrap: bool,
-        start_y: f32,
-        line_height: f32,
-        padding: f32,
-        char_width: f32,
+        view: &View,
     ) -> Vec<(f32, f32, f32, f32)> {
+        let View {
+            ref folds,
+            scroll_offset,
+            scroll_x,
+            do_wrap,
+            start_y,
+            line_height,
+            padding,
+            char_width,
+            ..
+        } = *view;
         // FlameSystem handles budget management - we only collect visible positions
         let mut char_positions = Vec::new();
 
@@ -227,11 +239,14 @@ impl<'a> TextContentRenderer<'a> {
                     .skip(visible_start)
                     .take_while(|(idx, _)| *idx <= visible_end)
                 {
-                    let visible_idx = line_idx.saturating_sub(scroll_offset);
+                    if folds.is_hidden(line_idx) {
+                        continue;
+                    }
+                    let visible_idx = folds.row_of(line_idx) - folds.row_of(scroll_offset);
                     let y = start_y + (visible_idx as f32 * line_height);
                     
                     // Stop if below visible area
-                    if y > self.height {
+                    if y > self.area.height {
                         break;
                     }
 
@@ -245,16 +260,12 @@ impl<'a> TextContentRenderer<'a> {
                     };
 
                     // Collect position for each selected character (age = 0.0 for selection)
-                    let visual_line = crate::visual_position::VisualLine::new(line_content);
+                    let layout = self.line_layout(line_content);
                     for col in start_col_in_line..end_col_in_line {
-                        let char_x = visual_line.char_col_to_visual_center_x(
-                            col,
-                            padding - scroll_x,
-                            char_width,
-                        );
+                        let char_x = padding - scroll_x + layout.center_of(col);
                         
                         // Skip horizontally off-screen characters
-                        if char_x < -char_width || char_x > self.width + char_width {
+                        if char_x < -char_width || char_x > self.area.width + char_width {
                             continue;
                         }
                         
@@ -270,39 +281,87 @@ impl<'a> TextContentRenderer<'a> {
 
     /// Calculate cursor screen position - single source of truth for cursor location
     fn calculate_cursor_position(
-        &self,
+        &mut self,
         text: &str,
         cursor_pos: usize,
-        scroll_offset: usize,
-        scroll_x: f32,
-        start_y: f32,
-        line_height: f32,
-        padding: f32,
-        char_width: f32,
+        view: &View,
     ) -> Option<(f32, f32)> {
+        let View {
+            ref folds,
+            scroll_offset,
+            scroll_x,
+            start_y,
+            line_height,
+            padding,
+            ..
+        } = *view;
         let (cursor_line, cursor_col) = get_cursor_line_col(text, cursor_pos);
 
-        // Cursor is above visible area (scrolled past)
-        if cursor_line < scroll_offset {
+        // Cursor is above visible area (scrolled past) or folded away
+        if cursor_line < scroll_offset || folds.is_hidden(cursor_line) {
             return None;
         }
 
-        let visual_line = cursor_line - scroll_offset;
+        let visual_line = folds.row_of(cursor_line) - folds.row_of(scroll_offset);
         let y = start_y + (visual_line as f32 * line_height);
 
         // Cursor is below visible area
-        if y > self.height {
+        if y > self.area.height {
             return None;
         }
 
-        // Get line content for accurate x position (handles tabs)
+        // Get line content for accurate x position (handles tabs and wide glyphs)
         let line_content = text.lines().nth(cursor_line).unwrap_or("");
-        let visual_line = crate::visual_position::VisualLine::new(line_content);
-        let x = visual_line.char_col_to_visual_x(cursor_col, padding - scroll_x, char_width);
+        let layout = self.line_layout(line_content);
+        let x = padding - scroll_x + layout.x_of(cursor_col);
 
         Some((x, y))
     }
 
+    /// Draw uncommitted IME text over the cursor position with an underline,
+    /// returning where the caret should be drawn inside it
+    fn draw_ime_preedit(
+        &mut self,
+        preedit: &ImePreedit,
+        x: f32,
+        y: f32,
+        line_height: f32,
+        text_paint: &Paint,
+    ) -> (f32, f32) {
+        let layout = self.line_layout(&preedit.text);
+        let width = layout.width();
+
+        // Cover the text under the composition so it reads as inserted
+        let mut bg_path = Path::new();
+        bg_path.rect(x, y, width, line_height);
+        self.canvas.fill_path(
+            &bg_path,
+            &Paint::color(Color::rgbf(self.theme.bg.0, self.theme.bg.1, self.theme.bg.2)),
+        );
+
+        let _ = self.canvas.fill_text(
+            snap_to_pixel(x),
+            snap_to_pixel(y + line_height * 0.75),
+            &preedit.text,
+            text_paint,
+        );
+
+        let thickness = (1.0 * self.scale).max(1.0);
+        let mut underline = Path::new();
+        underline.rect(x, y + line_height * 0.85, width, thickness);
+        self.canvas.fill_path(
+            &underline,
+            &Paint::color(Color::rgbf(self.theme.fg.0, self.theme.fg.1, self.theme.fg.2)),
+        );
+
+        let caret_col = preedit
+            .cursor
+            .and_then(|(start, _)| preedit.text.get(..start))
+            .map(|before| before.chars().count())
+            .unwrap_or(layout.char_count());
+        (x + layout.x_of(caret_col), y)
+    }
+
     /// Build a spatial hash map for O(1) flame position lookups
     fn build_flame_lookup(
         char_positions: &[(f32, f32, f32, f32)],
@@ -339,16 +398,26 @@ impl<'a> TextContentRenderer<'a> {
     fn draw_text_lines(
         &mut self,
         text: &str,
-        scroll_offset: usize,
-        scroll_x: f32,
-        do_wrap: bool,
-        start_y: f32,
-        line_height: f32,
-        padding: f32,
-        char_width: f32,
-        text_paint: &Paint,
+        view: &View,
         char_positions: &[(f32, f32, f32, f32)],
+        prose_lines: &[bool],
+        spell_checker: Option<&SpellChecker>,
     ) {
+        let View {
+            ref folds,
+            scroll_offset,
+            scroll_x,
+            do_wrap,
+            start_y,
+            line_height,
+            padding,
+            char_width,
+            time,
+            ..
+        } = *view;
+        let text_paint = &self.content_paint(self.theme.fg);
+        let link_paint = &self.content_paint(self.theme.link);
+
         // Build spatial lookup for O(1) flame checks
         let flame_lookup = Self::build_flame_lookup(char_positions, char_width, line_height);
         let cell_w = char_width.max(1.0);
@@ -357,10 +426,13 @@ impl<'a> TextContentRenderer<'a> {
         // Use iterator directly to avoid allocation
         let mut current_y = start_y;
 
-        for line in text.lines().skip(scroll_offset) {
-            if current_y > self.height {
+        for (line_idx, line) in text.lines().enumerate().skip(scroll_offset) {
+            if current_y > self.area.height {
                 break;
             }
+            if folds.is_hidden(line_idx) {
+                continue;
+            }
 
             let mut x_offset = if do_wrap {
                 padding
@@ -368,26 +440,52 @@ impl<'a> TextContentRenderer<'a> {
                 padding - scroll_x
             };
 
-            for ch in line.chars() {
-                let advance = crate::visual_position::get_char_visual_width(ch);
-                let char_w = char_width * advance as f32;
+            let layout = self.line_layout(line);
+            let prose = prose_lines.get(line_idx) == Some(&true);
+            let line_links = if prose { links::line_links(line) } else { Vec::new() };
+            let mut underline = Path::new();
+            let misspellings = match spell_checker {
+                Some(checker) if prose => checker.misspellings(line),
+                _ => Vec::new(),
+            };
+            let mut squiggle = Path::new();
+            for (col, ch) in line.chars().enumerate() {
+                let char_w = layout.advance(col);
+                let in_link = line_links
+                    .iter()
+                    .any(|link| link.start <= col && col < link.end);
+                let misspelled = misspellings
+                    .iter()
+                    .any(|&(start, end)| start <= col && col < end);
 
                 // Wrap check
-                if do_wrap && x_offset + char_w > self.width - padding {
+                if do_wrap && x_offset + char_w > self.area.width - padding {
                     current_y += line_height;
                     x_offset = padding;
-                    if current_y > self.height {
+                    if current_y > self.area.height {
                         break;
                     }
                 }
 
-                if current_y + line_height > 0.0 && current_y < self.height {
+                if current_y + line_height > 0.0 && current_y < self.area.height {
+                    if in_link {
+                        underline.rect(
+                            x_offset,
+                            current_y + line_height * 0.85,
+                            char_w,
+                            (1.0 * self.scale).max(1.0),
+                        );
+                    }
+                    if misspelled {
+                        let baseline = current_y + line_height * 0.9;
+                        self.add_squiggle(&mut squiggle, x_offset, char_w, baseline);
+                    }
                     if !ch.is_control() && ch != ' ' {
                         let text_x = snap_to_pixel(x_offset);
                         let text_y_snapped = snap_to_pixel(current_y + line_height * 0.75);
 
                         // O(1) lookup for flame state
-                        let char_center_x = x_offset + char_width * 0.5;
+                        let char_center_x = x_offset + char_w * 0.5;
                         let char_center_y = current_y + line_height * 0.5;
                         let grid_x = (char_center_x / cell_w) as i32;
                         let grid_y = (char_center_y / cell_h) as i32;
@@ -407,16 +505,17 @@ impl<'a> TextContentRenderer<'a> {
                                 let b = 0.0 * (1.0 - fade) + 1.0 * fade;
                                 let mut paint = Paint::color(Color::rgbf(r, g, b));
                                 paint.set_font(self.fonts);
-                                paint.set_font_size(16.0 * self.scale);
+                                paint.set_font_size(self.font_size());
                                 let _ = self.canvas.fill_text(text_x, text_y_snapped, s, &paint);
                             }
                             FlameHit::Selection => {
-                                let paint = self.create_burning_paint(x_offset, current_y);
+                                let paint = self.create_burning_paint(x_offset, current_y, time);
                                 let _ = self.canvas.fill_text(text_x, text_y_snapped, s, &paint);
                             }
                             FlameHit::None => {
                                 // Use reference directly - no clone needed
-                                let _ = self.canvas.fill_text(text_x, text_y_snapped, s, text_paint);
+                                let paint = if in_link { link_paint } else { text_paint };
+                                let _ = self.canvas.fill_text(text_x, text_y_snapped, s, paint);
                             }
                         };
                     }
@@ -425,14 +524,106 @@ impl<'a> TextContentRenderer<'a> {
                 x_offset += char_w;
             }
 
+            if !line_links.is_empty() {
+                self.canvas.fill_path(&underline, link_paint);
+            }
+            if !misspellings.is_empty() {
+                let color = self.theme.misspelling;
+                self.canvas.stroke_path(
+                    &squiggle,
+                    &Paint::color(Color::rgbf(color.0, color.1, color.2))
+                        .with_line_width((1.0 * self.scale).max(1.0)),
+                );
+            }
+            if folds.is_folded(line_idx) {
+                self.draw_fold_marker(x_offset + char_width, current_y, line_height);
+            }
             current_y += line_height;
         }
     }
 
-    fn create_burning_paint(&self, x_offset: f32, current_y: f32) -> Paint {
+    /// Zigzag under one character of a misspelled word. Its corners sit at
+    /// fixed x positions so the pieces under neighbouring characters join.
+    fn add_squiggle(&self, path: &mut Path, x: f32, width: f32, baseline: f32) {
+        let step = 2.0 * self.scale;
+        let height = 1.5 * self.scale;
+        let y_at = |x: f32| {
+            let phase = (x / step).rem_euclid(2.0);
+            let rising = phase.min(2.0 - phase);
+            baseline - height * rising
+        };
+        path.move_to(x, y_at(x));
+        let mut corner = (x / step).floor() * step + step;
+        while corner < x + width {
+            path.line_to(corner, y_at(corner));
+            corner += step;
+        }
+        path.line_to(x + width, y_at(x + width));
+    }
+
+    /// Arrows in the left padding next to every line that starts a foldable
+    /// region: pointing right when folded, down when open
+    fn draw_fold_markers(&mut self, tab: &Tab, view: &View) {
+        let View {
+            ref folds,
+            scroll_offset,
+            scroll_row,
+            start_y,
+            line_height,
+            padding,
+            ..
+        } = *view;
+        let mut paint = Paint::color(Color::rgba(
+            (self.theme.fg.0 * 255.0) as u8,
+            (self.theme.fg.1 * 255.0) as u8,
+            (self.theme.fg.2 * 255.0) as u8,
+            100,
+        ));
+        paint.set_font(self.fonts);
+        paint.set_font_size(self.font_size() * 0.7);
+        paint.set_text_align(Align::Center);
+        let mut starts: Vec<usize> = tab.fold_regions().iter().map(|&(first, _)| first).collect();
+        starts.dedup();
+        for line in starts {
+            if line < scroll_offset || folds.is_hidden(line) {
+                continue;
+            }
+            let y = start_y + (folds.row_of(line) - scroll_row) as f32 * line_height;
+            if y > self.area.height {
+                break;
+            }
+            let marker = if folds.is_folded(line) { "▸" } else { "▾" };
+            let _ = self.canvas.fill_text(
+                snap_to_pixel(padding * 0.5),
+                snap_to_pixel(y + line_height * 0.7),
+                marker,
+                &paint,
+            );
+        }
+    }
+
+    /// Dimmed ellipsis after a folded line, standing in for the hidden lines
+    fn draw_fold_marker(&mut self, x: f32, y: f32, line_height: f32) {
+        let mut paint = Paint::color(Color::rgba(
+            (self.theme.fg.0 * 255.0) as u8,
+            (self.theme.fg.1 * 255.0) as u8,
+            (self.theme.fg.2 * 255.0) as u8,
+            120,
+        ));
+        paint.set_font(self.fonts);
+        paint.set_font_size(self.font_size());
+        let _ = self.canvas.fill_text(
+            snap_to_pixel(x),
+            snap_to_pixel(y + line_height * 0.75),
+            "…",
+            &paint,
+        );
+    }
+
+    fn create_burning_paint(&self, x_offset: f32, current_y: f32, time: f32) -> Paint {
         // Use character position as random seed for phase offset
         let phase_offset = (x_offset * 0.1 + current_y * 0.07) % std::f32::consts::TAU;
-        let time = self.animation_start.elapsed().as_secs_f32() * 2.5;
+        let time = time * 2.5;
 
         // Subtle oscillation - stays reddish-orange
         let cycle = (time + phase_offset).sin() * 0.5 + 0.5; // 0.0 to 1.0
@@ -444,26 +635,24 @@ impl<'a> TextContentRenderer<'a> {
 
         let mut burning_paint = Paint::color(Color::rgbf(r, g, b));
         burning_paint.set_font(self.fonts);
-        burning_paint.set_font_size(16.0 * self.scale);
+        burning_paint.set_font_size(self.font_size());
         burning_paint
     }
 
     fn draw_scrollbar(
         &mut self,
         tab: &Tab,
-        start_y: f32,
-        padding: f32,
-        line_height: f32,
-        scroll_offset: usize,
+        view: &View,
         hovered_scrollbar: bool,
         dragging_scrollbar: bool,
     ) {
-        let max_visible_lines = ((self.height - start_y - padding) / line_height).ceil() as usize;
-        let total_lines = tab.total_lines().max(1);
+        let max_visible_lines =
+            ((self.area.height - view.start_y - view.padding) / view.line_height).ceil() as usize;
+        let total_rows = tab.total_rows().max(1);
 
-        if total_lines > max_visible_lines {
-            let scrollbar = ScrollbarWidget::new(self.width, self.height, self.scale);
-            if let Some(metrics) = scrollbar.metrics(total_lines, max_visible_lines, scroll_offset)
+        if total_rows > max_visible_lines {
+            let scrollbar = ScrollbarWidget::new(self.area.width, self.area.height, self.scale);
+            if let Some(metrics) = scrollbar.metrics(total_rows, max_visible_lines, view.scroll_row)
             {
                 let mut path = Path::new();
                 path.rounded_rect(
@@ -493,12 +682,27 @@ impl<'a> TextContentRenderer<'a> {
         }
     }
 
-    fn measure_char_width(&self, paint: &Paint) -> f32 {
-        if let Ok(metrics) = self.canvas.measure_text(0.0, 0.0, "M", paint) {
-            metrics.width()
-        } else {
-            9.6 * self.scale // Fallback approximate width
-        }
+    /// Content font in the given theme color
+    fn content_paint(&self, color: (f32, f32, f32)) -> Paint {
+        let mut paint = Paint::color(Color::rgbf(color.0, color.1, color.2));
+        paint.set_font(self.fonts);
+        paint.set_font_size(self.font_size());
+        paint
+    }
+
+    /// Content font size in physical pixels, including zoom
+    fn font_size(&self) -> f32 {
+        rendering::CONTENT_FONT_SIZE * self.scale * self.zoom
+    }
+
+    /// Glyph layout for a line, measured with the content font on a cache miss
+    fn line_layout(&mut self, line: &str) -> Rc<LineLayout> {
+        let canvas = &*self.canvas;
+        let mut paint = Paint::color(Color::white());
+        paint.set_font(self.fonts);
+        paint.set_font_size(self.font_size());
+        self.layout_cache
+            .get(line, |ch| super::measure_char_advance(canvas, &paint, ch))
     }
 }
 
@@ -0,0 +1,178 @@
+//! Snippets: a trigger word that expands into text with tab stops
+//!
+//! Bodies mark tab stops with `$1`, `$2`, ... and the final cursor position
+//! with `$0`; `${1:text}` gives a stop default text. `\$` is a literal
+//! dollar sign. Stops are visited in number order with `$0` last; without
+//! a `$0` the cursor ends after the snippet. Repeating a number only
+//! repeats its text, the copies are not tab stops.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snippet {
+    pub trigger: String,
+    pub body: String,
+}
+
+/// A snippet body ready to insert
+#[derive(Debug, PartialEq)]
+pub struct Expansion {
+    pub text: String,
+    /// Char ranges within `text`, in visiting order; the last is the final
+    /// cursor position
+    pub stops: Vec<(usize, usize)>,
+}
+
+/// Chars that can make up a trigger word
+fn is_trigger_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_' || ch == '-'
+}
+
+/// Start column and text of the trigger word ending at `col`
+pub fn trigger_before(line: &str, col: usize) -> Option<(usize, String)> {
+    let before: Vec<char> = line.chars().take(col).collect();
+    let len = before
+        .iter()
+        .rev()
+        .take_while(|ch| is_trigger_char(**ch))
+        .count();
+    if len == 0 {
+        return None;
+    }
+    let start = before.len() - len;
+    Some((start, before[start..].iter().collect()))
+}
+
+/// Expand a body for insertion on a line indented with `indent`
+pub fn expand(body: &str, indent: &str) -> Expansion {
+    let chars: Vec<char> = body.chars().collect();
+    let mut text = String::new();
+    let mut len = 0;
+    // (number, start, end) of each first occurrence
+    let mut stops: Vec<(u32, usize, usize)> = Vec::new();
+    let mut defaults: Vec<(u32, String)> = Vec::new();
+    let push = |text: &mut String, len: &mut usize, s: &str| {
+        text.push_str(s);
+        *len += s.chars().count();
+    };
+
+    let mut i = 0;
+    while i < chars.len() {
+        let ch = chars[i];
+        if ch == '\\' && chars.get(i + 1) == Some(&'$') {
+            push(&mut text, &mut len, "$");
+            i += 2;
+            continue;
+        }
+        if ch == '\n' {
+            push(&mut text, &mut len, "\n");
+            push(&mut text, &mut len, indent);
+            i += 1;
+            continue;
+        }
+        if ch == '$' {
+            if let Some((number, default, next)) = parse_stop(&chars, i + 1) {
+                let default = match default {
+                    Some(default) => default,
+                    None => defaults
+                        .iter()
+                        .find(|(n, _)| *n == number)
+                        .map(|(_, text)| text.clone())
+                        .unwrap_or_default(),
+                };
+                let start = len;
+                push(&mut text, &mut len, &default);
+                if !stops.iter().any(|(n, _, _)| *n == number) {
+                    stops.push((number, start, len));
+                    defaults.push((number, default));
+                }
+                i = next;
+                continue;
+            }
+        }
+        let mut buf = [0; 4];
+        push(&mut text, &mut len, ch.encode_utf8(&mut buf));
+        i += 1;
+    }
+
+    if !stops.iter().any(|(number, _, _)| *number == 0) {
+        stops.push((0, len, len));
+    }
+    // Number order with $0 last
+    stops.sort_by_key(|(number, _, _)| if *number == 0 { u32::MAX } else { *number });
+    Expansion {
+        text,
+        stops: stops
+            .into_iter()
+            .map(|(_, start, end)| (start, end))
+            .collect(),
+    }
+}
+
+/// Parse `N` or `{N:default}` after a `$` at `chars[at..]`. Returns the
+/// number, the default and the index after the placeholder.
+fn parse_stop(chars: &[char], at: usize) -> Option<(u32, Option<String>, usize)> {
+    let digits = |from: usize| {
+        chars[from..]
+            .iter()
+            .take_while(|ch| ch.is_ascii_digit())
+            .count()
+    };
+    if chars.get(at) == Some(&'{') {
+        let len = digits(at + 1);
+        let number = chars[at + 1..at + 1 + len]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .ok()?;
+        let after = at + 1 + len;
+        return match chars.get(after) {
+            Some('}') => Some((number, None, after + 1)),
+            Some(':') => {
+                let close = chars[after + 1..].iter().position(|ch| *ch == '}')?;
+                let default = chars[after + 1..after + 1 + close].iter().collect();
+                Some((number, Some(default), after + close + 2))
+            }
+            _ => None,
+        };
+    }
+    let len = digits(at);
+    let number = chars[at..at + len]
+        .iter()
+        .collect::<String>()
+        .parse()
+        .ok()?;
+    Some((number, None, at + len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trigger_before() {
+        assert_eq!(
+            trigger_before("  say meet", 10),
+            Some((6, "meet".to_string()))
+        );
+        assert_eq!(
+            trigger_before("code-block here", 10),
+            Some((0, "code-block".to_string()))
+        );
+        assert_eq!(trigger_before("end. ", 5), None);
+    }
+
+    #[test]
+    fn test_expand_orders_stops() {
+        let expansion = expand("${2:b} $1 \\$5 $0!", "");
+        assert_eq!(expansion.text, "b  $5 !");
+        assert_eq!(expansion.stops, vec![(2, 2), (0, 1), (6, 6)]);
+    }
+
+    #[test]
+    fn test_expand_indents_and_adds_final_stop() {
+        let expansion = expand("- ${1:item}\n- $1", "  ");
+        assert_eq!(expansion.text, "- item\n  - item");
+        assert_eq!(expansion.stops, vec![(2, 6), (15, 15)]);
+    }
+}
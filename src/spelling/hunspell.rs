@@ -0,0 +1,316 @@
+//! Reader for Hunspell `.aff` / `.dic` dictionaries
+//!
+//! Supports what plain word checking needs: prefix and suffix rules with
+//! their conditions (combined when both allow cross products), the flag
+//! formats, `NEEDAFFIX`, `FORBIDDENWORD`, `ONLYINCOMPOUND`, and the `TRY`
+//! and `REP` hints used for suggestions. Compounding is not supported.
+
+use std::collections::HashMap;
+
+type Flag = u32;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum FlagFormat {
+    /// One character per flag
+    #[default]
+    Single,
+    /// Two characters per flag
+    Long,
+    /// Comma-separated numbers
+    Num,
+}
+
+impl FlagFormat {
+    fn parse(self, text: &str) -> Vec<Flag> {
+        match self {
+            FlagFormat::Single => text.chars().map(Flag::from).collect(),
+            FlagFormat::Long => text
+                .chars()
+                .collect::<Vec<_>>()
+                .chunks(2)
+                .map(|pair| pair.iter().fold(0, |flag, &ch| flag << 16 | Flag::from(ch)))
+                .collect(),
+            FlagFormat::Num => text
+                .split(',')
+                .filter_map(|number| number.trim().parse().ok())
+                .collect(),
+        }
+    }
+}
+
+/// One character position of an affix condition
+#[derive(Debug, Clone)]
+enum CharClass {
+    Any,
+    Is(char),
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+}
+
+impl CharClass {
+    fn matches(&self, ch: char) -> bool {
+        match self {
+            CharClass::Any => true,
+            CharClass::Is(expected) => ch == *expected,
+            CharClass::OneOf(set) => set.contains(&ch),
+            CharClass::NoneOf(set) => !set.contains(&ch),
+        }
+    }
+}
+
+/// Parse a condition such as `[^aeiou]y` or `.`
+fn parse_condition(text: &str) -> Vec<CharClass> {
+    let mut classes = Vec::new();
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        classes.push(match ch {
+            '.' => CharClass::Any,
+            '[' => {
+                let set: String = chars.by_ref().take_while(|&ch| ch != ']').collect();
+                match set.strip_prefix('^') {
+                    Some(rest) => CharClass::NoneOf(rest.chars().collect()),
+                    None => CharClass::OneOf(set.chars().collect()),
+                }
+            }
+            _ => CharClass::Is(ch),
+        });
+    }
+    // A lone "." means no condition at all
+    if matches!(classes.as_slice(), [CharClass::Any]) {
+        classes.clear();
+    }
+    classes
+}
+
+#[derive(Debug, Clone)]
+struct Affix {
+    flag: Flag,
+    cross_product: bool,
+    /// Removed from the root before adding
+    strip: String,
+    add: String,
+    condition: Vec<CharClass>,
+}
+
+impl Affix {
+    /// Whether the condition holds at the end (suffix) or start (prefix)
+    /// of `root`
+    fn fits(&self, root: &str, suffix: bool) -> bool {
+        let chars: Vec<char> = root.chars().collect();
+        if chars.len() < self.condition.len() {
+            return false;
+        }
+        let part = if suffix {
+            &chars[chars.len() - self.condition.len()..]
+        } else {
+            &chars[..self.condition.len()]
+        };
+        part.iter()
+            .zip(&self.condition)
+            .all(|(&ch, class)| class.matches(ch))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Dictionary {
+    /// Roots with their flags
+    words: HashMap<String, Vec<Flag>>,
+    prefixes: Vec<Affix>,
+    suffixes: Vec<Affix>,
+    /// Roots with this flag only count with an affix
+    need_affix: Option<Flag>,
+    forbidden: Option<Flag>,
+    only_in_compound: Option<Flag>,
+    /// Characters to try when looking for suggestions, most likely first
+    pub(super) try_chars: Vec<char>,
+    /// Common misspelled fragments and their replacements
+    pub(super) replacements: Vec<(String, String)>,
+}
+
+/// Text of a dictionary file, decoded as its `SET` line says
+pub fn decode(bytes: &[u8], encoding: &str) -> String {
+    if encoding.eq_ignore_ascii_case("UTF-8") {
+        String::from_utf8_lossy(bytes).into_owned()
+    } else {
+        // The ISO 8859 sets share their ASCII half; Latin-1 is exact
+        bytes.iter().map(|&byte| char::from(byte)).collect()
+    }
+}
+
+/// Encoding named by the `SET` line of an `.aff` file (UTF-8 without one)
+pub fn encoding(aff: &[u8]) -> String {
+    String::from_utf8_lossy(aff)
+        .lines()
+        .find_map(|line| line.strip_prefix("SET "))
+        .map(|set| set.trim().to_string())
+        .unwrap_or_else(|| "UTF-8".to_string())
+}
+
+impl Dictionary {
+    pub fn parse(aff: &str, dic: &str) -> Self {
+        let mut dictionary = Dictionary::default();
+        let mut format = FlagFormat::Single;
+        let mut cross_products: HashMap<Flag, bool> = HashMap::new();
+        let first_flag = |text: &str, format: FlagFormat| format.parse(text).first().copied();
+
+        for line in aff.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["FLAG", "long", ..] => format = FlagFormat::Long,
+                ["FLAG", "num", ..] => format = FlagFormat::Num,
+                ["TRY", chars, ..] => dictionary.try_chars = chars.chars().collect(),
+                ["NEEDAFFIX", flag, ..] => dictionary.need_affix = first_flag(flag, format),
+                ["FORBIDDENWORD", flag, ..] => dictionary.forbidden = first_flag(flag, format),
+                ["ONLYINCOMPOUND", flag, ..] => {
+                    dictionary.only_in_compound = first_flag(flag, format)
+                }
+                ["REP", from, to, ..] => dictionary
+                    .replacements
+                    .push((from.replace('_', " "), to.replace('_', " "))),
+                // Header of an affix class: "SFX S Y 4"
+                ["PFX" | "SFX", flag, cross @ ("Y" | "N"), count]
+                    if count.parse::<usize>().is_ok() =>
+                {
+                    if let Some(flag) = first_flag(flag, format) {
+                        cross_products.insert(flag, *cross == "Y");
+                    }
+                }
+                // One rule: "SFX S y ies [^aeiou]y"
+                [kind @ ("PFX" | "SFX"), flag, strip, add, rest @ ..] => {
+                    let Some(flag) = first_flag(flag, format) else {
+                        continue;
+                    };
+                    // Flags after the affix text (two-level affixes) are
+                    // not supported and are dropped
+                    let add = add.split('/').next().unwrap_or("");
+                    let affix = Affix {
+                        flag,
+                        cross_product: cross_products.get(&flag).copied().unwrap_or(false),
+                        strip: if *strip == "0" {
+                            String::new()
+                        } else {
+                            strip.to_string()
+                        },
+                        add: if add == "0" {
+                            String::new()
+                        } else {
+                            add.to_string()
+                        },
+                        condition: parse_condition(rest.first().copied().unwrap_or(".")),
+                    };
+                    if *kind == "PFX" {
+                        dictionary.prefixes.push(affix);
+                    } else {
+                        dictionary.suffixes.push(affix);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // The first line of a .dic file is the (approximate) word count
+        for line in dic.lines().skip(1) {
+            let entry = line.split_whitespace().next().unwrap_or("");
+            let (word, flags) = entry.split_once('/').unwrap_or((entry, ""));
+            if word.is_empty() {
+                continue;
+            }
+            dictionary
+                .words
+                .entry(word.to_string())
+                .or_default()
+                .extend(format.parse(flags));
+        }
+        dictionary
+    }
+
+    /// Whether `word` is spelled correctly, as written (no case folding)
+    pub fn check(&self, word: &str) -> bool {
+        if let Some(flags) = self.words.get(word) {
+            let bare_ok = [self.need_affix, self.forbidden, self.only_in_compound]
+                .iter()
+                .flatten()
+                .all(|flag| !flags.contains(flag));
+            if bare_ok {
+                return true;
+            }
+        }
+        if self.check_suffixed(word, None) {
+            return true;
+        }
+        self.prefixes.iter().any(|prefix| {
+            let Some(rest) = word.strip_prefix(prefix.add.as_str()) else {
+                return false;
+            };
+            if rest.is_empty() {
+                return false;
+            }
+            let root = format!("{}{}", prefix.strip, rest);
+            if !prefix.fits(&root, false) {
+                return false;
+            }
+            self.has_flag(&root, prefix.flag)
+                || (prefix.cross_product && self.check_suffixed(&root, Some(prefix.flag)))
+        })
+    }
+
+    /// Whether `word` is a root plus one of its suffixes; with a prefix
+    /// flag the root must also take that prefix
+    fn check_suffixed(&self, word: &str, prefix_flag: Option<Flag>) -> bool {
+        self.suffixes.iter().any(|suffix| {
+            if prefix_flag.is_some() && !suffix.cross_product {
+                return false;
+            }
+            let Some(stem) = word.strip_suffix(suffix.add.as_str()) else {
+                return false;
+            };
+            if stem.is_empty() {
+                return false;
+            }
+            let root = format!("{}{}", stem, suffix.strip);
+            suffix.fits(&root, true)
+                && self.has_flag(&root, suffix.flag)
+                && prefix_flag.is_none_or(|flag| self.has_flag(&root, flag))
+        })
+    }
+
+    fn has_flag(&self, root: &str, flag: Flag) -> bool {
+        self.words.get(root).is_some_and(|flags| {
+            flags.contains(&flag)
+                && self
+                    .forbidden
+                    .is_none_or(|forbidden| !flags.contains(&forbidden))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "SET UTF-8\nTRY esiarn\nNEEDAFFIX X\n\nPFX U Y 1\nPFX U 0 un .\n\nSFX S Y 2\nSFX S y ies [^aeiou]y\nSFX S 0 s [^y]\n\nSFX D Y 2\nSFX D 0 d e\nSFX D 0 ed [^e]\n";
+    const DIC: &str = "4\nparty/S\nlock/UDS\nbake/D\nwalk/XD\n";
+
+    #[test]
+    fn test_affixes() {
+        let dictionary = Dictionary::parse(AFF, DIC);
+        for word in [
+            "party", "parties", "locks", "unlocked", "unlock", "baked", "walked",
+        ] {
+            assert!(dictionary.check(word), "{word}");
+        }
+        // Wrong rule for the ending, a prefix the root lacks, and a root
+        // that needs an affix
+        for word in ["partys", "unbaked", "bakeed", "walk"] {
+            assert!(!dictionary.check(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_long_flags_and_latin1() {
+        let aff = "SET ISO8859-1\nFLAG long\nSFX Aa Y 1\nSFX Aa 0 s .\n";
+        let dic = decode(b"1\ncaf\xe9/AaBb\n", &encoding(aff.as_bytes()));
+        let dictionary = Dictionary::parse(aff, &dic);
+        assert!(dictionary.check("cafés"));
+    }
+}
@@ -0,0 +1,339 @@
+//! Spell checking for notes
+//!
+//! Words are checked against a Hunspell dictionary from the system (picked by
+//! `LANG`, falling back to English) plus the user's personal word list.
+//! Without a system dictionary nothing is checked. Code spans, fenced code
+//! blocks, URLs, wiki links, tags and identifier-like words are never
+//! checked.
+
+mod hunspell;
+
+use crate::links;
+use crate::markdown;
+pub use hunspell::Dictionary;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Where system dictionaries are installed
+const SYSTEM_DICTIONARIES: &str = "/usr/share/hunspell";
+/// Most suggestions offered for one word
+const MAX_SUGGESTIONS: usize = 6;
+/// Lines whose misspellings are remembered before the cache starts over
+const MAX_CACHED_LINES: usize = 4096;
+
+pub struct SpellChecker {
+    dictionary: Dictionary,
+    /// Personal words, lowercased
+    personal: HashSet<String>,
+    /// Misspellings by line text, so unchanged lines are not checked again
+    cache: RefCell<HashMap<String, Vec<(usize, usize)>>>,
+}
+
+impl SpellChecker {
+    pub fn new(dictionary: Dictionary, personal: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            dictionary,
+            personal: personal
+                .into_iter()
+                .map(|word| word.to_lowercase())
+                .collect(),
+            cache: RefCell::default(),
+        }
+    }
+
+    /// The system dictionary for the user's language, if one is installed
+    pub fn load_dictionary() -> Option<Dictionary> {
+        let language = std::env::var("LANG").unwrap_or_default();
+        let language = language.split(['.', '@']).next().unwrap_or("");
+        [language, "en_US", "en_GB"]
+            .iter()
+            .filter(|name| !name.is_empty())
+            .find_map(|name| load_system_dictionary(Path::new(SYSTEM_DICTIONARIES), name))
+    }
+
+    /// Accept `word` from now on
+    pub fn add_word(&mut self, word: &str) {
+        self.personal.insert(word.to_lowercase());
+        self.cache.get_mut().clear();
+    }
+
+    pub fn is_correct(&self, word: &str) -> bool {
+        let word = word.replace('’', "'");
+        if self.personal.contains(&word.to_lowercase()) || self.dictionary.check(&word) {
+            return true;
+        }
+        // "Hello" and "HELLO" are fine when "hello" is
+        let lower = word.to_lowercase();
+        let rest_lower = word.chars().skip(1).all(|ch| !ch.is_uppercase());
+        let all_upper = word.chars().all(|ch| !ch.is_lowercase());
+        if (rest_lower || all_upper) && self.dictionary.check(&lower) {
+            return true;
+        }
+        all_upper && self.dictionary.check(&title_case(&lower))
+    }
+
+    /// Char ranges of the misspelled words in one line of prose
+    pub fn misspellings(&self, line: &str) -> Vec<(usize, usize)> {
+        if let Some(found) = self.cache.borrow().get(line) {
+            return found.clone();
+        }
+        let found: Vec<(usize, usize)> = words(line)
+            .into_iter()
+            .filter(|&(start, end)| {
+                let word: String = line.chars().skip(start).take(end - start).collect();
+                !self.is_correct(&word)
+            })
+            .collect();
+        let mut cache = self.cache.borrow_mut();
+        if cache.len() >= MAX_CACHED_LINES {
+            cache.clear();
+        }
+        cache.insert(line.to_string(), found.clone());
+        found
+    }
+
+    /// Corrections for `word`, most likely first, in the word's case
+    pub fn suggestions(&self, word: &str) -> Vec<String> {
+        let lower = word.to_lowercase();
+        let chars: Vec<char> = lower.chars().collect();
+        let mut letters: Vec<char> = if self.dictionary.try_chars.is_empty() {
+            ('a'..='z').collect()
+        } else {
+            self.dictionary
+                .try_chars
+                .iter()
+                .flat_map(|ch| ch.to_lowercase())
+                .collect()
+        };
+        let mut seen_letters = HashSet::new();
+        letters.retain(|&letter| seen_letters.insert(letter));
+
+        // Known replacements first, then single-letter edits
+        let mut candidates: Vec<String> = Vec::new();
+        for (from, to) in &self.dictionary.replacements {
+            for (index, _) in lower.match_indices(from.as_str()) {
+                candidates.push(format!(
+                    "{}{}{}",
+                    &lower[..index],
+                    to,
+                    &lower[index + from.len()..]
+                ));
+            }
+        }
+        let join = |parts: &[&[char]]| {
+            parts
+                .iter()
+                .flat_map(|part| part.iter())
+                .collect::<String>()
+        };
+        for index in 0..chars.len().saturating_sub(1) {
+            let mut swapped = chars.clone();
+            swapped.swap(index, index + 1);
+            candidates.push(swapped.into_iter().collect());
+        }
+        for index in 0..chars.len() {
+            for &letter in &letters {
+                if letter != chars[index] {
+                    candidates.push(join(&[&chars[..index], &[letter], &chars[index + 1..]]));
+                }
+            }
+        }
+        for index in 0..chars.len() {
+            candidates.push(join(&[&chars[..index], &chars[index + 1..]]));
+        }
+        for index in 0..=chars.len() {
+            for &letter in &letters {
+                candidates.push(join(&[&chars[..index], &[letter], &chars[index..]]));
+            }
+        }
+        // Two words run together
+        for index in 1..chars.len() {
+            candidates.push(join(&[&chars[..index], &[' '], &chars[index..]]));
+        }
+
+        let mut seen = HashSet::new();
+        let mut suggestions: Vec<String> = candidates
+            .into_iter()
+            .filter(|candidate| *candidate != lower && seen.insert(candidate.clone()))
+            .filter(|candidate| candidate.split(' ').all(|part| self.is_correct(part)))
+            .collect();
+        // Keeping the first letter is the better guess
+        suggestions.sort_by_key(|candidate| candidate.chars().next() != chars.first().copied());
+        suggestions.truncate(MAX_SUGGESTIONS);
+        suggestions
+            .into_iter()
+            .map(|suggestion| match_case(&suggestion, word))
+            .collect()
+    }
+}
+
+/// `<name>.aff` and `<name>.dic` from `dir`, if both exist
+fn load_system_dictionary(dir: &Path, name: &str) -> Option<Dictionary> {
+    let aff = fs::read(dir.join(format!("{name}.aff"))).ok()?;
+    let dic = fs::read(dir.join(format!("{name}.dic"))).ok()?;
+    let encoding = hunspell::encoding(&aff);
+    Some(Dictionary::parse(
+        &hunspell::decode(&aff, &encoding),
+        &hunspell::decode(&dic, &encoding),
+    ))
+}
+
+fn title_case(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// `suggestion` capitalized like `original`
+fn match_case(suggestion: &str, original: &str) -> String {
+    let mut letters = original.chars().filter(|ch| ch.is_alphabetic());
+    match letters.next() {
+        Some(first)
+            if first.is_uppercase()
+                && original.chars().count() > 1
+                && letters.all(char::is_uppercase) =>
+        {
+            suggestion.to_uppercase()
+        }
+        Some(first) if first.is_uppercase() => title_case(suggestion),
+        _ => suggestion.to_string(),
+    }
+}
+
+/// Char ranges of the words to check in one line: runs of letters (with
+/// inner apostrophes) outside code spans, URLs, wiki links and tags, and not
+/// part of identifiers, numbers or paths. All-caps words (acronyms),
+/// mixed-case words and single letters are left alone.
+pub fn words(line: &str) -> Vec<(usize, usize)> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut skipped = vec![false; chars.len()];
+
+    let mut in_code = false;
+    for (index, &ch) in chars.iter().enumerate() {
+        if ch == '`' {
+            in_code = !in_code;
+        }
+        skipped[index] |= in_code;
+    }
+    for link in links::line_links(line) {
+        skipped[link.start..link.end.min(chars.len())].fill(true);
+    }
+    // URLs, e-mail addresses and paths, by whitespace-separated token
+    let mut start = 0;
+    for token in line.split(char::is_whitespace) {
+        let len = token.chars().count();
+        if token.contains("://") || token.starts_with("www.") || token.contains('@') {
+            skipped[start..start + len].fill(true);
+        }
+        start += len + 1;
+    }
+
+    let is_word_char = |index: usize| {
+        chars.get(index).is_some_and(|&ch| {
+            ch.is_alphabetic()
+                || (matches!(ch, '\'' | '’')
+                    && index > 0
+                    && chars[index - 1].is_alphabetic()
+                    && chars
+                        .get(index + 1)
+                        .is_some_and(|next| next.is_alphabetic()))
+        })
+    };
+    // Touching one of these makes a run part of something that is not prose
+    let joins = |index: Option<usize>| {
+        index
+            .and_then(|index| chars.get(index))
+            .is_some_and(|&ch| ch.is_numeric() || matches!(ch, '_' | '#' | '/' | '\\' | '@'))
+    };
+
+    let mut words = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        if !is_word_char(index) {
+            index += 1;
+            continue;
+        }
+        let start = index;
+        while is_word_char(index) {
+            index += 1;
+        }
+        let word = &chars[start..index];
+        let inner_upper = word.iter().skip(1).any(|ch| ch.is_uppercase());
+        if word.len() > 1
+            && !inner_upper
+            && !skipped[start..index].iter().any(|&skip| skip)
+            && !joins(start.checked_sub(1))
+            && !joins(Some(index))
+        {
+            words.push((start, index));
+        }
+    }
+    words
+}
+
+/// For each of `lines`, whether it is prose to check (outside fenced code
+/// blocks)
+pub fn prose_lines<S: AsRef<str>>(lines: impl IntoIterator<Item = S>) -> Vec<bool> {
+    let mut in_fence = false;
+    lines
+        .into_iter()
+        .map(|line| {
+            if markdown::is_fence(line.as_ref()) {
+                in_fence = !in_fence;
+                return false;
+            }
+            !in_fence
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "SET UTF-8\nTRY esianrtolcdugmphbyfvkwzxjq'\n\n\
+        SFX S Y 1\nSFX S 0 s .\n\nSFX G Y 1\nSFX G 0 ing .\n";
+    const DIC: &str = "5\nhouse/S\nwork/SG\ndon't\ntheir\nthe\n";
+
+    fn checker() -> SpellChecker {
+        SpellChecker::new(Dictionary::parse(AFF, DIC), ["fnord".to_string()])
+    }
+
+    fn misspelled(line: &str) -> Vec<String> {
+        checker()
+            .misspellings(line)
+            .into_iter()
+            .map(|(start, end)| line.chars().skip(start).take(end - start).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_skips_code_links_and_urls() {
+        assert_eq!(
+            misspelled("Teh `fooo` [[Qwrty]] https://exmple.com #tagz snake_casse API Fnord"),
+            ["Teh"]
+        );
+        assert_eq!(
+            prose_lines("a\n```\ncode\n```\nb".lines()),
+            [true, false, false, false, true]
+        );
+    }
+
+    #[test]
+    fn test_case_and_suggestions() {
+        let checker = checker();
+        assert!(checker.is_correct("Houses"));
+        assert!(checker.is_correct("WORKING"));
+        assert!(checker.is_correct("don’t"));
+        assert!(!checker.is_correct("wrok"));
+        assert_eq!(
+            checker.suggestions("Wrok").first().map(String::as_str),
+            Some("Work")
+        );
+        assert!(checker.suggestions("thier").contains(&"their".to_string()));
+    }
+}
@@ -0,0 +1,210 @@
+//! Document statistics: words, characters, sentences, paragraphs, headings
+//! and reading time
+//!
+//! Counts are kept per line so an edit only recounts the lines it touched.
+//! Word and character totals are running sums; sentences, paragraphs and
+//! headings depend on neighbouring lines and are counted once per change.
+
+use std::cell::Cell;
+
+/// Words read per minute for the reading time estimate
+const WORDS_PER_MINUTE: usize = 200;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Stats {
+    pub words: usize,
+    pub chars: usize,
+    pub chars_no_spaces: usize,
+    pub sentences: usize,
+    pub paragraphs: usize,
+    pub headings: usize,
+}
+
+impl Stats {
+    /// Count everything in `text` at once (used for selections)
+    pub fn of(text: &str) -> Self {
+        let mut lines = LineStats::default();
+        lines.splice(0, 0, text.split('\n'));
+        lines.total()
+    }
+
+    /// Minutes to read, rounded up; zero only for an empty text
+    pub fn reading_minutes(&self) -> usize {
+        self.words.div_ceil(WORDS_PER_MINUTE)
+    }
+}
+
+/// What one line contributes to the totals
+#[derive(Debug, Clone, Copy, Default)]
+struct LineCount {
+    words: usize,
+    chars: usize,
+    chars_no_spaces: usize,
+    /// Runs of `.`, `!` or `?` that end a sentence
+    sentence_ends: usize,
+    /// Words follow the last sentence end (or there is none)
+    open_sentence: bool,
+    blank: bool,
+    heading: bool,
+    fence: bool,
+}
+
+impl LineCount {
+    fn of(line: &str) -> Self {
+        let line = line.trim_end_matches(['\n', '\r']);
+        let chars: Vec<char> = line.chars().collect();
+        let mut sentence_ends = 0;
+        let mut open_sentence = false;
+        for (index, &ch) in chars.iter().enumerate() {
+            if matches!(ch, '.' | '!' | '?') {
+                let next = chars.get(index + 1);
+                if next.is_none_or(|next| next.is_whitespace()) && open_sentence {
+                    sentence_ends += 1;
+                    open_sentence = false;
+                }
+            } else if ch.is_alphanumeric() {
+                open_sentence = true;
+            }
+        }
+        let level = line.bytes().take_while(|byte| *byte == b'#').count();
+        let rest = &line[level..];
+        LineCount {
+            // Lone markup such as `#`, `-` or ``` is not a word
+            words: line
+                .split_whitespace()
+                .filter(|word| word.chars().any(char::is_alphanumeric))
+                .count(),
+            chars: chars.len(),
+            chars_no_spaces: chars.iter().filter(|ch| !ch.is_whitespace()).count(),
+            sentence_ends,
+            open_sentence,
+            blank: line.trim().is_empty(),
+            heading: (1..=6).contains(&level) && (rest.is_empty() || rest.starts_with([' ', '\t'])),
+            fence: crate::markdown::is_fence(line),
+        }
+    }
+}
+
+/// Per-line counts for a whole document, kept in step with its edits
+#[derive(Debug, Clone, Default)]
+pub struct LineStats {
+    lines: Vec<LineCount>,
+    words: usize,
+    chars: usize,
+    chars_no_spaces: usize,
+    /// Sentences, paragraphs and headings; None until counted after a change
+    structure: Cell<Option<(usize, usize, usize)>>,
+}
+
+impl LineStats {
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Replace `removed` lines from `first` with the counts of `lines`
+    pub fn splice<S: AsRef<str>>(
+        &mut self,
+        first: usize,
+        removed: usize,
+        lines: impl IntoIterator<Item = S>,
+    ) {
+        let first = first.min(self.lines.len());
+        let end = (first + removed).min(self.lines.len());
+        let added: Vec<LineCount> = lines
+            .into_iter()
+            .map(|line| LineCount::of(line.as_ref()))
+            .collect();
+        for line in &added {
+            self.words += line.words;
+            self.chars += line.chars;
+            self.chars_no_spaces += line.chars_no_spaces;
+        }
+        for line in self.lines.splice(first..end, added) {
+            self.words -= line.words;
+            self.chars -= line.chars;
+            self.chars_no_spaces -= line.chars_no_spaces;
+        }
+        self.structure.set(None);
+    }
+
+    pub fn total(&self) -> Stats {
+        let (sentences, paragraphs, headings) = match self.structure.get() {
+            Some(structure) => structure,
+            None => {
+                let structure = self.count_structure();
+                self.structure.set(Some(structure));
+                structure
+            }
+        };
+        Stats {
+            words: self.words,
+            chars: self.chars,
+            chars_no_spaces: self.chars_no_spaces,
+            sentences,
+            paragraphs,
+            headings,
+        }
+    }
+
+    /// Sentences, paragraphs and headings, walking the lines in order
+    fn count_structure(&self) -> (usize, usize, usize) {
+        let (mut sentences, mut paragraphs, mut headings) = (0, 0, 0);
+        let mut in_fence = false;
+        let mut in_paragraph = false;
+        let mut open_sentence = false;
+        for line in &self.lines {
+            // Code blocks, headings and blank lines end a paragraph
+            let prose = !(in_fence || line.fence || line.blank || line.heading);
+            if line.fence {
+                in_fence = !in_fence;
+            }
+            if line.heading && !in_fence {
+                headings += 1;
+            }
+            if !prose {
+                sentences += usize::from(open_sentence);
+                in_paragraph = false;
+                open_sentence = false;
+                continue;
+            }
+            if !in_paragraph {
+                paragraphs += 1;
+                in_paragraph = true;
+            }
+            sentences += line.sentence_ends;
+            open_sentence = line.open_sentence || (open_sentence && line.sentence_ends == 0);
+        }
+        sentences += usize::from(open_sentence);
+        (sentences, paragraphs, headings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts() {
+        let text = "# Notes\n\nFirst sentence. Second one!\nStill the\nsame paragraph\n\n```\nlet x = 1.0;\n```\nv1.2 is out...";
+        let stats = Stats::of(text);
+        assert_eq!(stats.headings, 1);
+        assert_eq!(stats.paragraphs, 2);
+        assert_eq!(stats.sentences, 4);
+        assert_eq!(stats.words, 15);
+        assert_eq!(Stats::of("a b").chars, 3);
+        assert_eq!(Stats::of("a b").chars_no_spaces, 2);
+        assert_eq!(Stats::of("").reading_minutes(), 0);
+        assert_eq!(Stats::of("word").reading_minutes(), 1);
+    }
+
+    #[test]
+    fn test_splice_matches_full_count() {
+        let mut lines = LineStats::default();
+        lines.splice(0, 0, ["One.", "", "Two"]);
+        lines.splice(1, 2, ["Still one.", "Three words here"]);
+        assert_eq!(
+            lines.total(),
+            Stats::of("One.\nStill one.\nThree words here")
+        );
+    }
+}
@@ -1,16 +1,73 @@
 //! Tab state - represents a single open file
 
+use crate::config;
+use crate::date;
+use crate::folding::{self, FoldMap};
+use crate::front_matter::{self, FrontMatter};
+use crate::links;
+use crate::markdown;
 use crate::persistence::{self, TabState};
+use crate::snippets::{self, Snippet};
+use crate::spelling::{self, SpellChecker};
+use crate::stats::Stats;
+use crate::tables::{self, TableEdit};
+use crate::tags;
 use crate::text_buffer::TextBuffer;
+use crate::ui::{SaveStatus, StatusInfo};
 use native_dialog::FileDialog;
+use std::cell::RefCell;
+use std::cmp::Reverse;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// How edits reach the disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveMode {
+    /// Written after every edit (notes in the data directory)
+    Auto,
+    /// Written only on explicit save (files opened from elsewhere)
+    Manual,
+}
+
+impl SaveMode {
+    fn for_path(path: &Path) -> Self {
+        if persistence::is_in_data_dir(path) {
+            SaveMode::Auto
+        } else {
+            SaveMode::Manual
+        }
+    }
+}
+
+/// Foldable line ranges, first and last line
+type FoldRegions = Rc<[(usize, usize)]>;
+
+/// Views of the buffer that are costly to build, each kept with the buffer
+/// revision it was built from
+#[derive(Default)]
+struct ViewCache {
+    /// Foldable regions, and whether they were found as markdown
+    fold_regions: Option<(u64, bool, FoldRegions)>,
+    fold_map: Option<(u64, Rc<FoldMap>)>,
+    /// Which lines are prose, outside fenced code
+    prose_lines: Option<(u64, Rc<[bool]>)>,
+}
 
 pub struct Tab {
     buffer: TextBuffer,
+    cache: RefCell<ViewCache>,
+    /// Metadata block kept out of the editor and written back on save
+    front_matter: Option<FrontMatter>,
     path: Option<PathBuf>,
     title: String,
     modified: bool,
+    save_mode: SaveMode,
+    folder: Option<PathBuf>, // Where auto-save creates the file, if not the data dir root
+    /// When the file is renamed after its first heading; each auto-save
+    /// while the title is a placeholder pushes it back
+    rename_due: Option<Instant>,
     scroll_offset: usize, // Line offset for scrolling
     scroll_offset_x: f32, // Horizontal pixel offset
     word_wrap: bool,
@@ -23,9 +80,14 @@ impl Tab {
 
         Self {
             buffer: TextBuffer::new(),
+            cache: RefCell::default(),
+            front_matter: None,
             path: None,
             title: format!("Untitled-{}", num),
             modified: false,
+            save_mode: SaveMode::Auto,
+            folder: None,
+            rename_due: None,
             scroll_offset: 0,
             scroll_offset_x: 0.0,
             word_wrap: false,
@@ -34,24 +96,59 @@ impl Tab {
 
     pub fn from_file(path: PathBuf) -> Option<Self> {
         let content = fs::read_to_string(&path).ok()?;
-        let title = persistence::load_note_title(&path).unwrap_or_else(|| {
-            path.file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("Unknown")
-                .to_string()
-        });
+        let (front_matter, body) = split_front_matter(&path, &content);
+        let title = front_matter::parse(&content)
+            .0
+            .and_then(|front_matter| front_matter.title)
+            .unwrap_or_else(|| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("Unknown")
+                    .to_string()
+            });
 
         Some(Self {
-            buffer: TextBuffer::from_str(&content),
+            buffer: TextBuffer::from_str(body),
+            cache: RefCell::default(),
+            front_matter,
+            save_mode: SaveMode::for_path(&path),
             path: Some(path),
             title,
             modified: false,
+            folder: None,
+            rename_due: None,
             scroll_offset: 0,
             scroll_offset_x: 0.0,
             word_wrap: false,
         })
     }
 
+    /// Untitled note that will be created inside `folder` on first save
+    pub fn new_untitled_in(folder: PathBuf) -> Self {
+        let mut tab = Self::new_untitled();
+        tab.folder = Some(folder);
+        tab
+    }
+
+    /// Unsaved copy of this tab's content, cursor and view settings
+    pub fn duplicate(&self) -> Self {
+        let mut copy = Self::new_untitled();
+        copy.buffer = TextBuffer::from_str(self.buffer.content());
+        copy.title = format!("{} (copy)", self.title);
+        copy.front_matter = self.front_matter.clone().map(|mut front_matter| {
+            front_matter.title = Some(copy.title.clone());
+            front_matter.created = None;
+            front_matter
+        });
+        copy.scroll_offset = self.scroll_offset;
+        copy.scroll_offset_x = self.scroll_offset_x;
+        copy.word_wrap = self.word_wrap;
+        copy.folder = self.folder.clone();
+        let (line, col) = self.buffer.char_to_line_col(self.buffer.cursor());
+        copy.buffer.set_cursor_by_line_col(line, col, false);
+        copy
+    }
+
     pub fn open() -> Option<Self> {
         let path = FileDialog::new()
             .add_filter("Markdown", &["md", "markdown", "txt"])
@@ -63,69 +160,292 @@ impl Tab {
     pub fn save(&mut self) {
         let path = match &self.path {
             Some(p) => p.clone(),
-            None => {
-                match FileDialog::new()
-                    .add_filter("Markdown", &["md"])
-                    .set_filename(&self.title)
-                    .show_save_single_file()
-                {
-                    Ok(Some(p)) => {
-                        self.path = Some(p.clone());
-                        self.title = p
-                            .file_name()
-                            .and_then(|n| n.to_str())
-                            .unwrap_or("Unknown")
-                            .to_string();
-                        p
+            None => match self.choose_save_path() {
+                Some(p) => {
+                    if persistence::is_in_data_dir(&p) {
+                        self.init_front_matter();
                     }
-                    _ => return,
+                    self.adopt_path(p.clone());
+                    p
                 }
-            }
+                None => return,
+            },
         };
 
-        if fs::write(&path, self.buffer.content()).is_ok() {
-            let _ = persistence::save_note_title(&path, &self.title);
+        if self.write_to(&path).is_ok() {
             self.modified = false;
         }
     }
 
-    /// Auto-save to data directory (silent, no dialog)
+    /// Save to a new path picked in a dialog. The tab switches to the new
+    /// file; the old one is left untouched.
+    pub fn save_as(&mut self) -> bool {
+        let Some(path) = self.choose_save_path() else {
+            return false;
+        };
+        if self.write_to(&path).is_err() {
+            return false;
+        }
+        self.adopt_path(path);
+        self.modified = false;
+        true
+    }
+
+    /// Reload the file from disk, dropping unsaved edits as one undoable step
+    pub fn revert(&mut self) -> bool {
+        let Some(path) = &self.path else {
+            return false;
+        };
+        let Ok(content) = fs::read_to_string(path) else {
+            return false;
+        };
+        let (front_matter, body) = split_front_matter(path, &content);
+        if body != self.buffer.content() {
+            self.buffer.replace_all(body);
+        }
+        self.front_matter = front_matter;
+        let changed = self.modified;
+        self.modified = false;
+        changed
+    }
+
+    /// Rename a tag in the front matter and body as one undoable step.
+    /// Returns true when the note used the tag.
+    pub fn rename_tag(&mut self, from: &str, to: &str) -> bool {
+        let in_front_matter = self
+            .front_matter
+            .as_mut()
+            .is_some_and(|front_matter| tags::rename_front_matter_tag(front_matter, from, to));
+        let body = tags::rename_inline_tag(self.buffer.content(), from, to);
+        if let Some(body) = &body {
+            self.buffer.replace_all(body);
+        }
+        let changed = in_front_matter || body.is_some();
+        if changed {
+            self.modified = true;
+        }
+        changed
+    }
+
+    /// Point `[[from]]` links at `to` (undoable); returns true if any changed
+    pub fn retarget_links(&mut self, from: &str, to: &str) -> bool {
+        let Some(body) = links::retarget(self.buffer.content(), from, to) else {
+            return false;
+        };
+        self.buffer.replace_all(&body);
+        self.modified = true;
+        true
+    }
+
+    /// Move the file to a path picked in a dialog, keeping its title.
+    /// Returns the old and new path on success.
+    pub fn move_file(&mut self) -> Option<(PathBuf, PathBuf)> {
+        let old = self.path.clone()?;
+        let file_name = old.file_name()?.to_string_lossy().to_string();
+        let mut dialog = FileDialog::new()
+            .add_filter("Markdown", &["md", "markdown", "txt"])
+            .set_filename(&file_name);
+        if let Some(dir) = old.parent() {
+            dialog = dialog.set_location(dir);
+        }
+        let new = dialog.show_save_single_file().ok()??;
+        if new == old {
+            return None;
+        }
+
+        persistence::move_note(&old, &new).ok()?;
+        self.save_mode = SaveMode::for_path(&new);
+        self.path = Some(new.clone());
+        Some((old, new))
+    }
+
+    /// Move the note into `folder`, keeping its file name unless a note there
+    /// already has it. Nothing moves onto a file open in another tab
+    /// (`open_paths`). A note that has not been written yet just remembers
+    /// the folder for its first save. Returns the old and new path when a
+    /// file was moved.
+    pub fn move_to_folder(&mut self, folder: &Path, open_paths: &[PathBuf]) -> Option<(PathBuf, PathBuf)> {
+        let Some(old) = self.path.clone() else {
+            self.folder = Some(folder.to_path_buf());
+            return None;
+        };
+        let stem = old.file_stem()?.to_string_lossy().to_string();
+        let new = persistence::unique_note_path(folder, &stem, Some(&old));
+        if new == old || open_paths.contains(&new) {
+            return None;
+        }
+
+        persistence::move_note(&old, &new).ok()?;
+        self.save_mode = SaveMode::for_path(&new);
+        self.path = Some(new.clone());
+        Some((old, new))
+    }
+
+    /// Rename a note's file to match its title. Only notes in the data
+    /// directory are renamed; files opened from elsewhere keep their name.
+    /// Returns the old and new path when the file was renamed.
+    pub fn rename_file_to_title(&mut self) -> Option<(PathBuf, PathBuf)> {
+        let old = self.path.clone()?;
+        if !persistence::is_in_data_dir(&old) {
+            return None;
+        }
+        let slug = persistence::note_slug(&self.title, self.buffer.content());
+        let new = persistence::unique_note_path(old.parent()?, &slug, Some(&old));
+        if new == old {
+            return None;
+        }
+
+        persistence::move_note(&old, &new).ok()?;
+        self.path = Some(new.clone());
+        Some((old, new))
+    }
+
+    fn choose_save_path(&self) -> Option<PathBuf> {
+        let mut dialog = FileDialog::new()
+            .add_filter("Markdown", &["md"])
+            .set_filename(&self.title);
+        if let Some(dir) = self
+            .path
+            .as_deref()
+            .and_then(Path::parent)
+            .or(self.folder.as_deref())
+        {
+            dialog = dialog.set_location(dir);
+        }
+        dialog.show_save_single_file().ok()?
+    }
+
+    /// Point the tab at a file it was just saved to
+    fn adopt_path(&mut self, path: PathBuf) {
+        self.save_mode = SaveMode::for_path(&path);
+        self.title = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("Unknown")
+            .to_string();
+        self.path = Some(path);
+    }
+
+    /// Auto-save to data directory (silent, no dialog). Does nothing for
+    /// tabs in `SaveMode::Manual`, which keep their changes until saved.
+    /// While the title is a placeholder, the file follows the first heading,
+    /// but only once typing has paused (see `rename_if_due`).
     pub fn auto_save(&mut self) {
+        if self.save_mode == SaveMode::Manual {
+            return;
+        }
+
         // If we have a path, save there
-        if let Some(ref path) = self.path {
-            let _ = fs::write(path, self.buffer.content());
-            let _ = persistence::save_note_title(path, &self.title);
+        if let Some(path) = self.path.clone() {
+            let _ = self.write_to(&path);
             self.modified = false;
+            if config::notes::RENAME_FILES_WITH_TITLE
+                && persistence::is_placeholder_title(&self.title)
+            {
+                let delay = Duration::from_millis(config::notes::RENAME_DELAY_MS);
+                self.rename_due = Some(Instant::now() + delay);
+            }
             return;
         }
 
-        // Otherwise, create a new file in data directory
-        let filename = persistence::generate_note_filename();
-        if let Ok(path) = persistence::save_note(&filename, self.buffer.content()) {
-            self.path = Some(path.clone());
-            let _ = persistence::save_note_title(&path, &self.title);
+        // Otherwise, create a new file in data directory, named after the
+        // title or first heading
+        let Ok(root) = persistence::ensure_data_dir() else {
+            return;
+        };
+        let dir = self.folder.clone().unwrap_or(root);
+        let slug = persistence::note_slug(&self.title, self.buffer.content());
+        let path = persistence::unique_note_path(&dir, &slug, None);
+        self.init_front_matter();
+        if self.write_to(&path).is_ok() {
+            self.path = Some(path);
             self.modified = false;
         }
     }
 
-    #[allow(dead_code)]
+    /// Rename the file after its first heading once the auto-save delay has
+    /// passed. Returns the old and new path when the file was renamed.
+    pub fn rename_if_due(&mut self, now: Instant) -> Option<(PathBuf, PathBuf)> {
+        if self.rename_due.is_some_and(|due| due <= now) {
+            self.finish_rename()
+        } else {
+            None
+        }
+    }
+
+    /// Do a pending rename right away, on explicit save or close
+    pub fn finish_rename(&mut self) -> Option<(PathBuf, PathBuf)> {
+        self.rename_due.take()?;
+        self.rename_file_to_title()
+    }
+
+    /// Start front matter for a note file that is about to be created
+    fn init_front_matter(&mut self) {
+        let front_matter = self.front_matter.get_or_insert_with(FrontMatter::default);
+        front_matter.title = Some(self.title.clone());
+        front_matter.created.get_or_insert_with(date::now_timestamp);
+    }
+
+    /// Write front matter and content to `path`, stamping `updated` on notes
+    /// in the data directory
+    fn write_to(&mut self, path: &Path) -> std::io::Result<()> {
+        if let Some(front_matter) = &mut self.front_matter {
+            if persistence::is_in_data_dir(path) {
+                front_matter.updated = Some(date::now_timestamp());
+            }
+        }
+        let content = front_matter::join(self.front_matter.as_ref(), self.buffer.content());
+        persistence::save_note(path, &content)
+    }
+
+    /// Unsaved tabs and `.md` files get markdown editing behaviours
+    pub fn is_markdown(&self) -> bool {
+        self.path.as_ref().is_none_or(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
+        })
+    }
+
     pub fn path(&self) -> Option<&PathBuf> {
         self.path.as_ref()
     }
 
-    #[allow(dead_code)]
+    /// Whether the tab has edits that are not on disk yet
     pub fn is_modified(&self) -> bool {
         self.modified
     }
 
+    pub fn save_mode(&self) -> SaveMode {
+        self.save_mode
+    }
+
+    pub fn set_save_mode(&mut self, mode: SaveMode) {
+        self.save_mode = mode;
+    }
+
     pub fn title(&self) -> &str {
         &self.title
     }
 
+    /// Rename the tab. Notes keep the title in their front matter; files
+    /// opened from elsewhere are edited as plain text, so only the tab is
+    /// renamed.
     pub fn set_title(&mut self, title: String) {
         self.title = title;
-        if let Some(path) = &self.path {
-            let _ = persistence::save_note_title(path, &self.title);
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+        if self.front_matter.is_none() && !persistence::is_in_data_dir(&path) {
+            return;
+        }
+        self.front_matter.get_or_insert_with(FrontMatter::default).title = Some(self.title.clone());
+        match self.save_mode {
+            SaveMode::Auto => {
+                if self.write_to(&path).is_ok() {
+                    self.modified = false;
+                }
+            }
+            SaveMode::Manual => self.modified = true,
         }
     }
 
@@ -137,16 +457,275 @@ impl Tab {
         self.buffer.cursor()
     }
 
+    /// Insert a typed character. In markdown notes Enter adds table rows and
+    /// continues lists and quotes, and brackets and emphasis are paired.
     pub fn insert_char(&mut self, ch: char) {
-        self.buffer.insert(ch);
+        let handled = self.is_markdown()
+            && match ch {
+                '\n' => {
+                    tables::insert_newline(&mut self.buffer)
+                        || markdown::insert_newline(&mut self.buffer)
+                }
+                _ => markdown::insert_pair(&mut self.buffer, ch),
+            };
+        if !handled {
+            self.buffer.insert(ch);
+        }
         self.modified = true;
     }
 
     pub fn backspace(&mut self) {
-        self.buffer.backspace();
+        if !(self.is_markdown() && markdown::delete_pair(&mut self.buffer)) {
+            self.buffer.backspace();
+        }
+        self.modified = true;
+    }
+
+    /// Tab / Shift+Tab in a table: align it and move to the next or previous
+    /// cell. Returns false outside tables.
+    pub fn move_table_cell(&mut self, backward: bool) -> bool {
+        if !self.is_markdown() || !tables::move_to_cell(&mut self.buffer, backward) {
+            return false;
+        }
+        self.modified = true;
+        true
+    }
+
+    /// Insert or delete a row or column of the table at the cursor
+    pub fn edit_table(&mut self, edit: TableEdit) -> bool {
+        if !self.is_markdown() || !tables::edit(&mut self.buffer, edit) {
+            return false;
+        }
+        self.modified = true;
+        true
+    }
+
+    pub fn in_table(&self) -> bool {
+        self.is_markdown() && tables::in_table(&self.buffer)
+    }
+
+    /// Check or uncheck the task box on `line`
+    pub fn toggle_task(&mut self, line: usize) -> bool {
+        if !markdown::toggle_task(&mut self.buffer, line) {
+            return false;
+        }
+        self.modified = true;
+        true
+    }
+
+    /// Whether `col` on `line` falls on a task box (`[ ]` or `[x]`)
+    pub fn is_on_task_box(&self, line: usize, col: usize) -> bool {
+        markdown::task_box(self.line_text(line)).is_some_and(|start| (start..=start + 3).contains(&col))
+    }
+
+    /// Indent (or outdent) the list items at the cursor or in the selection.
+    /// Returns false when they are not list items.
+    pub fn indent_list_items(&mut self, outdent: bool) -> bool {
+        if !self.is_markdown() || !markdown::indent_list_items(&mut self.buffer, outdent) {
+            return false;
+        }
+        self.modified = true;
+        true
+    }
+
+    /// Misspelled word touching `col` on `line`: its column range and text.
+    /// Only prose in notes is checked.
+    pub fn misspelling_at(
+        &self,
+        checker: &SpellChecker,
+        line: usize,
+        col: usize,
+    ) -> Option<(usize, usize, String)> {
+        if !self.is_markdown() || self.prose_lines().get(line) != Some(&true) {
+            return None;
+        }
+        let text = self.buffer.line_text(line);
+        let (start, end) = checker
+            .misspellings(&text)
+            .into_iter()
+            .find(|&(start, end)| start <= col && col <= end)?;
+        Some((start, end, text.chars().skip(start).take(end - start).collect()))
+    }
+
+    /// Replace columns `start..end` of `line` with `text` as one undoable edit
+    pub fn replace_in_line(&mut self, line: usize, start: usize, end: usize, text: &str) {
+        let line_start = self.buffer.line_to_char(line);
+        self.buffer
+            .replace_range(line_start + start, line_start + end, text);
         self.modified = true;
     }
 
+    /// Markdown headings, empty for other files
+    pub fn headings(&self) -> Vec<markdown::Heading> {
+        if !self.is_markdown() {
+            return Vec::new();
+        }
+        markdown::headings(self.content())
+    }
+
+    /// Move the cursor to the start of the next (or previous) visible heading
+    pub fn go_to_heading(&mut self, forward: bool) -> bool {
+        let line = self.cursor_line();
+        let folds = self.fold_map();
+        let headings = self.headings();
+        let mut candidates = headings.iter().filter(|heading| !folds.is_hidden(heading.line));
+        let target = if forward {
+            candidates.find(|heading| heading.line > line)
+        } else {
+            candidates.rfind(|heading| heading.line < line)
+        };
+        match target {
+            Some(heading) => {
+                self.set_cursor_position(heading.line, 0, false);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Foldable line ranges: indented blocks, and in markdown also heading
+    /// sections and fenced code blocks
+    pub fn fold_regions(&self) -> FoldRegions {
+        let revision = self.buffer.revision();
+        let is_markdown = self.is_markdown();
+        let mut cache = self.cache.borrow_mut();
+        match &cache.fold_regions {
+            Some((built, markdown, regions)) if *built == revision && *markdown == is_markdown => {
+                regions.clone()
+            }
+            _ => {
+                let regions: FoldRegions = folding::regions(self.content(), is_markdown).into();
+                cache.fold_regions = Some((revision, is_markdown, regions.clone()));
+                regions
+            }
+        }
+    }
+
+    /// Unfold the fold on the cursor line, or fold the innermost region
+    /// around the cursor
+    pub fn toggle_fold(&mut self) -> bool {
+        let line = self.cursor_line();
+        if self.buffer.unfold_line(line) {
+            return true;
+        }
+        let innermost = self
+            .fold_regions()
+            .iter()
+            .copied()
+            .filter(|&(first, last)| first <= line && line <= last)
+            .max_by_key(|&(first, last)| (first, Reverse(last)));
+        let Some((first, last)) = innermost else {
+            return false;
+        };
+        self.buffer.fold_lines(first, last);
+        self.keep_scroll_on_visible_line();
+        true
+    }
+
+    /// Unfold the fold whose header is `line`, or fold the largest region
+    /// starting there (a click on a fold marker)
+    pub fn toggle_fold_at(&mut self, line: usize) -> bool {
+        if self.buffer.unfold_line(line) {
+            return true;
+        }
+        let outermost = self
+            .fold_regions()
+            .iter()
+            .copied()
+            .filter(|&(first, _)| first == line)
+            .max_by_key(|&(_, last)| last);
+        let Some((first, last)) = outermost else {
+            return false;
+        };
+        self.buffer.fold_lines(first, last);
+        self.keep_scroll_on_visible_line();
+        true
+    }
+
+    /// Fold every region, nested ones included
+    pub fn fold_all(&mut self) -> bool {
+        let mut regions = self.fold_regions().to_vec();
+        if regions.is_empty() {
+            return false;
+        }
+        // Outer regions first, so folding them keeps the inner folds
+        regions.sort_by_key(|&(first, last)| Reverse(last - first));
+        for (first, last) in regions {
+            self.buffer.fold_lines(first, last);
+        }
+        self.keep_scroll_on_visible_line();
+        true
+    }
+
+    pub fn unfold_all(&mut self) -> bool {
+        self.buffer.unfold_all()
+    }
+
+    pub fn fold_map(&self) -> Rc<FoldMap> {
+        let revision = self.buffer.revision();
+        let mut cache = self.cache.borrow_mut();
+        match &cache.fold_map {
+            Some((built, map)) if *built == revision => map.clone(),
+            _ => {
+                let map = Rc::new(FoldMap::new(&self.buffer.folded_lines()));
+                cache.fold_map = Some((revision, map.clone()));
+                map
+            }
+        }
+    }
+
+    /// For each line, whether it is prose to check for links and spelling
+    pub fn prose_lines(&self) -> Rc<[bool]> {
+        let revision = self.buffer.revision();
+        let mut cache = self.cache.borrow_mut();
+        match &cache.prose_lines {
+            Some((built, lines)) if *built == revision => lines.clone(),
+            _ => {
+                let lines: Rc<[bool]> = spelling::prose_lines(
+                    (0..self.buffer.len_lines()).map(|line| self.buffer.line_text(line)),
+                )
+                .into();
+                cache.prose_lines = Some((revision, lines.clone()));
+                lines
+            }
+        }
+    }
+
+    /// Number of rows on screen, counting each fold as its header line
+    pub fn total_rows(&self) -> usize {
+        self.fold_map().rows(self.total_lines())
+    }
+
+    /// Scroll offset in rows
+    pub fn scroll_row(&self) -> usize {
+        self.fold_map().row_of(self.scroll_offset)
+    }
+
+    /// Scroll to `row`; returns false if already there
+    pub fn set_scroll_row(&mut self, row: usize) -> bool {
+        let line = self.fold_map().line_at_row(row);
+        self.set_scroll_offset(line)
+    }
+
+    /// Line shown at `row` rows below the top of the view (negative is above)
+    pub fn line_at_visual_row(&self, row: isize) -> usize {
+        let folds = self.fold_map();
+        let last_row = folds.rows(self.total_lines()).saturating_sub(1);
+        let row = (folds.row_of(self.scroll_offset) as isize + row).max(0) as usize;
+        folds.line_at_row(row.min(last_row))
+    }
+
+    /// Row of `line` counted from the top of the view
+    pub fn visual_row_of(&self, line: usize) -> usize {
+        let folds = self.fold_map();
+        folds.row_of(line).saturating_sub(folds.row_of(self.scroll_offset))
+    }
+
+    fn keep_scroll_on_visible_line(&mut self) {
+        let folds = self.fold_map();
+        self.scroll_offset = folds.line_at_row(folds.row_of(self.scroll_offset));
+    }
+
     pub fn delete_word_left(&mut self) {
         self.buffer.delete_word_left();
         self.modified = true;
@@ -178,6 +757,14 @@ impl Tab {
         self.buffer.move_word_right(selecting);
     }
 
+    pub fn move_subword_left(&mut self, selecting: bool) {
+        self.buffer.move_subword_left(selecting);
+    }
+
+    pub fn move_subword_right(&mut self, selecting: bool) {
+        self.buffer.move_subword_right(selecting);
+    }
+
     pub fn move_up(&mut self, selecting: bool) {
         self.buffer.move_up(selecting);
     }
@@ -224,13 +811,15 @@ impl Tab {
     }
 
     pub fn scroll_up(&mut self, lines: usize) {
-        self.scroll_offset = self.scroll_offset.saturating_sub(lines);
+        let folds = self.fold_map();
+        self.scroll_offset = folds.line_at_row(folds.row_of(self.scroll_offset).saturating_sub(lines));
     }
 
     pub fn scroll_down(&mut self, lines: usize, visible_lines: usize) {
-        let total_lines = self.buffer.len_lines();
-        let max_scroll = total_lines.saturating_sub(visible_lines);
-        self.scroll_offset = (self.scroll_offset + lines).min(max_scroll);
+        let folds = self.fold_map();
+        let max_scroll = folds.rows(self.buffer.len_lines()).saturating_sub(visible_lines);
+        let row = (folds.row_of(self.scroll_offset) + lines).min(max_scroll);
+        self.scroll_offset = folds.line_at_row(row);
     }
 
     /// Get the current cursor line number
@@ -246,29 +835,33 @@ impl Tab {
     }
 
     /// Ensure cursor is visible by auto-scrolling
+    ///
+    /// `cursor_x` is the cursor's pixel offset within its line, as laid out
+    /// by the renderer.
     pub fn ensure_cursor_visible(
         &mut self,
         visible_lines: usize,
         visible_width: f32,
+        cursor_x: f32,
         char_width: f32,
     ) {
-        let cursor_line = self.cursor_line();
+        // Work in rows so folded lines take no space
+        let folds = self.fold_map();
+        let cursor_row = folds.row_of(self.cursor_line());
+        let scroll_row = folds.row_of(self.scroll_offset);
 
         // Scroll up if cursor is above visible area
-        if cursor_line < self.scroll_offset {
-            self.scroll_offset = cursor_line;
+        if cursor_row < scroll_row {
+            self.scroll_offset = folds.line_at_row(cursor_row);
         }
 
         // Scroll down if cursor is below visible area
-        if cursor_line >= self.scroll_offset + visible_lines {
-            self.scroll_offset = cursor_line.saturating_sub(visible_lines - 1);
+        if cursor_row >= scroll_row + visible_lines {
+            self.scroll_offset = folds.line_at_row(cursor_row.saturating_sub(visible_lines - 1));
         }
 
         // Horizontal scrolling (only if wrap is off)
         if !self.word_wrap {
-            let cursor_col = self.cursor_col();
-            let cursor_x = cursor_col as f32 * char_width;
-
             // Scroll left
             if cursor_x < self.scroll_offset_x {
                 self.scroll_offset_x = cursor_x;
@@ -288,15 +881,9 @@ impl Tab {
         self.buffer.set_cursor_by_line_col(line, col, selecting);
     }
 
-    /// Convert visual column to character column (accounting for tab width)
-    pub fn visual_col_to_char_col(&self, line: usize, visual_col: usize) -> usize {
-        // Get the line content and use VisualLine abstraction
-        if let Some(line_content) = self.content().lines().nth(line) {
-            let visual_line = crate::visual_position::VisualLine::new(line_content);
-            visual_line.visual_col_to_char_col(visual_col)
-        } else {
-            0
-        }
+    /// Get the text of a line (without its line break), empty if out of range
+    pub fn line_text(&self, line: usize) -> &str {
+        self.content().lines().nth(line).unwrap_or("")
     }
 
     #[allow(dead_code)]
@@ -304,6 +891,38 @@ impl Tab {
         self.buffer.selection_range()
     }
 
+    /// Cursor position and document facts for the status bar
+    pub fn status_info(&self) -> StatusInfo {
+        let stats = self.buffer.stats();
+        let save = if self.path.is_none() {
+            SaveStatus::NotSaved
+        } else if self.modified {
+            SaveStatus::Modified
+        } else {
+            SaveStatus::Saved
+        };
+        StatusInfo {
+            line: self.cursor_line(),
+            col: self.cursor_col(),
+            selected: self.selection_range().map_or(0, |(start, end)| end - start),
+            words: stats.words,
+            chars: stats.chars,
+            crlf: self.buffer.uses_crlf(),
+            word_wrap: self.word_wrap,
+            save,
+            no_dictionary: false,
+        }
+    }
+
+    /// Statistics of the selection, or of the whole note when nothing is
+    /// selected; the flag tells which
+    pub fn statistics(&self) -> (Stats, bool) {
+        match self.copy_selection() {
+            Some(text) => (Stats::of(&text), true),
+            None => (self.buffer.stats(), false),
+        }
+    }
+
     pub fn selection_range_line_col(&self) -> Option<((usize, usize), (usize, usize))> {
         if let Some((start, end)) = self.buffer.selection_range() {
             Some((
@@ -338,6 +957,67 @@ impl Tab {
         false
     }
 
+    /// Expand the snippet whose trigger ends at the cursor, selecting its
+    /// first tab stop. Returns false when no trigger matches.
+    pub fn expand_snippet(&mut self, snippets: &[Snippet]) -> bool {
+        if self.buffer.has_selection() {
+            return false;
+        }
+        let line = self.line_text(self.cursor_line());
+        let Some((start_col, word)) = snippets::trigger_before(line, self.cursor_col()) else {
+            return false;
+        };
+        let Some(snippet) = snippets.iter().find(|snippet| snippet.trigger == word) else {
+            return false;
+        };
+        let indent: String = line.chars().take_while(|ch| *ch == ' ' || *ch == '\t').collect();
+        let expansion = snippets::expand(&snippet.body, &indent);
+
+        let end = self.buffer.cursor();
+        let start = end - (self.cursor_col() - start_col);
+        self.buffer.replace_range(start, end, &expansion.text);
+        let stops = expansion
+            .stops
+            .iter()
+            .map(|(from, to)| (start + from, start + to))
+            .collect();
+        self.buffer.set_regions(stops);
+        self.modified = true;
+        self.go_to_tab_stop(0);
+        true
+    }
+
+    /// Move to the next (`forward`) or previous tab stop of the expanded
+    /// snippet. Returns false when no snippet is active or the cursor has
+    /// left its stops, which ends it.
+    pub fn cycle_tab_stop(&mut self, forward: bool) -> bool {
+        let cursor = self.buffer.cursor();
+        let inside = self
+            .buffer
+            .regions()
+            .iter()
+            .any(|(start, end)| (*start..=*end).contains(&cursor));
+        let Some(current) = self.buffer.focused_region().filter(|_| inside) else {
+            self.buffer.clear_regions();
+            return false;
+        };
+        let index = if forward {
+            current + 1
+        } else {
+            current.saturating_sub(1)
+        };
+        self.go_to_tab_stop(index);
+        true
+    }
+
+    /// Select a tab stop; reaching the last one ends the snippet
+    fn go_to_tab_stop(&mut self, index: usize) {
+        self.buffer.focus_region(index);
+        if index + 1 >= self.buffer.regions().len() {
+            self.buffer.clear_regions();
+        }
+    }
+
     pub fn select_all(&mut self) {
         self.buffer.select_all();
     }
@@ -352,6 +1032,7 @@ impl Tab {
 
     pub fn move_lines_up(&mut self) -> bool {
         self.buffer.move_lines_up();
+        self.modified = true;
         // Assume buffering actions modify state for now, returns void in TextBuffer usually
         // But for AppResult::Redraw optimization, better to assume true or check hash?
         // Let's assume true for actions.
@@ -360,17 +1041,20 @@ impl Tab {
 
     pub fn move_lines_down(&mut self) -> bool {
         self.buffer.move_lines_down();
+        self.modified = true;
         true
     }
 
     pub fn undo(&mut self) -> bool {
-        self.buffer.undo();
-        true
+        let changed = self.buffer.undo();
+        self.modified |= changed;
+        changed
     }
 
     pub fn redo(&mut self) -> bool {
-        self.buffer.redo();
-        true
+        let changed = self.buffer.redo();
+        self.modified |= changed;
+        changed
     }
 
     pub fn total_lines(&self) -> usize {
@@ -394,6 +1078,7 @@ impl Tab {
             scroll_offset: self.scroll_offset,
             scroll_offset_x: self.scroll_offset_x,
             word_wrap: self.word_wrap,
+            auto_save: Some(self.save_mode == SaveMode::Auto),
         })
     }
 
@@ -402,6 +1087,20 @@ impl Tab {
         self.scroll_offset = state.scroll_offset;
         self.scroll_offset_x = state.scroll_offset_x.max(0.0);
         self.word_wrap = state.word_wrap;
+        if let Some(auto_save) = state.auto_save {
+            self.save_mode = if auto_save { SaveMode::Auto } else { SaveMode::Manual };
+        }
+    }
+}
+
+/// Front matter and body of a file's text. Only notes in the data directory
+/// keep their front matter out of the editor; other files are edited as they
+/// are, so saving them writes back exactly what the user sees.
+fn split_front_matter<'a>(path: &Path, content: &'a str) -> (Option<FrontMatter>, &'a str) {
+    if persistence::is_in_data_dir(path) {
+        front_matter::parse(content)
+    } else {
+        (None, content)
     }
 }
 
@@ -432,4 +1131,82 @@ mod tests {
         tab.backspace();
         assert_eq!(tab.content(), "A");
     }
+
+    #[test]
+    fn test_edits_and_undo_mark_modified() {
+        let mut tab = Tab::new_untitled();
+        tab.set_save_mode(SaveMode::Manual);
+        assert!(!tab.undo());
+        assert!(!tab.is_modified());
+        tab.insert_char('x');
+        assert!(tab.is_modified());
+        // Manual tabs keep their changes until an explicit save
+        tab.auto_save();
+        assert!(tab.is_modified());
+        assert!(tab.path().is_none());
+    }
+
+    #[test]
+    fn test_auto_saves_rename_the_file_once() {
+        let folder = persistence::ensure_data_dir()
+            .unwrap()
+            .join(format!("rename-test-{}", std::process::id()));
+        fs::create_dir_all(&folder).unwrap();
+        let mut tab = Tab::new_untitled_in(folder.clone());
+        let mut moves = Vec::new();
+        for ch in "# Meeting".chars() {
+            tab.insert_char(ch);
+            tab.auto_save();
+            moves.extend(tab.rename_if_due(Instant::now()));
+        }
+        let settled = Instant::now() + Duration::from_millis(config::notes::RENAME_DELAY_MS);
+        moves.extend(tab.rename_if_due(settled));
+        moves.extend(tab.rename_if_due(settled));
+        assert_eq!(moves.len(), 1);
+        assert_eq!(tab.path(), Some(&folder.join("meeting.md")));
+        let _ = fs::remove_dir_all(&folder);
+    }
+
+    #[test]
+    fn test_save_mode_survives_session_state() {
+        let mut tab = Tab::new_untitled();
+        assert_eq!(tab.save_mode(), SaveMode::Auto);
+        let state = TabState {
+            path: PathBuf::from("elsewhere.md"),
+            cursor_line: 0,
+            cursor_col: 0,
+            scroll_offset: 0,
+            scroll_offset_x: 0.0,
+            word_wrap: false,
+            auto_save: Some(false),
+        };
+        tab.apply_state(&state);
+        assert_eq!(tab.save_mode(), SaveMode::Manual);
+    }
+
+    #[test]
+    fn test_fold_views_follow_edits() {
+        let mut tab = Tab::new_untitled();
+        tab.paste_text("# A\ntext");
+        assert_eq!(&*tab.fold_regions(), [(0, 1)]);
+        assert!(tab.toggle_fold_at(0));
+        assert!(tab.fold_map().is_hidden(1));
+        tab.unfold_all();
+        assert!(!tab.fold_map().is_hidden(1));
+        // Folding left the cursor at the end of the header
+        tab.paste_text("\n# B\nmore");
+        assert_eq!(&*tab.fold_regions(), [(1, 3)]);
+    }
+
+    #[test]
+    fn test_external_file_saved_as_is() {
+        let path = std::env::temp_dir().join(format!("fire-notes-external-{}.md", std::process::id()));
+        let content = "---\ntags:\n  - b\n  - a\nauthor: me\n---\nBody\n";
+        fs::write(&path, content).unwrap();
+        let mut tab = Tab::from_file(path.clone()).unwrap();
+        assert_eq!(tab.content(), content);
+        tab.save();
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+        let _ = fs::remove_file(&path);
+    }
 }
@@ -0,0 +1,460 @@
+//! Markdown pipe tables: parsing, aligned formatting and cell navigation
+//!
+//! A table is a run of lines starting with `|` whose second line is the
+//! delimiter row (`| --- | :-: |`). Every edit made through this module
+//! rewrites the whole table with padded cells, so columns stay aligned.
+
+use crate::text_buffer::TextBuffer;
+use crate::visual_position::get_char_visual_width;
+
+/// Narrowest column, so the delimiter row always has `---`
+const MIN_COLUMN_WIDTH: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Alignment {
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Table {
+    /// Leading whitespace of the first line, kept on every line
+    indent: String,
+    alignments: Vec<Alignment>,
+    /// The header row followed by the body rows; all as wide as `alignments`
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn parse(lines: &[String]) -> Option<Self> {
+        if lines.len() < 2 || !lines.iter().all(|line| is_table_line(line)) {
+            return None;
+        }
+        let alignments = split_cells(&lines[1])
+            .iter()
+            .map(|cell| parse_alignment(cell))
+            .collect::<Option<Vec<_>>>()?;
+        let mut rows: Vec<Vec<String>> = lines
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != 1)
+            .map(|(_, line)| split_cells(line))
+            .collect();
+        let columns = rows.iter().map(Vec::len).chain([alignments.len()]).max()?;
+        for row in &mut rows {
+            row.resize(columns, String::new());
+        }
+        let mut table = Self {
+            indent: lines[0]
+                .chars()
+                .take_while(|ch| ch.is_whitespace())
+                .collect(),
+            alignments,
+            rows,
+        };
+        table.alignments.resize(columns, Alignment::None);
+        Some(table)
+    }
+
+    fn columns(&self) -> usize {
+        self.alignments.len()
+    }
+
+    /// The table's lines with every column padded to its widest cell
+    fn format(&self) -> Vec<String> {
+        let widths: Vec<usize> = (0..self.columns())
+            .map(|column| {
+                self.rows
+                    .iter()
+                    .map(|row| display_width(&row[column]))
+                    .max()
+                    .unwrap_or(0)
+                    .max(MIN_COLUMN_WIDTH)
+            })
+            .collect();
+        let line = |cells: Vec<String>| format!("{}| {} |", self.indent, cells.join(" | "));
+        let row_line = |row: &Vec<String>| {
+            line(
+                row.iter()
+                    .zip(&widths)
+                    .zip(&self.alignments)
+                    .map(|((cell, width), alignment)| pad(cell, *width, *alignment))
+                    .collect(),
+            )
+        };
+
+        let delimiter = line(
+            widths
+                .iter()
+                .zip(&self.alignments)
+                .map(|(width, alignment)| match alignment {
+                    Alignment::None => "-".repeat(*width),
+                    Alignment::Left => format!(":{}", "-".repeat(width - 1)),
+                    Alignment::Right => format!("{}:", "-".repeat(width - 1)),
+                    Alignment::Center => format!(":{}:", "-".repeat(width - 2)),
+                })
+                .collect(),
+        );
+        let mut lines: Vec<String> = self.rows.iter().map(row_line).collect();
+        lines.insert(1, delimiter);
+        lines
+    }
+
+    fn empty_row(&self) -> Vec<String> {
+        vec![String::new(); self.columns()]
+    }
+}
+
+/// Whether a line can belong to a table
+pub fn is_table_line(line: &str) -> bool {
+    line.trim_start().starts_with('|')
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars().map(get_char_visual_width).sum()
+}
+
+fn pad(text: &str, width: usize, alignment: Alignment) -> String {
+    let gap = width.saturating_sub(display_width(text));
+    match alignment {
+        Alignment::Right => format!("{}{text}", " ".repeat(gap)),
+        Alignment::Center => format!("{}{text}{}", " ".repeat(gap / 2), " ".repeat(gap - gap / 2)),
+        Alignment::None | Alignment::Left => format!("{text}{}", " ".repeat(gap)),
+    }
+}
+
+/// `---`, `:--`, `--:` or `:-:`
+fn parse_alignment(cell: &str) -> Option<Alignment> {
+    let left = cell.starts_with(':');
+    let right = cell.len() > 1 && cell.ends_with(':');
+    let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+    if dashes.is_empty() || !dashes.chars().all(|ch| ch == '-') {
+        return None;
+    }
+    Some(match (left, right) {
+        (true, true) => Alignment::Center,
+        (true, false) => Alignment::Left,
+        (false, true) => Alignment::Right,
+        (false, false) => Alignment::None,
+    })
+}
+
+/// Char indices of the `|` separators of a line, skipping escaped `\|`
+fn pipes(line: &str) -> Vec<usize> {
+    let mut escaped = false;
+    let mut pipes = Vec::new();
+    for (index, ch) in line.chars().enumerate() {
+        if ch == '|' && !escaped {
+            pipes.push(index);
+        }
+        escaped = ch == '\\' && !escaped;
+    }
+    pipes
+}
+
+/// Trimmed cell contents of a table line
+fn split_cells(line: &str) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut bounds = pipes(line);
+    // A row without a closing pipe still ends its last cell
+    let closed = bounds.len() > 1
+        && bounds
+            .last()
+            .is_some_and(|last| chars[last + 1..].iter().all(|ch| ch.is_whitespace()));
+    if !closed {
+        bounds.push(chars.len());
+    }
+    bounds
+        .windows(2)
+        .map(|pair| {
+            chars[pair[0] + 1..pair[1]]
+                .iter()
+                .collect::<String>()
+                .trim()
+                .to_string()
+        })
+        .collect()
+}
+
+/// Column of the cell holding char column `col` of a table line
+fn cell_at(line: &str, col: usize) -> usize {
+    pipes(line)
+        .iter()
+        .filter(|pipe| **pipe < col)
+        .count()
+        .saturating_sub(1)
+}
+
+/// Char range of a cell's content in a formatted line (an empty range
+/// after the padding space for an empty cell)
+fn cell_range(line: &str, column: usize) -> (usize, usize) {
+    let chars: Vec<char> = line.chars().collect();
+    let pipes = pipes(line);
+    let start = pipes.get(column).map_or(chars.len(), |pipe| pipe + 1);
+    let end = pipes.get(column + 1).copied().unwrap_or(chars.len());
+    let cell = &chars[start..end];
+    match cell.iter().position(|ch| !ch.is_whitespace()) {
+        Some(first) => {
+            let last = cell
+                .iter()
+                .rposition(|ch| !ch.is_whitespace())
+                .unwrap_or(first);
+            (start + first, start + last + 1)
+        }
+        None => {
+            let col = (start + 1).min(end);
+            (col, col)
+        }
+    }
+}
+
+/// Table edits offered as commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableEdit {
+    InsertRow,
+    DeleteRow,
+    InsertColumn,
+    DeleteColumn,
+}
+
+/// The table around the cursor and the cell the cursor is in
+struct Located {
+    first: usize,
+    last: usize,
+    table: Table,
+    /// Index into `Table::rows` (the delimiter line counts as the header)
+    row: usize,
+    column: usize,
+}
+
+fn locate(buffer: &TextBuffer) -> Option<Located> {
+    let (line, col) = buffer.char_to_line_col(buffer.cursor());
+    if !is_table_line(&buffer.line_text(line)) {
+        return None;
+    }
+    let mut first = line;
+    while first > 0 && is_table_line(&buffer.line_text(first - 1)) {
+        first -= 1;
+    }
+    let mut last = line;
+    while last + 1 < buffer.len_lines() && is_table_line(&buffer.line_text(last + 1)) {
+        last += 1;
+    }
+    let lines: Vec<String> = (first..=last).map(|l| buffer.line_text(l)).collect();
+    let table = Table::parse(&lines)?;
+    let index = line - first;
+    let (row, column) = match index {
+        0 => (0, cell_at(&lines[0], col)),
+        1 => (0, 0),
+        _ => (index - 1, cell_at(&lines[index], col)),
+    };
+    Some(Located {
+        first,
+        last,
+        row,
+        column: column.min(table.columns() - 1),
+        table,
+    })
+}
+
+/// Rewrite the located table formatted, selecting the content of a cell
+fn write(buffer: &mut TextBuffer, at: &Located, row: usize, column: usize) {
+    let lines = at.table.format();
+    replace_lines(buffer, at, &lines.join("\n"));
+    let index = if row == 0 { 0 } else { row + 1 };
+    let (start, end) = cell_range(&lines[index], column);
+    let line_start = buffer.line_to_char(at.first + index);
+    buffer.set_selection(line_start + start, line_start + end);
+}
+
+fn replace_lines(buffer: &mut TextBuffer, at: &Located, text: &str) {
+    let start = buffer.line_to_char(at.first);
+    let end = buffer.line_to_char(at.last) + buffer.line_text(at.last).chars().count();
+    buffer.replace_range(start, end, text);
+}
+
+/// Tab / Shift+Tab inside a table: align it and select the next (or
+/// previous) cell. Tab in the last cell adds a row.
+pub fn move_to_cell(buffer: &mut TextBuffer, backward: bool) -> bool {
+    let Some(mut at) = locate(buffer) else {
+        return false;
+    };
+    let columns = at.table.columns();
+    let (row, column) = if backward {
+        if at.column > 0 {
+            (at.row, at.column - 1)
+        } else if at.row > 0 {
+            (at.row - 1, columns - 1)
+        } else {
+            (0, 0)
+        }
+    } else if at.column + 1 < columns {
+        (at.row, at.column + 1)
+    } else {
+        if at.row + 1 == at.table.rows.len() {
+            let row = at.table.empty_row();
+            at.table.rows.push(row);
+        }
+        (at.row + 1, 0)
+    };
+    write(buffer, &at, row, column);
+    true
+}
+
+/// Enter inside a table adds a row below the cursor's. Enter on an empty
+/// last row removes it and leaves the table.
+pub fn insert_newline(buffer: &mut TextBuffer) -> bool {
+    let Some(mut at) = locate(buffer) else {
+        return false;
+    };
+    let is_last = at.row + 1 == at.table.rows.len();
+    if at.row > 0 && is_last && at.table.rows[at.row].iter().all(String::is_empty) {
+        at.table.rows.pop();
+        let text = at.table.format().join("\n") + "\n";
+        replace_lines(buffer, &at, &text);
+        return true;
+    }
+    let row = at.table.empty_row();
+    at.table.rows.insert(at.row + 1, row);
+    write(buffer, &at, at.row + 1, 0);
+    true
+}
+
+/// Insert or delete a row or column at the cursor. Rows go below the
+/// cursor's and columns to its right; the header row and the last column
+/// cannot be deleted.
+pub fn edit(buffer: &mut TextBuffer, edit: TableEdit) -> bool {
+    let Some(mut at) = locate(buffer) else {
+        return false;
+    };
+    let (row, column) = match edit {
+        TableEdit::InsertRow => {
+            let row = at.table.empty_row();
+            at.table.rows.insert(at.row + 1, row);
+            (at.row + 1, at.column)
+        }
+        TableEdit::DeleteRow => {
+            if at.row == 0 {
+                return false;
+            }
+            at.table.rows.remove(at.row);
+            (at.row.min(at.table.rows.len() - 1), at.column)
+        }
+        TableEdit::InsertColumn => {
+            let column = at.column + 1;
+            at.table.alignments.insert(column, Alignment::None);
+            for row in &mut at.table.rows {
+                row.insert(column, String::new());
+            }
+            (at.row, column)
+        }
+        TableEdit::DeleteColumn => {
+            if at.table.columns() == 1 {
+                return false;
+            }
+            at.table.alignments.remove(at.column);
+            for row in &mut at.table.rows {
+                row.remove(at.column);
+            }
+            (at.row, at.column.min(at.table.columns() - 1))
+        }
+    };
+    write(buffer, &at, row, column);
+    true
+}
+
+/// Whether the cursor is inside a table
+pub fn in_table(buffer: &TextBuffer) -> bool {
+    locate(buffer).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_format_aligns_columns() {
+        let table = Table::parse(&lines("|Name|Qty|\n|:-|-:|\n| apple | 3\n|pear|12|x|")).unwrap();
+        assert_eq!(
+            table.format(),
+            [
+                "| Name  | Qty |     |",
+                "| :---- | --: | --- |",
+                "| apple |   3 |     |",
+                "| pear  |  12 | x   |",
+            ]
+        );
+
+        let table = Table::parse(&lines("  | a \\| b | c |\n  |:-:|---|")).unwrap();
+        assert_eq!(
+            table.format(),
+            ["  | a \\| b | c   |", "  | :----: | --- |"]
+        );
+
+        assert!(Table::parse(&lines("| a | b |\n| c | d |")).is_none());
+        assert!(Table::parse(&lines("| a | b |")).is_none());
+    }
+
+    #[test]
+    fn test_cells() {
+        let line = "| one | two |   |";
+        assert_eq!(cell_at(line, 0), 0);
+        assert_eq!(cell_at(line, 3), 0);
+        assert_eq!(cell_at(line, 7), 1);
+        assert_eq!(cell_range(line, 1), (8, 11));
+        assert_eq!(cell_range(line, 2), (14, 14));
+    }
+
+    #[test]
+    fn test_navigation_and_rows() {
+        let mut b = TextBuffer::from_str("| a | bb |\n|---|---|\n| c | d |");
+        b.set_selection(2, 2);
+        assert!(move_to_cell(&mut b, false));
+        assert_eq!(b.content(), "| a   | bb  |\n| --- | --- |\n| c   | d   |");
+        assert_eq!(b.selected_text(), "bb");
+
+        // Tab from the last cell adds a row; Enter on it again leaves the table
+        b.set_selection(b.len() - 2, b.len() - 2);
+        assert!(move_to_cell(&mut b, false));
+        assert!(b.content().ends_with("| d   |\n|     |     |"));
+        assert!(insert_newline(&mut b));
+        assert_eq!(b.content(), "| a   | bb  |\n| --- | --- |\n| c   | d   |\n");
+        assert_eq!(b.cursor(), b.len());
+
+        b.set_selection(2, 2);
+        assert!(move_to_cell(&mut b, true));
+        assert_eq!(b.selected_text(), "a");
+        assert!(!move_to_cell(&mut TextBuffer::from_str("plain"), false));
+    }
+
+    #[test]
+    fn test_edit_rows_and_columns() {
+        let mut b = TextBuffer::from_str("| a | b |\n|---|---|\n| c | d |");
+        b.set_selection(2, 2);
+        assert!(edit(&mut b, TableEdit::InsertColumn));
+        assert_eq!(
+            b.content(),
+            "| a   |     | b   |\n| --- | --- | --- |\n| c   |     | d   |"
+        );
+        assert!(edit(&mut b, TableEdit::DeleteColumn));
+        assert!(!edit(&mut b, TableEdit::DeleteRow));
+        assert!(edit(&mut b, TableEdit::InsertRow));
+        assert_eq!(
+            b.content(),
+            "| a   | b   |\n| --- | --- |\n|     |     |\n| c   | d   |"
+        );
+        assert!(edit(&mut b, TableEdit::DeleteRow));
+        assert_eq!(b.content(), "| a   | b   |\n| --- | --- |\n| c   | d   |");
+        b.undo();
+        assert_eq!(
+            b.content(),
+            "| a   | b   |\n| --- | --- |\n|     |     |\n| c   | d   |"
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Note tags: `#tag` in the body and `tags:` in the front matter
+//!
+//! Tags compare case-insensitively and are stored lowercased without the
+//! leading `#`. Code spans and fenced code blocks never contain tags.
+
+use crate::front_matter::{self, FrontMatter};
+
+fn is_tag_char(ch: char) -> bool {
+    ch.is_alphanumeric() || matches!(ch, '_' | '-' | '/')
+}
+
+/// Lowercase a tag and drop its leading `#`
+pub fn normalize(tag: &str) -> String {
+    tag.trim().trim_start_matches('#').to_lowercase()
+}
+
+/// Byte ranges of the tag names (without `#`) in a body
+fn tag_spans(body: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut in_fence = false;
+    let mut line_start = 0;
+    for line in body.split_inclusive('\n') {
+        let offset = line_start;
+        line_start += line.len();
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let mut in_code = false;
+        let mut prev: Option<char> = None;
+        let mut chars = line.char_indices().peekable();
+        while let Some((i, ch)) = chars.next() {
+            if ch == '`' {
+                in_code = !in_code;
+            } else if ch == '#' && !in_code && prev.is_none_or(|p| p.is_whitespace() || p == '(') {
+                let start = i + 1;
+                let mut end = start;
+                while let Some(&(j, next)) = chars.peek() {
+                    if !is_tag_char(next) {
+                        break;
+                    }
+                    end = j + next.len_utf8();
+                    chars.next();
+                }
+                let name = &line[start..end];
+                // `#` alone is a heading marker and `#12` an issue number
+                if name.chars().any(|c| !c.is_ascii_digit()) {
+                    spans.push((offset + start, offset + end));
+                }
+                prev = line[..end].chars().next_back();
+                continue;
+            }
+            prev = Some(ch);
+        }
+    }
+    spans
+}
+
+/// Inline tags in order of first appearance, without duplicates
+pub fn inline_tags(body: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for (start, end) in tag_spans(body) {
+        let tag = normalize(&body[start..end]);
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Front matter tags followed by inline tags, without duplicates
+pub fn note_tags(content: &str) -> Vec<String> {
+    let (front_matter, body) = front_matter::parse(content);
+    let mut tags: Vec<String> = Vec::new();
+    let front_matter_tags = front_matter
+        .map(|front_matter| front_matter.tags)
+        .unwrap_or_default();
+    for tag in front_matter_tags
+        .iter()
+        .map(|tag| normalize(tag))
+        .chain(inline_tags(body))
+    {
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Replace `#from` with `#to` in a body; None when the tag does not occur
+pub fn rename_inline_tag(body: &str, from: &str, to: &str) -> Option<String> {
+    let from = normalize(from);
+    let mut renamed = String::with_capacity(body.len());
+    let mut last = 0;
+    for (start, end) in tag_spans(body) {
+        if normalize(&body[start..end]) == from {
+            renamed.push_str(&body[last..start]);
+            renamed.push_str(to);
+            last = end;
+        }
+    }
+    if last == 0 {
+        return None;
+    }
+    renamed.push_str(&body[last..]);
+    Some(renamed)
+}
+
+/// Rename a tag in front matter; returns true when it was present
+pub fn rename_front_matter_tag(front_matter: &mut FrontMatter, from: &str, to: &str) -> bool {
+    let from = normalize(from);
+    if !front_matter.tags.iter().any(|tag| normalize(tag) == from) {
+        return false;
+    }
+    let mut tags: Vec<String> = Vec::new();
+    for tag in &front_matter.tags {
+        let tag = if normalize(tag) == from {
+            to.to_string()
+        } else {
+            tag.clone()
+        };
+        if !tags
+            .iter()
+            .any(|existing| normalize(existing) == normalize(&tag))
+        {
+            tags.push(tag);
+        }
+    }
+    front_matter.tags = tags;
+    true
+}
+
+/// Rename a tag in a whole note file; None when the note does not use it
+pub fn rename_in_note(content: &str, from: &str, to: &str) -> Option<String> {
+    let (front_matter, body) = front_matter::parse(content);
+    let mut front_matter = front_matter;
+    let in_front_matter = front_matter
+        .as_mut()
+        .is_some_and(|front_matter| rename_front_matter_tag(front_matter, from, to));
+    let renamed_body = rename_inline_tag(body, from, to);
+    if !in_front_matter && renamed_body.is_none() {
+        return None;
+    }
+    let body = renamed_body.as_deref().unwrap_or(body);
+    Some(front_matter::join(front_matter.as_ref(), body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inline_tags() {
+        let body = "# Heading\nMet with #Work and #work/projects (#idea).\nIssue #42, a#b, `#code`\n```\n#fenced\n```\n#idea again";
+        assert_eq!(inline_tags(body), vec!["work", "work/projects", "idea"]);
+    }
+
+    #[test]
+    fn test_note_tags_merge_front_matter() {
+        let content = "---\ntags: [Work, reading]\n---\nSee #work and #later\n";
+        assert_eq!(note_tags(content), vec!["work", "reading", "later"]);
+    }
+
+    #[test]
+    fn test_rename_tag_in_note() {
+        let content = "---\ntags: [work, home]\n---\n#Work items, #workshop stays, `#work` too\n";
+        assert_eq!(
+            rename_in_note(content, "#work", "job").as_deref(),
+            Some("---\ntags: [job, home]\n---\n#job items, #workshop stays, `#work` too\n")
+        );
+        assert_eq!(rename_in_note("no tags here", "work", "job"), None);
+        // Renaming onto an existing tag merges them
+        let mut front_matter = front_matter::parse("---\ntags: [a, b]\n---\n").0.unwrap();
+        assert!(rename_front_matter_tag(&mut front_matter, "a", "b"));
+        assert_eq!(front_matter.tags, vec!["b"]);
+    }
+}
@@ -0,0 +1,92 @@
+//! Note templates with `{{variable}}` expansion
+//!
+//! `{{date}}` and `{{time}}` become the local date (`YYYY-MM-DD`) and time
+//! (`HH:MM`), `{{title}}` the new note's title, and `{{cursor}}` marks where
+//! the cursor starts. Unknown variables are left as they are.
+
+/// Values substituted into a template
+pub struct Variables<'a> {
+    pub date: &'a str,
+    pub time: &'a str,
+    pub title: &'a str,
+}
+
+/// Template text after expansion
+#[derive(Debug, PartialEq)]
+pub struct Expanded {
+    pub text: String,
+    /// Line and char column of the first `{{cursor}}` marker
+    pub cursor: Option<(usize, usize)>,
+}
+
+pub fn expand(template: &str, variables: &Variables) -> Expanded {
+    let mut text = String::with_capacity(template.len());
+    let mut cursor = None;
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        text.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(len) = after.find("}}") else {
+            text.push_str("{{");
+            rest = after;
+            continue;
+        };
+        let value = match after[..len].trim() {
+            "date" => Some(variables.date),
+            "time" => Some(variables.time),
+            "title" => Some(variables.title),
+            "cursor" => {
+                if cursor.is_none() {
+                    cursor = Some(line_col(&text));
+                }
+                Some("")
+            }
+            _ => None,
+        };
+        match value {
+            Some(value) => text.push_str(value),
+            None => text.push_str(&rest[start..start + len + 4]),
+        }
+        rest = &after[len + 2..];
+    }
+    text.push_str(rest);
+    Expanded { text, cursor }
+}
+
+/// Position just after the end of `text`
+fn line_col(text: &str) -> (usize, usize) {
+    let line = text.matches('\n').count();
+    let col = text
+        .rsplit('\n')
+        .next()
+        .map_or(0, |last| last.chars().count());
+    (line, col)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VARIABLES: Variables = Variables {
+        date: "2024-03-09",
+        time: "14:05",
+        title: "Standup",
+    };
+
+    #[test]
+    fn test_expand_variables() {
+        let expanded = expand(
+            "# {{title}}\n{{ date }} {{time}} {{unknown}} {{",
+            &VARIABLES,
+        );
+        assert_eq!(expanded.text, "# Standup\n2024-03-09 14:05 {{unknown}} {{");
+        assert_eq!(expanded.cursor, None);
+    }
+
+    #[test]
+    fn test_cursor_marker() {
+        let expanded = expand("# {{title}}\n\n- {{cursor}}\n- {{cursor}}", &VARIABLES);
+        assert_eq!(expanded.text, "# Standup\n\n- \n- ");
+        assert_eq!(expanded.cursor, Some((2, 2)));
+    }
+}
@@ -1,6 +1,9 @@
 //! Efficient text buffer using ropey (rope data structure)
 //! O(log n) insertions and deletions
 
+use crate::grapheme;
+use crate::stats::{LineStats, Stats};
+use crate::word::{self, WordMode};
 use ropey::Rope;
 
 #[derive(Clone, Debug)]
@@ -13,7 +16,6 @@ enum Action {
         start: usize,
         text: String,
     },
-    #[allow(dead_code)]
     Replace {
         start: usize,
         old_text: String,
@@ -27,27 +29,40 @@ pub struct TextBuffer {
     selection_anchor: Option<usize>, // Start of selection (None = no selection)
     undo_stack: Vec<Action>,
     redo_stack: Vec<Action>,
+    /// Char ranges that follow edits (snippet tab stops), in visiting order
+    regions: Vec<(usize, usize)>,
+    /// Region being edited; text typed at its edges extends it
+    focused_region: Option<usize>,
+    /// Folded char ranges, from the line break ending a fold's header line
+    /// to the end of its last line; the lines in between are hidden
+    folds: Vec<(usize, usize)>,
+    /// Per-line counts behind the document statistics
+    stats: LineStats,
+    /// Bumped on every change to the text or the folds, so views derived
+    /// from them know when to rebuild
+    revision: u64,
 }
 
 impl TextBuffer {
     pub fn new() -> Self {
-        Self {
-            rope: Rope::new(),
-            cursor: 0,
-            selection_anchor: None,
-            undo_stack: Vec::new(),
-            redo_stack: Vec::new(),
-        }
+        Self::from_str("")
     }
 
     pub fn from_str(text: &str) -> Self {
-        Self {
+        let mut buffer = Self {
             rope: Rope::from_str(text),
             cursor: 0,
             selection_anchor: None,
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
-        }
+            regions: Vec::new(),
+            focused_region: None,
+            folds: Vec::new(),
+            stats: LineStats::default(),
+            revision: 0,
+        };
+        buffer.recount_stats();
+        buffer
     }
 
     pub fn content(&self) -> &str {
@@ -84,6 +99,7 @@ impl TextBuffer {
             text: ch.to_string(),
         });
         self.rope.insert_char(self.cursor, ch);
+        self.regions_inserted(self.cursor, 1);
         self.cursor += 1;
     }
 
@@ -96,11 +112,54 @@ impl TextBuffer {
             text: text.to_string(),
         });
         self.rope.insert(self.cursor, text);
+        self.regions_inserted(self.cursor, text.chars().count());
         self.cursor += text.chars().count();
     }
 
-    pub fn undo(&mut self) {
+    /// Replace `start..end` with `text` as a single undoable edit and put
+    /// the cursor after it
+    pub fn replace_range(&mut self, start: usize, end: usize, text: &str) {
+        self.record_action(Action::Replace {
+            start,
+            old_text: self.rope.slice(start..end).to_string(),
+            new_text: text.to_string(),
+        });
+        self.rope.remove(start..end);
+        self.regions_removed(start, end);
+        self.rope.insert(start, text);
+        self.regions_inserted(start, text.chars().count());
+        self.cursor = start + text.chars().count();
+        self.selection_anchor = None;
+    }
+
+    /// Replace the whole text as a single undoable edit, keeping the cursor on
+    /// the same line and column where possible
+    pub fn replace_all(&mut self, text: &str) {
+        let (line, col) = self.char_to_line_col(self.cursor);
+        self.record_action(Action::Replace {
+            start: 0,
+            old_text: self.rope.to_string(),
+            new_text: text.to_string(),
+        });
+        self.rope = Rope::from_str(text);
+        self.clear_regions();
+        self.folds.clear();
+        self.recount_stats();
+        self.revision += 1;
+        self.set_cursor_by_line_col(line, col, false);
+    }
+
+    /// Undo the last edit; returns false if there was nothing to undo
+    pub fn undo(&mut self) -> bool {
         if let Some(action) = self.undo_stack.pop() {
+            let (start, removed_len) = match &action {
+                Action::Insert { start, text } => (*start, text.chars().count()),
+                Action::Delete { start, .. } => (*start, 0),
+                Action::Replace {
+                    start, new_text, ..
+                } => (*start, new_text.chars().count()),
+            };
+            let (first, removed) = self.lines_spanned(start, removed_len);
             match action.clone() {
                 Action::Insert { start, text } => {
                     // Undo insert = delete
@@ -127,11 +186,26 @@ impl TextBuffer {
             }
             self.redo_stack.push(action);
             self.selection_anchor = None;
+            self.clear_regions();
+            self.folds.clear();
+            self.recount_lines(first, removed);
+            self.revision += 1;
+            return true;
         }
+        false
     }
 
-    pub fn redo(&mut self) {
+    /// Redo the last undone edit; returns false if there was nothing to redo
+    pub fn redo(&mut self) -> bool {
         if let Some(action) = self.redo_stack.pop() {
+            let (start, removed_len) = match &action {
+                Action::Insert { start, .. } => (*start, 0),
+                Action::Delete { start, text } => (*start, text.chars().count()),
+                Action::Replace {
+                    start, old_text, ..
+                } => (*start, old_text.chars().count()),
+            };
+            let (first, removed) = self.lines_spanned(start, removed_len);
             match action.clone() {
                 Action::Insert { start, text } => {
                     // Redo insert = insert
@@ -158,7 +232,13 @@ impl TextBuffer {
             }
             self.undo_stack.push(action);
             self.selection_anchor = None;
+            self.clear_regions();
+            self.folds.clear();
+            self.recount_lines(first, removed);
+            self.revision += 1;
+            return true;
         }
+        false
     }
 
     fn record_action(&mut self, action: Action) {
@@ -170,13 +250,15 @@ impl TextBuffer {
         if self.has_selection() {
             self.delete_selection();
         } else if self.cursor > 0 {
-            let char_to_delete = self.rope.slice(self.cursor - 1..self.cursor).to_string();
+            let start = self.prev_grapheme_boundary(self.cursor);
+            let cluster = self.rope.slice(start..self.cursor).to_string();
             self.record_action(Action::Delete {
-                start: self.cursor - 1,
-                text: char_to_delete,
+                start,
+                text: cluster,
             });
-            self.cursor -= 1;
-            self.rope.remove(self.cursor..self.cursor + 1);
+            self.rope.remove(start..self.cursor);
+            self.regions_removed(start, self.cursor);
+            self.cursor = start;
         }
     }
 
@@ -190,48 +272,7 @@ impl TextBuffer {
             return;
         }
 
-        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
-        let is_whitespace = |c: char| c.is_whitespace();
-        let category_check = |c: char| -> u8 {
-            if is_word_char(c) {
-                1
-            } else if is_whitespace(c) {
-                2
-            } else {
-                3
-            }
-        };
-
-        let mut start = self.cursor;
-
-        // Count consecutive spaces immediately before cursor
-        let mut space_count = 0;
-        while start > 0 && category_check(self.rope.char(start - 1)) == 2 {
-            start -= 1;
-            space_count += 1;
-        }
-
-        if space_count > 1 {
-            // More than one space: treat spaces as a word, already deleted above
-        } else {
-            // Single space or not space: normal word deletion
-            // If we deleted a single space, restore it and do normal word deletion
-            if space_count == 1 {
-                start = self.cursor;
-            }
-            // Normal word deletion: skip trailing whitespace first
-            while start > 0 && category_check(self.rope.char(start - 1)) == 2 {
-                start -= 1;
-            }
-
-            if start > 0 {
-                let category = category_check(self.rope.char(start - 1));
-                while start > 0 && category_check(self.rope.char(start - 1)) == category {
-                    start -= 1;
-                }
-            }
-        }
-
+        let start = self.word_boundary(self.cursor, word::delete_word_left_start);
         if start < self.cursor {
             let removed_text = self.rope.slice(start..self.cursor).to_string();
             self.record_action(Action::Delete {
@@ -239,6 +280,7 @@ impl TextBuffer {
                 text: removed_text,
             });
             self.rope.remove(start..self.cursor);
+            self.regions_removed(start, self.cursor);
             self.cursor = start;
         }
     }
@@ -247,12 +289,14 @@ impl TextBuffer {
         if self.has_selection() {
             self.delete_selection();
         } else if self.cursor < self.rope.len_chars() {
-            let char_to_delete = self.rope.slice(self.cursor..self.cursor + 1).to_string();
+            let end = self.next_grapheme_boundary(self.cursor);
+            let cluster = self.rope.slice(self.cursor..end).to_string();
             self.record_action(Action::Delete {
                 start: self.cursor,
-                text: char_to_delete,
+                text: cluster,
             });
-            self.rope.remove(self.cursor..self.cursor + 1);
+            self.rope.remove(self.cursor..end);
+            self.regions_removed(self.cursor, end);
         }
     }
 
@@ -262,33 +306,11 @@ impl TextBuffer {
             return;
         }
 
-        let len = self.rope.len_chars();
-        if self.cursor >= len {
+        if self.cursor >= self.rope.len_chars() {
             return;
         }
 
-        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
-        let is_whitespace = |c: char| c.is_whitespace();
-        let category_check = |c: char| -> u8 {
-            if is_word_char(c) {
-                1
-            } else if is_whitespace(c) {
-                2
-            } else {
-                3
-            }
-        };
-
-        let mut end = self.cursor;
-
-        // Skip current word/category (don't include trailing whitespace)
-        if end < len {
-            let category = category_check(self.rope.char(end));
-            while end < len && category_check(self.rope.char(end)) == category {
-                end += 1;
-            }
-        }
-
+        let end = self.word_boundary(self.cursor, word::delete_word_right_end);
         if end > self.cursor {
             let removed_text = self.rope.slice(self.cursor..end).to_string();
             self.record_action(Action::Delete {
@@ -296,6 +318,7 @@ impl TextBuffer {
                 text: removed_text,
             });
             self.rope.remove(self.cursor..end);
+            self.regions_removed(self.cursor, end);
         }
     }
 
@@ -306,9 +329,7 @@ impl TextBuffer {
             self.clear_selection();
         }
 
-        if self.cursor > 0 {
-            self.cursor -= 1;
-        }
+        self.cursor = self.skip_fold(self.prev_grapheme_boundary(self.cursor), false);
     }
 
     pub fn move_right(&mut self, selecting: bool) {
@@ -318,12 +339,19 @@ impl TextBuffer {
             self.clear_selection();
         }
 
-        if self.cursor < self.rope.len_chars() {
-            self.cursor += 1;
-        }
+        self.cursor = self.skip_fold(self.next_grapheme_boundary(self.cursor), true);
     }
 
     pub fn move_word_left(&mut self, selecting: bool) {
+        self.move_word_left_by(selecting, WordMode::Word);
+    }
+
+    /// Move to the previous camelCase / snake_case part
+    pub fn move_subword_left(&mut self, selecting: bool) {
+        self.move_word_left_by(selecting, WordMode::SubWord);
+    }
+
+    fn move_word_left_by(&mut self, selecting: bool, mode: WordMode) {
         if selecting {
             self.start_selection();
         } else {
@@ -334,78 +362,36 @@ impl TextBuffer {
             return;
         }
 
-        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
-        let is_whitespace = |c: char| c.is_whitespace();
-        let category_check = |c: char| -> u8 {
-            if is_word_char(c) {
-                1
-            } else if is_whitespace(c) {
-                2
-            } else {
-                3
-            }
-        };
-
-        let mut pos = self.cursor;
-
-        // 1. Skip whitespace backwards
-        while pos > 0 && category_check(self.rope.char(pos - 1)) == 2 {
-            pos -= 1;
-        }
+        let target = self.word_boundary(self.cursor, |text, offset| {
+            word::prev_word_start(text, offset, mode)
+        });
+        self.cursor = self.skip_fold(target, false);
+    }
 
-        if pos > 0 {
-            // 2. Determine category of what's left
-            let cat = category_check(self.rope.char(pos - 1));
-            // 3. Skip same category
-            while pos > 0 && category_check(self.rope.char(pos - 1)) == cat {
-                pos -= 1;
-            }
-        }
+    pub fn move_word_right(&mut self, selecting: bool) {
+        self.move_word_right_by(selecting, WordMode::Word);
+    }
 
-        self.cursor = pos;
+    /// Move to the end of the next camelCase / snake_case part
+    pub fn move_subword_right(&mut self, selecting: bool) {
+        self.move_word_right_by(selecting, WordMode::SubWord);
     }
 
-    pub fn move_word_right(&mut self, selecting: bool) {
+    fn move_word_right_by(&mut self, selecting: bool, mode: WordMode) {
         if selecting {
             self.start_selection();
         } else {
             self.clear_selection();
         }
 
-        let len = self.rope.len_chars();
-        if self.cursor >= len {
+        if self.cursor >= self.rope.len_chars() {
             return;
         }
 
-        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
-        let is_whitespace = |c: char| c.is_whitespace();
-        let category_check = |c: char| -> u8 {
-            if is_word_char(c) {
-                1
-            } else if is_whitespace(c) {
-                2
-            } else {
-                3
-            }
-        };
-
-        let mut pos = self.cursor;
-
-        // 1. Skip whitespace forwards
-        while pos < len && category_check(self.rope.char(pos)) == 2 {
-            pos += 1;
-        }
-
-        if pos < len {
-            // 2. Determine category of token
-            let cat = category_check(self.rope.char(pos));
-            // 3. Skip same category
-            while pos < len && category_check(self.rope.char(pos)) == cat {
-                pos += 1;
-            }
-        }
-
-        self.cursor = pos;
+        let target = self.word_boundary(self.cursor, |text, offset| {
+            word::next_word_end(text, offset, mode)
+        });
+        self.cursor = self.skip_fold(target, true);
     }
 
     pub fn move_up(&mut self, selecting: bool) {
@@ -425,10 +411,14 @@ impl TextBuffer {
         let line_start = self.rope.line_to_char(line);
         let col = self.cursor - line_start;
 
-        // Move to previous line, same column if possible
-        let prev_line_start = self.rope.line_to_char(line - 1);
-        let prev_line_len = self.rope.line(line - 1).len_chars().saturating_sub(1); // Exclude newline
-        self.cursor = prev_line_start + col.min(prev_line_len);
+        // Move to previous visible line, same column if possible
+        let mut prev_line = line - 1;
+        while prev_line > 0 && self.is_line_hidden(prev_line) {
+            prev_line -= 1;
+        }
+        let prev_line_start = self.rope.line_to_char(prev_line);
+        let prev_line_len = self.rope.line(prev_line).len_chars().saturating_sub(1); // Exclude newline
+        self.cursor = self.grapheme_floor(prev_line_start + col.min(prev_line_len));
     }
 
     pub fn move_down(&mut self, selecting: bool) {
@@ -449,14 +439,23 @@ impl TextBuffer {
         let line_start = self.rope.line_to_char(line);
         let col = self.cursor - line_start;
 
-        // Move to next line, same column if possible
-        let next_line_start = self.rope.line_to_char(line + 1);
-        let next_line_len = if line + 1 < total_lines - 1 {
-            self.rope.line(line + 1).len_chars().saturating_sub(1)
+        // Move to next visible line, same column if possible; a fold that
+        // runs to the end of the text leaves the cursor on its header
+        let mut next_line = line + 1;
+        while next_line < total_lines && self.is_line_hidden(next_line) {
+            next_line += 1;
+        }
+        if next_line >= total_lines {
+            self.cursor = line_start + self.line_text(line).chars().count();
+            return;
+        }
+        let next_line_start = self.rope.line_to_char(next_line);
+        let next_line_len = if next_line < total_lines - 1 {
+            self.rope.line(next_line).len_chars().saturating_sub(1)
         } else {
-            self.rope.line(line + 1).len_chars()
+            self.rope.line(next_line).len_chars()
         };
-        self.cursor = next_line_start + col.min(next_line_len);
+        self.cursor = self.grapheme_floor(next_line_start + col.min(next_line_len));
     }
 
     pub fn move_to_line_start(&mut self, selecting: bool) {
@@ -542,7 +541,74 @@ impl TextBuffer {
         // Clamp column to line length
         let target_col = col.min(effective_line_len);
 
-        self.cursor = line_start + target_col;
+        self.cursor = self.grapheme_floor(line_start + target_col);
+        self.reveal(self.cursor);
+    }
+
+    /// Char index of the grapheme cluster boundary before `pos`
+    fn prev_grapheme_boundary(&self, pos: usize) -> usize {
+        if pos == 0 {
+            return 0;
+        }
+        // Clusters never span lines (CRLF stays inside its line), so segment
+        // only the line holding the preceding character
+        let line = self.rope.char_to_line(pos - 1);
+        let line_start = self.rope.line_to_char(line);
+        let text = self.rope.line(line).to_string();
+        let byte = grapheme::char_to_byte(&text, pos - line_start);
+        line_start + grapheme::byte_to_char(&text, grapheme::prev_boundary(&text, byte))
+    }
+
+    /// Char index of the grapheme cluster boundary after `pos`
+    fn next_grapheme_boundary(&self, pos: usize) -> usize {
+        if pos >= self.rope.len_chars() {
+            return self.rope.len_chars();
+        }
+        let line = self.rope.char_to_line(pos);
+        let line_start = self.rope.line_to_char(line);
+        let text = self.rope.line(line).to_string();
+        let byte = grapheme::char_to_byte(&text, pos - line_start);
+        line_start + grapheme::byte_to_char(&text, grapheme::next_boundary(&text, byte))
+    }
+
+    /// Snap `pos` back to the start of the grapheme cluster containing it
+    fn grapheme_floor(&self, pos: usize) -> usize {
+        let pos = pos.min(self.rope.len_chars());
+        let line = self.rope.char_to_line(pos);
+        let line_start = self.rope.line_to_char(line);
+        let text = self.rope.line(line).to_string();
+        let byte = grapheme::char_to_byte(&text, pos - line_start);
+        line_start + grapheme::byte_to_char(&text, grapheme::floor_boundary(&text, byte))
+    }
+
+    /// Text around `pos` for word segmentation, with the char index it starts at
+    ///
+    /// Covers the neighbouring lines, extended past blank lines, so whitespace
+    /// skipping can cross line breaks without copying the whole rope.
+    fn word_context(&self, pos: usize) -> (usize, String) {
+        let last_line = self.rope.len_lines().saturating_sub(1);
+        let line = self.rope.char_to_line(pos);
+        let is_blank = |l: usize| self.rope.line(l).chars().all(char::is_whitespace);
+
+        let mut first = line.saturating_sub(1);
+        while first > 0 && is_blank(first) {
+            first -= 1;
+        }
+        let mut last = (line + 1).min(last_line);
+        while last < last_line && is_blank(last) {
+            last += 1;
+        }
+
+        let start = self.rope.line_to_char(first);
+        let end = self.rope.line_to_char(last) + self.rope.line(last).len_chars();
+        (start, self.rope.slice(start..end).to_string())
+    }
+
+    /// Apply a byte-offset word function from `word` to a char position
+    fn word_boundary(&self, pos: usize, find: impl Fn(&str, usize) -> usize) -> usize {
+        let (context_start, text) = self.word_context(pos);
+        let offset = grapheme::char_to_byte(&text, pos - context_start);
+        context_start + grapheme::byte_to_char(&text, find(&text, offset))
     }
 
     pub fn start_selection(&mut self) {
@@ -591,65 +657,23 @@ impl TextBuffer {
             let text = self.rope.slice(start..end).to_string();
             self.record_action(Action::Delete { start, text });
             self.rope.remove(start..end);
+            self.regions_removed(start, end);
             self.cursor = start;
             self.selection_anchor = None;
         }
     }
 
     pub fn select_word_at_cursor(&mut self) {
-        let len = self.rope.len_chars();
-        if len == 0 {
+        if self.rope.len_chars() == 0 {
             return;
         }
 
-        // If cursor is at end, select backward from last char
-        // Otherwise select based on char after cursor (which acts as "under" cursor)
-        let check_idx = if self.cursor == len {
-            len - 1
-        } else {
-            self.cursor
-        };
-        let char_at_cursor = self.rope.char(check_idx);
-
-        // Define word character categories
-        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
-        let is_whitespace = |c: char| c.is_whitespace();
-
-        // Determine category of clicked character
-        let category_check = |c: char| -> u8 {
-            if is_word_char(c) {
-                1
-            } else if is_whitespace(c) {
-                2
-            } else {
-                3
-            }
-        };
-
-        let target_category = category_check(char_at_cursor);
-
-        // Scan backwards
-        let mut start = check_idx;
-        while start > 0 {
-            let prev_char = self.rope.char(start - 1);
-            if category_check(prev_char) != target_category {
-                break;
-            }
-            start -= 1;
-        }
+        let (context_start, text) = self.word_context(self.cursor);
+        let offset = grapheme::char_to_byte(&text, self.cursor - context_start);
+        let (start, end) = word::word_range_at(&text, offset);
 
-        // Scan forwards
-        let mut end = check_idx + 1;
-        while end < len {
-            let next_char = self.rope.char(end);
-            if category_check(next_char) != target_category {
-                break;
-            }
-            end += 1;
-        }
-
-        self.selection_anchor = Some(start);
-        self.cursor = end;
+        self.selection_anchor = Some(context_start + grapheme::byte_to_char(&text, start));
+        self.cursor = context_start + grapheme::byte_to_char(&text, end);
     }
 
     pub fn select_line_at_cursor(&mut self) {
@@ -670,6 +694,32 @@ impl TextBuffer {
         self.cursor = end;
     }
 
+    /// Text of a line without its line break
+    pub fn line_text(&self, line: usize) -> String {
+        if line >= self.rope.len_lines() {
+            return String::new();
+        }
+        let text = self.rope.line(line).to_string();
+        text.trim_end_matches(['\n', '\r']).to_string()
+    }
+
+    /// Char index where a line starts
+    pub fn line_to_char(&self, line: usize) -> usize {
+        self.rope.line_to_char(line.min(self.rope.len_lines()))
+    }
+
+    pub fn char_at(&self, char_idx: usize) -> Option<char> {
+        (char_idx < self.rope.len_chars()).then(|| self.rope.char(char_idx))
+    }
+
+    /// Select `anchor..cursor` (or just place the cursor when they are equal)
+    pub fn set_selection(&mut self, anchor: usize, cursor: usize) {
+        let len = self.rope.len_chars();
+        self.cursor = cursor.min(len);
+        self.selection_anchor = (anchor != cursor).then_some(anchor.min(len));
+        self.reveal(self.cursor);
+    }
+
     pub fn char_to_line_col(&self, char_idx: usize) -> (usize, usize) {
         let line = self.rope.char_to_line(char_idx);
         let line_start = self.rope.line_to_char(line);
@@ -677,8 +727,216 @@ impl TextBuffer {
         (line, col)
     }
 
+    /// Start tracking `regions` (char ranges) through later edits
+    pub fn set_regions(&mut self, regions: Vec<(usize, usize)>) {
+        self.regions = regions;
+        self.focused_region = None;
+    }
+
+    pub fn regions(&self) -> &[(usize, usize)] {
+        &self.regions
+    }
+
+    pub fn focused_region(&self) -> Option<usize> {
+        self.focused_region
+    }
+
+    /// Select region `index` so typing replaces it
+    pub fn focus_region(&mut self, index: usize) {
+        if let Some(&(start, end)) = self.regions.get(index) {
+            self.focused_region = Some(index);
+            self.selection_anchor = Some(start);
+            self.cursor = end;
+        }
+    }
+
+    pub fn clear_regions(&mut self) {
+        self.regions.clear();
+        self.focused_region = None;
+    }
+
+    /// Keep regions on their text after inserting `len` chars at `at`.
+    /// Inside a region the text joins it; at an edge it only joins the
+    /// focused region, so neighbouring empty stops stay apart.
+    fn regions_inserted(&mut self, at: usize, len: usize) {
+        for (index, (start, end)) in self.regions.iter_mut().enumerate() {
+            let focused = self.focused_region == Some(index);
+            if at < *start || (at == *start && !focused) {
+                *start += len;
+                *end += len;
+            } else if at < *end || (at == *end && focused) {
+                *end += len;
+            }
+        }
+        // A fold opens when its hidden lines change or a line break is
+        // typed at the end of its header
+        let breaks_header = self.rope.slice(at..at + len).chars().any(|ch| ch == '\n');
+        self.folds.retain(|&(start, end)| {
+            let hidden_edited = start < at && at <= end;
+            !(hidden_edited || (at == start && breaks_header))
+        });
+        for (start, end) in &mut self.folds {
+            if at <= *start {
+                *start += len;
+                *end += len;
+            }
+        }
+        // Recount the line the text went into and any lines it added
+        self.recount_lines(self.rope.char_to_line(at), 1);
+        self.revision += 1;
+    }
+
+    /// Keep regions on their text after removing `from..to`
+    fn regions_removed(&mut self, from: usize, to: usize) {
+        let shift = |pos: usize| {
+            if pos <= from {
+                pos
+            } else if pos >= to {
+                pos - (to - from)
+            } else {
+                from
+            }
+        };
+        for (start, end) in &mut self.regions {
+            *start = shift(*start);
+            *end = shift(*end);
+        }
+        self.folds.retain(|&(start, end)| to <= start || from > end);
+        for (start, end) in &mut self.folds {
+            if to <= *start {
+                *start -= to - from;
+                *end -= to - from;
+            }
+        }
+        // The lines the removal joined collapse into the one at `from`
+        let removed = self.stats.len().saturating_sub(self.rope.len_lines());
+        self.recount_lines(self.rope.char_to_line(from), removed + 1);
+        self.revision += 1;
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Statistics for the whole text, from the per-line counts
+    pub fn stats(&self) -> Stats {
+        self.stats.total()
+    }
+
+    /// Count every line again, after the whole text was replaced
+    fn recount_stats(&mut self) {
+        self.stats = LineStats::default();
+        self.stats
+            .splice(0, 0, self.rope.lines().map(|line| line.to_string()));
+    }
+
+    /// Recount after an edit that rewrote `removed` lines from `first`; the
+    /// change in line count tells how many lines took their place
+    fn recount_lines(&mut self, first: usize, removed: usize) {
+        let added = (removed + self.rope.len_lines()).saturating_sub(self.stats.len());
+        let rope = &self.rope;
+        self.stats.splice(
+            first,
+            removed,
+            (first..first + added).map(|line| rope.line(line).to_string()),
+        );
+    }
+
+    /// First line and number of lines touched by `len` chars from `start`
+    fn lines_spanned(&self, start: usize, len: usize) -> (usize, usize) {
+        let first = self.rope.char_to_line(start);
+        (first, self.rope.char_to_line(start + len) - first + 1)
+    }
+
+    /// Whether lines end in CRLF, judging by the first line break
+    pub fn uses_crlf(&self) -> bool {
+        let line = self.rope.line(0);
+        let len = line.len_chars();
+        len >= 2 && line.char(len - 2) == '\r' && line.char(len - 1) == '\n'
+    }
+
+    /// Hide lines `first + 1..=last`, keeping `first` visible as the header
+    pub fn fold_lines(&mut self, first: usize, last: usize) {
+        if last <= first || last >= self.rope.len_lines() {
+            return;
+        }
+        let start = self.rope.line_to_char(first) + self.line_text(first).chars().count();
+        let end = self.rope.line_to_char(last) + self.line_text(last).chars().count();
+        self.folds.retain(|&(s, e)| !(start <= s && e <= end));
+        self.folds.push((start, end));
+        self.folds.sort_unstable();
+        self.revision += 1;
+        // Keep the cursor out of the hidden lines
+        if start < self.cursor && self.cursor <= end {
+            self.cursor = start;
+        }
+        self.selection_anchor = None;
+    }
+
+    /// Open the fold whose header is `line`; returns false if there is none
+    pub fn unfold_line(&mut self, line: usize) -> bool {
+        let rope = &self.rope;
+        let before = self.folds.len();
+        self.folds
+            .retain(|&(start, _)| rope.char_to_line(start) != line);
+        let unfolded = self.folds.len() != before;
+        if unfolded {
+            self.revision += 1;
+        }
+        unfolded
+    }
+
+    /// Open every fold; returns false if there were none
+    pub fn unfold_all(&mut self) -> bool {
+        let had_folds = !self.folds.is_empty();
+        self.folds.clear();
+        self.revision += 1;
+        had_folds
+    }
+
+    /// (header line, last hidden line) of each fold
+    pub fn folded_lines(&self) -> Vec<(usize, usize)> {
+        self.folds
+            .iter()
+            .map(|&(start, end)| (self.rope.char_to_line(start), self.rope.char_to_line(end)))
+            .collect()
+    }
+
+    fn is_line_hidden(&self, line: usize) -> bool {
+        let pos = self.rope.line_to_char(line);
+        self.folds
+            .iter()
+            .any(|&(start, end)| start < pos && pos <= end)
+    }
+
+    /// Step over a fold that `pos` landed in, moving past it or back to its header
+    fn skip_fold(&self, pos: usize, forward: bool) -> usize {
+        match self
+            .folds
+            .iter()
+            .find(|&&(start, end)| start < pos && pos <= end)
+        {
+            Some(&(_, end)) if forward && end < self.rope.len_chars() => end + 1,
+            Some(&(start, _)) => start,
+            None => pos,
+        }
+    }
+
+    /// Open any fold hiding `pos`
+    fn reveal(&mut self, pos: usize) {
+        let before = self.folds.len();
+        self.folds
+            .retain(|&(start, end)| !(start < pos && pos <= end));
+        if self.folds.len() != before {
+            self.revision += 1;
+        }
+    }
+
     /// Move current line or selected lines up one line
     pub fn move_lines_up(&mut self) {
+        self.clear_regions();
+        self.folds.clear();
+        self.revision += 1;
         let (start_line, _end_line) = self.get_line_range_to_move();
 
         if start_line == 0 {
@@ -689,10 +947,12 @@ impl TextBuffer {
 
         // Ensure we have clean newline boundaries
         // 1. Check if the file ends with newline. If not, and we are touching the last line, append one.
+        let mut appended_newline = false;
         if self.rope.len_chars() > 0 {
             let last_char_idx = self.rope.len_chars() - 1;
             if self.rope.char(last_char_idx) != '\n' {
                 self.rope.insert_char(self.rope.len_chars(), '\n');
+                appended_newline = true;
                 // If selection encompasses end, adjust it
                 if let Some(anchor) = self.selection_anchor {
                     if anchor > self.cursor {
@@ -728,10 +988,22 @@ impl TextBuffer {
         if let Some(anchor) = self.selection_anchor {
             self.selection_anchor = Some(anchor - move_amount);
         }
+
+        // The moved block and the line it swapped with, and the last line
+        // if it gained a line break
+        let last = if appended_newline {
+            self.stats.len()
+        } else {
+            end_line + 1
+        };
+        self.recount_lines(swap_target_line, last - swap_target_line);
     }
 
     /// Move current line or selected lines down one line
     pub fn move_lines_down(&mut self) {
+        self.clear_regions();
+        self.folds.clear();
+        self.revision += 1;
         let (_start_line, end_line) = self.get_line_range_to_move();
         let total_lines = self.rope.len_lines();
 
@@ -740,10 +1012,12 @@ impl TextBuffer {
         }
 
         // Ensure newline at EOF if needed to simplify logic
+        let mut appended_newline = false;
         if self.rope.len_chars() > 0 {
             let last_char_idx = self.rope.len_chars() - 1;
             if self.rope.char(last_char_idx) != '\n' {
                 self.rope.insert_char(self.rope.len_chars(), '\n');
+                appended_newline = true;
             }
         }
 
@@ -752,6 +1026,10 @@ impl TextBuffer {
         let total_lines = self.rope.len_lines();
 
         if end_line + 1 >= total_lines {
+            // Only the line break added above changed
+            if appended_newline {
+                self.recount_lines(self.stats.len() - 1, 1);
+            }
             return;
         }
 
@@ -782,6 +1060,15 @@ impl TextBuffer {
         if let Some(anchor) = self.selection_anchor {
             self.selection_anchor = Some(anchor + move_up_len);
         }
+
+        // The moved block and the line it swapped with, and the last line
+        // if it gained a line break
+        let last = if appended_newline {
+            self.stats.len()
+        } else {
+            target_line_below + 1
+        };
+        self.recount_lines(start_line, last - start_line);
     }
 
     /// Helper to get the line range involved in operation
@@ -1049,6 +1336,20 @@ mod tests {
         assert_eq!(buf.content(), "ab");
     }
 
+    #[test]
+    fn test_replace_all_is_undoable() {
+        let mut buf = TextBuffer::from_str("one\ntwo edited\n");
+        buf.set_cursor_by_line_col(1, 6, false);
+        buf.replace_all("one\ntwo\n");
+        assert_eq!(buf.content(), "one\ntwo\n");
+        assert_eq!(buf.char_to_line_col(buf.cursor()), (1, 3));
+
+        assert!(buf.undo());
+        assert_eq!(buf.content(), "one\ntwo edited\n");
+        assert!(buf.redo());
+        assert_eq!(buf.content(), "one\ntwo\n");
+    }
+
     #[test]
     fn test_undo_selection_delete() {
         let mut buf = TextBuffer::from_str("hello world");
@@ -1058,6 +1359,7 @@ mod tests {
         buf.move_right(true); // 'l'
         buf.move_right(true); // 'l'
         buf.move_right(true); // 'o'
+
         // Selection is "hello"
         buf.delete_selection();
         assert_eq!(buf.content(), " world");
@@ -1068,4 +1370,170 @@ mod tests {
         buf.redo();
         assert_eq!(buf.content(), " world");
     }
+
+    #[test]
+    fn test_insert_str_is_single_undo_step() {
+        // IME commits arrive as whole strings and must undo together
+        let mut buf = TextBuffer::from_str("a");
+        buf.move_to_end(false);
+        buf.insert_str("日本語");
+        assert_eq!(buf.cursor(), 4);
+        buf.undo();
+        assert_eq!(buf.content(), "a");
+    }
+
+    #[test]
+    fn test_regions_follow_edits() {
+        let mut buffer = TextBuffer::from_str("a  b");
+        buffer.set_regions(vec![(1, 1), (2, 2), (4, 4)]);
+        buffer.focus_region(0);
+        buffer.insert_str("xy");
+        // Only the focused empty region takes the typed text
+        assert_eq!(buffer.regions(), &[(1, 3), (4, 4), (6, 6)]);
+        buffer.focus_region(1);
+        buffer.insert('z');
+        assert_eq!(buffer.regions(), &[(1, 3), (4, 5), (7, 7)]);
+        buffer.set_cursor_by_line_col(0, 2, false);
+        buffer.backspace();
+        assert_eq!(buffer.content(), "ay z b");
+        assert_eq!(buffer.regions(), &[(1, 2), (3, 4), (6, 6)]);
+        buffer.undo();
+        assert!(buffer.regions().is_empty());
+    }
+
+    #[test]
+    fn test_folds_follow_edits() {
+        let mut buffer = TextBuffer::from_str("# A\none\ntwo\n# B\nthree");
+        buffer.fold_lines(0, 2);
+        assert_eq!(buffer.folded_lines(), [(0, 2)]);
+
+        // Vertical moves skip the hidden lines, horizontal ones step over them
+        buffer.move_down(false);
+        assert_eq!(buffer.char_to_line_col(buffer.cursor()), (3, 0));
+        buffer.move_left(false);
+        assert_eq!(buffer.char_to_line_col(buffer.cursor()), (0, 3));
+
+        // Typing on the header keeps the fold; Enter at its end opens it
+        buffer.set_cursor_by_line_col(0, 1, false);
+        buffer.insert('!');
+        assert_eq!(buffer.folded_lines(), [(0, 2)]);
+        buffer.set_cursor_by_line_col(0, 4, false);
+        buffer.insert('\n');
+        assert!(buffer.folded_lines().is_empty());
+
+        // Placing the cursor inside a fold reveals it
+        buffer.fold_lines(4, 5);
+        buffer.set_cursor_by_line_col(5, 0, false);
+        assert!(buffer.folded_lines().is_empty());
+    }
+
+    #[test]
+    fn test_stats_follow_edits() {
+        let mut buffer = TextBuffer::from_str("# Title\r\n\r\nOne two.");
+        assert!(buffer.uses_crlf());
+        buffer.set_cursor_by_line_col(2, 8, false);
+        buffer.insert_str(" Three\nfour\n\nfive.");
+        assert_eq!(buffer.stats(), Stats::of(buffer.content()));
+        buffer.set_cursor_by_line_col(1, 0, false);
+        buffer.set_selection(buffer.cursor(), buffer.cursor() + 12);
+        buffer.backspace();
+        assert_eq!(buffer.stats(), Stats::of(buffer.content()));
+        buffer.replace_range(0, 2, "Intro\n\n#");
+        assert_eq!(buffer.stats(), Stats::of(buffer.content()));
+        buffer.undo();
+        assert_eq!(buffer.stats(), Stats::of(buffer.content()));
+        assert_eq!(buffer.stats().paragraphs, 2);
+        for _ in 0..2 {
+            buffer.undo();
+            assert_eq!(buffer.stats(), Stats::of(buffer.content()));
+        }
+        buffer.redo();
+        assert_eq!(buffer.stats(), Stats::of(buffer.content()));
+    }
+
+    #[test]
+    fn test_stats_follow_moved_lines() {
+        // No line break at the end, so moving the last line adds one
+        let mut buffer = TextBuffer::from_str("# A\none.\n\ntwo three");
+        buffer.set_cursor_by_line_col(3, 0, false);
+        buffer.move_lines_up();
+        assert_eq!(buffer.stats(), Stats::of(buffer.content()));
+        buffer.set_cursor_by_line_col(0, 0, false);
+        for _ in 0..4 {
+            buffer.move_lines_down();
+            assert_eq!(buffer.stats(), Stats::of(buffer.content()));
+        }
+        buffer.undo();
+        assert_eq!(buffer.stats(), Stats::of(buffer.content()));
+    }
+
+    #[test]
+    fn test_replace_range_is_single_undo_step() {
+        let mut buffer = TextBuffer::from_str("say hi now");
+        buffer.replace_range(4, 6, "hello");
+        assert_eq!(buffer.content(), "say hello now");
+        assert_eq!(buffer.cursor(), 9);
+        buffer.undo();
+        assert_eq!(buffer.content(), "say hi now");
+    }
+
+    #[test]
+    fn test_grapheme_cluster_movement() {
+        // ZWJ family emoji, flag pair, decomposed Hangul syllable, e + combining acute
+        let family = "👨\u{200d}👩\u{200d}👧";
+        let text = format!("a{family}🇯🇵\u{1100}\u{1161}\u{11a8}e\u{301}");
+        let mut buf = TextBuffer::from_str(&text);
+        let mut stops = vec![0];
+        while buf.cursor() < buf.len() {
+            buf.move_right(false);
+            stops.push(buf.cursor());
+        }
+        assert_eq!(stops, vec![0, 1, 6, 8, 11, 13]);
+
+        buf.move_left(false);
+        assert_eq!(buf.cursor(), 11);
+        buf.move_left(false);
+        assert_eq!(buf.cursor(), 8);
+    }
+
+    #[test]
+    fn test_backspace_and_delete_whole_cluster() {
+        let mut buf = TextBuffer::from_str("x👨\u{200d}👩\u{200d}👧e\u{301}");
+        buf.move_to_end(false);
+        buf.backspace();
+        assert_eq!(buf.content(), "x👨\u{200d}👩\u{200d}👧");
+        buf.backspace();
+        assert_eq!(buf.content(), "x");
+
+        buf.undo();
+        assert_eq!(buf.content(), "x👨\u{200d}👩\u{200d}👧");
+
+        let mut buf = TextBuffer::from_str("\u{1100}\u{1161}\u{11a8}🇯🇵!");
+        buf.delete();
+        assert_eq!(buf.content(), "🇯🇵!");
+        buf.delete();
+        assert_eq!(buf.content(), "!");
+    }
+
+    #[test]
+    fn test_crlf_is_one_step() {
+        let mut buf = TextBuffer::from_str("a\r\nb");
+        buf.cursor = 1;
+        buf.move_right(false);
+        assert_eq!(buf.cursor(), 3);
+        buf.backspace();
+        assert_eq!(buf.content(), "ab");
+    }
+
+    #[test]
+    fn test_vertical_move_snaps_to_cluster_start() {
+        let mut buf = TextBuffer::from_str("abc\ne\u{301}x");
+        buf.cursor = 1;
+        buf.move_down(false);
+        // Column 1 would split e + accent; snap to the start of the cluster
+        assert_eq!(buf.cursor(), 4);
+
+        buf.set_cursor_by_line_col(1, 1, false);
+        assert_eq!(buf.cursor(), 4);
+    }
 }
@@ -0,0 +1,229 @@
+//! Per-line glyph layout for the editor
+//!
+//! `visual_position` works in whole columns, which is only correct for a
+//! monospace font with single-width glyphs. This module turns a line of text
+//! into pixel offsets for every character boundary, so the cursor, hit testing
+//! and selection all agree with what the renderer actually draws:
+//! - With a monospace font, advances are whole cells taken from
+//!   `visual_position::get_char_visual_width` (East Asian wide characters are
+//!   two cells, combining marks zero).
+//! - With a proportional font, advances come from the font's own metrics.
+//!
+//! Layouts are cached per line content in `LineLayoutCache`; the cache is
+//! cleared whenever the font metrics change (zoom, DPI, font switch).
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::visual_position::{get_char_visual_width, TAB_WIDTH};
+
+/// Upper bound on cached lines before the cache is flushed
+const MAX_CACHED_LINES: usize = 4096;
+
+/// Font metrics that determine how a line is laid out
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutMetrics {
+    /// Width of one monospace cell (the advance of "M")
+    pub char_width: f32,
+    /// Whether the primary font is monospace
+    pub monospace: bool,
+}
+
+/// Pixel offsets of every character boundary in a single line
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineLayout {
+    /// `offsets[i]` is the x offset of the left edge of character `i`;
+    /// the final entry is the total line width.
+    offsets: Vec<f32>,
+}
+
+impl LineLayout {
+    /// Lay out a line, calling `measure` for glyph advances in proportional mode
+    pub fn new(line: &str, metrics: LayoutMetrics, mut measure: impl FnMut(char) -> f32) -> Self {
+        let mut offsets = Vec::with_capacity(line.len() + 1);
+        let mut x = 0.0;
+        offsets.push(x);
+        for ch in line.chars() {
+            if ch == '\n' || ch == '\r' {
+                break;
+            }
+            x += match ch {
+                '\t' => metrics.char_width * TAB_WIDTH as f32,
+                _ if metrics.monospace => metrics.char_width * get_char_visual_width(ch) as f32,
+                _ => measure(ch).max(0.0),
+            };
+            offsets.push(x);
+        }
+        Self { offsets }
+    }
+
+    /// Lay out a line on a fixed monospace grid (no font access needed)
+    #[allow(dead_code)]
+    pub fn monospace(line: &str, char_width: f32) -> Self {
+        let metrics = LayoutMetrics {
+            char_width,
+            monospace: true,
+        };
+        Self::new(line, metrics, |_| char_width)
+    }
+
+    /// Number of characters in the line
+    pub fn char_count(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Total width of the line in pixels
+    pub fn width(&self) -> f32 {
+        self.offsets[self.offsets.len() - 1]
+    }
+
+    /// X offset of the left edge of the character at `col` (clamped to line end)
+    pub fn x_of(&self, col: usize) -> f32 {
+        self.offsets[col.min(self.char_count())]
+    }
+
+    /// Advance width of the character at `col` (0 past the end of the line)
+    pub fn advance(&self, col: usize) -> f32 {
+        if col < self.char_count() {
+            self.offsets[col + 1] - self.offsets[col]
+        } else {
+            0.0
+        }
+    }
+
+    /// X offset of the center of the character at `col`
+    pub fn center_of(&self, col: usize) -> f32 {
+        self.x_of(col) + self.advance(col) * 0.5
+    }
+
+    /// Character boundary closest to a pixel offset (for hit testing)
+    pub fn col_at_x(&self, x: f32) -> usize {
+        if x <= 0.0 {
+            return 0;
+        }
+        // First boundary at or beyond x, then pick the nearer of it and its predecessor
+        let idx = self.offsets.partition_point(|&offset| offset < x);
+        if idx > self.char_count() {
+            return self.char_count();
+        }
+        if idx > 0 && x - self.offsets[idx - 1] < self.offsets[idx] - x {
+            idx - 1
+        } else {
+            idx
+        }
+    }
+}
+
+/// Cache of line layouts keyed by line content
+#[derive(Debug)]
+pub struct LineLayoutCache {
+    metrics: LayoutMetrics,
+    lines: HashMap<String, Rc<LineLayout>>,
+}
+
+impl LineLayoutCache {
+    pub fn new(metrics: LayoutMetrics) -> Self {
+        Self {
+            metrics,
+            lines: HashMap::new(),
+        }
+    }
+
+    /// Current layout metrics
+    pub fn metrics(&self) -> LayoutMetrics {
+        self.metrics
+    }
+
+    /// Update metrics, dropping all cached layouts if they changed
+    pub fn set_metrics(&mut self, metrics: LayoutMetrics) {
+        if self.metrics != metrics {
+            self.metrics = metrics;
+            self.lines.clear();
+        }
+    }
+
+    /// Get the layout for a line, measuring it on a cache miss
+    pub fn get(&mut self, line: &str, measure: impl FnMut(char) -> f32) -> Rc<LineLayout> {
+        if let Some(layout) = self.lines.get(line) {
+            return Rc::clone(layout);
+        }
+        if self.lines.len() >= MAX_CACHED_LINES {
+            self.lines.clear();
+        }
+        let layout = Rc::new(LineLayout::new(line, self.metrics, measure));
+        self.lines.insert(line.to_string(), Rc::clone(&layout));
+        layout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proportional(char_width: f32) -> LayoutMetrics {
+        LayoutMetrics {
+            char_width,
+            monospace: false,
+        }
+    }
+
+    #[test]
+    fn test_monospace_ascii_and_tabs() {
+        let layout = LineLayout::monospace("a\tb", 10.0);
+        assert_eq!(layout.char_count(), 3);
+        assert_eq!(layout.x_of(1), 10.0);
+        assert_eq!(layout.x_of(2), 10.0 + 10.0 * TAB_WIDTH as f32);
+        assert_eq!(layout.x_of(99), layout.width());
+    }
+
+    #[test]
+    fn test_monospace_wide_and_combining() {
+        // "日" is East Asian wide, U+0301 is a combining acute accent
+        let layout = LineLayout::monospace("日e\u{301}x", 10.0);
+        assert_eq!(layout.advance(0), 20.0);
+        assert_eq!(layout.advance(1), 10.0);
+        assert_eq!(layout.advance(2), 0.0);
+        assert_eq!(layout.x_of(3), 30.0);
+        assert_eq!(layout.width(), 40.0);
+    }
+
+    #[test]
+    fn test_proportional_uses_measured_advances() {
+        let layout = LineLayout::new("iW", proportional(10.0), |ch| match ch {
+            'i' => 4.0,
+            _ => 14.0,
+        });
+        assert_eq!(layout.x_of(1), 4.0);
+        assert_eq!(layout.width(), 18.0);
+        assert_eq!(layout.center_of(1), 11.0);
+    }
+
+    #[test]
+    fn test_col_at_x_rounds_to_nearest_boundary() {
+        let layout = LineLayout::monospace("日本", 10.0);
+        assert_eq!(layout.col_at_x(-5.0), 0);
+        assert_eq!(layout.col_at_x(9.0), 0);
+        assert_eq!(layout.col_at_x(11.0), 1);
+        assert_eq!(layout.col_at_x(31.0), 2);
+        assert_eq!(layout.col_at_x(500.0), 2);
+    }
+
+    #[test]
+    fn test_cache_invalidates_on_metrics_change() {
+        let mut cache = LineLayoutCache::new(proportional(10.0));
+        let mut calls = 0;
+        cache.get("ab", |_| {
+            calls += 1;
+            5.0
+        });
+        cache.get("ab", |_| {
+            calls += 1;
+            5.0
+        });
+        assert_eq!(calls, 2); // measured once per char, second lookup hits cache
+
+        cache.set_metrics(proportional(12.0));
+        let layout = cache.get("ab", |_| 6.0);
+        assert_eq!(layout.width(), 12.0);
+    }
+}
@@ -23,6 +23,10 @@ pub struct Theme {
     pub border: (f32, f32, f32),
     /// Cursor color
     pub cursor: (f32, f32, f32),
+    /// `[[wiki link]]` text and underline
+    pub link: (f32, f32, f32),
+    /// Squiggly underline under misspelled words
+    pub misspelling: (f32, f32, f32),
 }
 
 impl Theme {
@@ -40,6 +44,8 @@ impl Theme {
             button_fg: (1.0, 0.6, 0.0),         // Flame yellow-orange
             border: (0.2, 0.05, 0.05),          // Deep ember border
             cursor: (1.0, 0.8, 0.0),            // Bright yellow flame
+            link: (1.0, 0.65, 0.25),            // Warm amber
+            misspelling: (1.0, 0.2, 0.2),       // Hot red
         }
     }
 
@@ -58,6 +64,8 @@ impl Theme {
             button_fg: (0.2, 0.4, 0.8), // Blue accent
             border: (0.85, 0.85, 0.85),
             cursor: (0.2, 0.4, 0.8),         // Blue
+            link: (0.1, 0.35, 0.75),         // Link blue
+            misspelling: (0.85, 0.1, 0.1),   // Red
         }
     }
 }
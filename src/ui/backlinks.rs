@@ -0,0 +1,135 @@
+//! Backlinks panel: the notes that link to the current one
+//!
+//! The panel sits on the right of the window below the tab bar. Its list
+//! starts under a header line and scrolls independently of the editor.
+
+use std::path::{Path, PathBuf};
+
+use super::status_bar::status_bar_top;
+use super::types::Rect;
+use crate::config::layout;
+
+#[derive(Debug, Clone)]
+pub struct BacklinksPanel {
+    /// Note the backlinks were collected for; None for unsaved tabs
+    note: Option<PathBuf>,
+    /// Linking notes with their display titles
+    entries: Vec<(PathBuf, String)>,
+    scroll_offset: usize,
+    hovered: Option<usize>,
+}
+
+impl BacklinksPanel {
+    pub fn new(note: Option<PathBuf>, entries: Vec<(PathBuf, String)>) -> Self {
+        Self {
+            note,
+            entries,
+            scroll_offset: 0,
+            hovered: None,
+        }
+    }
+
+    pub fn note(&self) -> Option<&Path> {
+        self.note.as_deref()
+    }
+
+    pub fn entries(&self) -> &[(PathBuf, String)] {
+        &self.entries
+    }
+
+    /// Replace the list, resetting scroll when it is for a different note
+    pub fn refresh(&mut self, note: Option<PathBuf>, entries: Vec<(PathBuf, String)>) {
+        if note != self.note {
+            self.scroll_offset = 0;
+        }
+        self.note = note;
+        self.entries = entries;
+        self.scroll_offset = self.scroll_offset.min(self.entries.len().saturating_sub(1));
+        self.hovered = None;
+    }
+
+    pub fn scroll_offset(&self) -> usize {
+        self.scroll_offset
+    }
+
+    pub fn hovered(&self) -> Option<usize> {
+        self.hovered
+    }
+
+    /// Returns true when the hovered row changed
+    pub fn set_hovered(&mut self, row: Option<usize>) -> bool {
+        let changed = self.hovered != row;
+        self.hovered = row;
+        changed
+    }
+
+    /// Scroll by `delta` rows, keeping at least one screen of rows in view
+    pub fn scroll_by(&mut self, delta: isize, visible_rows: usize) -> bool {
+        let max_offset = self.entries.len().saturating_sub(visible_rows);
+        let offset = (self.scroll_offset as isize + delta).clamp(0, max_offset as isize) as usize;
+        let changed = offset != self.scroll_offset;
+        self.scroll_offset = offset;
+        changed
+    }
+
+    /// Index of the entry at `y` pixels below the top of the list
+    pub fn row_at(&self, relative_y: f32, row_height: f32) -> Option<usize> {
+        if relative_y < 0.0 {
+            return None;
+        }
+        let index = self.scroll_offset + (relative_y / row_height) as usize;
+        (index < self.entries.len()).then_some(index)
+    }
+}
+
+/// Backlinks panel area on the right of the window, below the tab bar
+pub fn backlinks_rect(width: f32, height: f32, scale: f32) -> Rect {
+    let y = layout::TAB_HEIGHT * scale;
+    let panel_width = layout::BACKLINKS_WIDTH * scale;
+    Rect {
+        x: (width - panel_width).max(0.0),
+        y,
+        width: panel_width.min(width),
+        height: (status_bar_top(height, scale) - y).max(0.0),
+    }
+}
+
+/// Top of the entry list inside the panel, below the header line
+pub fn backlinks_list_top(rect: Rect, scale: f32) -> f32 {
+    rect.y + layout::PADDING * 0.5 * scale + layout::SIDEBAR_ROW_HEIGHT * scale
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn panel(count: usize) -> BacklinksPanel {
+        let entries = (0..count)
+            .map(|i| (PathBuf::from(format!("{i}.md")), format!("Note {i}")))
+            .collect();
+        BacklinksPanel::new(Some(PathBuf::from("target.md")), entries)
+    }
+
+    #[test]
+    fn test_row_at_follows_scroll() {
+        let mut panel = panel(10);
+        assert_eq!(panel.row_at(-1.0, 20.0), None);
+        assert_eq!(panel.row_at(45.0, 20.0), Some(2));
+        assert!(panel.scroll_by(3, 4));
+        assert_eq!(panel.row_at(45.0, 20.0), Some(5));
+        assert_eq!(panel.row_at(200.0, 20.0), None);
+        // Cannot scroll past the last screen of rows
+        assert!(panel.scroll_by(100, 4));
+        assert_eq!(panel.scroll_offset(), 6);
+    }
+
+    #[test]
+    fn test_refresh_keeps_scroll_for_same_note() {
+        let mut panel = panel(10);
+        panel.scroll_by(5, 2);
+        panel.refresh(Some(PathBuf::from("target.md")), panel.entries().to_vec());
+        assert_eq!(panel.scroll_offset(), 5);
+        panel.refresh(Some(PathBuf::from("other.md")), Vec::new());
+        assert_eq!(panel.scroll_offset(), 0);
+    }
+}
@@ -0,0 +1,186 @@
+//! Month calendar for picking a daily note
+//!
+//! The days of the shown month are kept in a `ListWidget`, so selection
+//! works like any other list. The grid starts on Monday; moving the
+//! selection past either end of the month turns the page.
+
+use super::list_widget::ListWidget;
+use super::types::Rect;
+use crate::config::layout;
+use crate::date::{self, Date};
+
+/// Rows above the day grid: the month title and the weekday names
+const HEADER_ROWS: f32 = 2.0;
+
+#[derive(Debug, Clone)]
+pub struct CalendarDay {
+    pub date: Date,
+    pub has_note: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Calendar {
+    days: ListWidget<CalendarDay>,
+    today: Date,
+    /// Days with a daily note, across all months
+    noted: Vec<Date>,
+}
+
+impl Calendar {
+    pub fn new(selected: Date, today: Date, noted: Vec<Date>) -> Self {
+        let mut calendar = Self {
+            days: ListWidget::new(Vec::new()),
+            today,
+            noted,
+        };
+        calendar.show(selected);
+        calendar
+    }
+
+    /// Fill the list with the month of `selected` and select it
+    fn show(&mut self, selected: Date) {
+        let first = selected.first_of_month();
+        let count = date::days_in_month(first.year, first.month);
+        let days = (0..count)
+            .map(|offset| {
+                let date = first.add_days(i64::from(offset));
+                CalendarDay {
+                    date,
+                    has_note: self.noted.contains(&date),
+                }
+            })
+            .collect();
+        self.days = ListWidget::new(days);
+        self.days.set_max_visible(count as usize);
+        self.days.select_index(selected.day as usize - 1);
+    }
+
+    /// First day of the shown month
+    pub fn month(&self) -> Date {
+        self.selected().first_of_month()
+    }
+
+    pub fn days(&self) -> &ListWidget<CalendarDay> {
+        &self.days
+    }
+
+    pub fn today(&self) -> Date {
+        self.today
+    }
+
+    pub fn selected(&self) -> Date {
+        self.days.selected_item().map_or(self.today, |day| day.date)
+    }
+
+    /// Select a date, turning to its month if needed
+    pub fn select(&mut self, date: Date) {
+        if date.first_of_month() == self.month() {
+            self.days.select_index(date.day as usize - 1);
+        } else {
+            self.show(date);
+        }
+    }
+
+    pub fn move_days(&mut self, days: i64) {
+        self.select(self.selected().add_days(days));
+    }
+
+    pub fn move_months(&mut self, months: i32) {
+        self.select(self.selected().add_months(months));
+    }
+
+    /// Empty cells before the first of the month
+    pub fn leading_blanks(&self) -> usize {
+        self.month().weekday() as usize
+    }
+
+    /// Grid slot (row-major, including the leading blanks) of a day
+    pub fn slot_of(&self, index: usize) -> usize {
+        self.leading_blanks() + index
+    }
+
+    /// Index of the day under a point, if any
+    pub fn index_at(&self, rect: Rect, scale: f32, x: f32, y: f32) -> Option<usize> {
+        let cell = layout::CALENDAR_CELL_SIZE * scale;
+        let grid_x = x - calendar_grid_left(rect, scale);
+        let grid_y = y - calendar_grid_top(rect, scale);
+        if grid_x < 0.0 || grid_y < 0.0 || grid_x >= 7.0 * cell {
+            return None;
+        }
+        let slot = (grid_y / cell) as usize * 7 + (grid_x / cell) as usize;
+        let index = slot.checked_sub(self.leading_blanks())?;
+        (index < self.days.len()).then_some(index)
+    }
+}
+
+/// Calendar overlay, centered below the tab bar like the notes picker.
+/// Always tall enough for six weeks.
+pub fn calendar_rect(width: f32, scale: f32) -> Rect {
+    let cell = layout::CALENDAR_CELL_SIZE * scale;
+    let padding = layout::PADDING * scale;
+    let overlay_width = 7.0 * cell + 2.0 * padding;
+    Rect {
+        x: ((width - overlay_width) / 2.0).max(0.0),
+        y: 60.0 * scale,
+        width: overlay_width,
+        height: (HEADER_ROWS + 6.0) * cell + 2.0 * padding,
+    }
+}
+
+pub fn calendar_grid_left(rect: Rect, scale: f32) -> f32 {
+    rect.x + layout::PADDING * scale
+}
+
+/// Top of the day grid, below the month title and weekday names
+pub fn calendar_grid_top(rect: Rect, scale: f32) -> f32 {
+    rect.y + layout::PADDING * scale + HEADER_ROWS * layout::CALENDAR_CELL_SIZE * scale
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> Date {
+        Date { year, month, day }
+    }
+
+    #[test]
+    fn test_navigation_turns_pages() {
+        let today = date(2024, 3, 9);
+        let mut calendar = Calendar::new(today, today, vec![date(2024, 2, 29)]);
+        // March 2024 starts on a Friday
+        assert_eq!(calendar.leading_blanks(), 4);
+        assert_eq!(calendar.days().len(), 31);
+
+        calendar.move_days(-9);
+        assert_eq!(calendar.selected(), date(2024, 2, 29));
+        assert!(calendar.days().selected_item().unwrap().has_note);
+
+        calendar.move_months(-12);
+        assert_eq!(calendar.selected(), date(2023, 2, 28));
+        assert_eq!(calendar.days().len(), 28);
+    }
+
+    #[test]
+    fn test_index_at() {
+        let today = date(2024, 3, 9);
+        let calendar = Calendar::new(today, today, Vec::new());
+        let rect = calendar_rect(800.0, 1.0);
+        let cell = layout::CALENDAR_CELL_SIZE;
+        let left = calendar_grid_left(rect, 1.0);
+        let top = calendar_grid_top(rect, 1.0);
+        // Blank cell before the 1st
+        assert_eq!(calendar.index_at(rect, 1.0, left + 1.0, top + 1.0), None);
+        // Friday of the first week is the 1st
+        assert_eq!(
+            calendar.index_at(rect, 1.0, left + 4.5 * cell, top + 1.0),
+            Some(0)
+        );
+        // Monday of the second week is the 4th
+        assert_eq!(
+            calendar.index_at(rect, 1.0, left + 1.0, top + 1.5 * cell),
+            Some(3)
+        );
+        assert_eq!(calendar.index_at(rect, 1.0, left - 1.0, top + 1.0), None);
+    }
+}
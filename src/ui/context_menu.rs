@@ -0,0 +1,251 @@
+//! Popup context menu widget
+//!
+//! Holds the items, placement and keyboard selection of a right-click menu.
+//! The item payload is generic (the app stores `Action`s), so this widget only
+//! knows about layout and selection. `UiTree` uses `MenuGeometry` for hit
+//! testing; drawing lives in the renderer.
+
+use super::types::Rect;
+
+/// Height of a single menu item (logical pixels)
+const ITEM_HEIGHT: f32 = 28.0;
+/// Width of the menu (logical pixels)
+const MENU_WIDTH: f32 = 220.0;
+/// Vertical padding above the first and below the last item
+const MENU_PADDING: f32 = 4.0;
+
+/// One entry in a context menu
+#[derive(Debug, Clone)]
+pub struct MenuItem<A> {
+    pub label: String,
+    pub shortcut: Option<&'static str>,
+    pub enabled: bool,
+    pub action: A,
+}
+
+impl<A> MenuItem<A> {
+    pub fn new(label: impl Into<String>, action: A) -> Self {
+        Self {
+            label: label.into(),
+            shortcut: None,
+            enabled: true,
+            action,
+        }
+    }
+
+    /// Shortcut hint drawn right-aligned next to the label
+    pub fn with_shortcut(mut self, shortcut: &'static str) -> Self {
+        self.shortcut = Some(shortcut);
+        self
+    }
+
+    /// Disabled items are drawn dimmed and cannot be activated
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
+
+/// Placement of an open menu, used for hit testing without the item payload
+#[derive(Debug, Clone, Copy)]
+pub struct MenuGeometry {
+    pub rect: Rect,
+    pub item_height: f32,
+    pub padding: f32,
+    pub item_count: usize,
+}
+
+impl MenuGeometry {
+    /// Rectangle of the item at `index`
+    pub fn item_rect(&self, index: usize) -> Rect {
+        Rect {
+            x: self.rect.x,
+            y: self.rect.y + self.padding + index as f32 * self.item_height,
+            width: self.rect.width,
+            height: self.item_height,
+        }
+    }
+
+    /// Index of the item under a point, if any
+    pub fn item_at(&self, x: f32, y: f32) -> Option<usize> {
+        if !self.rect.contains(x, y) {
+            return None;
+        }
+        let relative_y = y - self.rect.y - self.padding;
+        if relative_y < 0.0 {
+            return None;
+        }
+        let index = (relative_y / self.item_height) as usize;
+        (index < self.item_count).then_some(index)
+    }
+}
+
+/// An open context menu
+#[derive(Debug, Clone)]
+pub struct ContextMenu<A> {
+    items: Vec<MenuItem<A>>,
+    geometry: MenuGeometry,
+    selected: Option<usize>,
+}
+
+impl<A> ContextMenu<A> {
+    /// Open a menu with its top-left corner at (x, y), shifted so it stays
+    /// inside the window
+    pub fn new(
+        items: Vec<MenuItem<A>>,
+        x: f32,
+        y: f32,
+        window_width: f32,
+        window_height: f32,
+        scale: f32,
+    ) -> Self {
+        let item_height = ITEM_HEIGHT * scale;
+        let padding = MENU_PADDING * scale;
+        let width = MENU_WIDTH * scale;
+        let height = items.len() as f32 * item_height + padding * 2.0;
+
+        // Open towards the left/top when there is no room to the right/bottom
+        let x = if x + width > window_width {
+            (x - width).max(0.0)
+        } else {
+            x
+        };
+        let y = if y + height > window_height {
+            (y - height).max(0.0)
+        } else {
+            y
+        };
+
+        let item_count = items.len();
+        Self {
+            items,
+            geometry: MenuGeometry {
+                rect: Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                },
+                item_height,
+                padding,
+                item_count,
+            },
+            selected: None,
+        }
+    }
+
+    pub fn items(&self) -> &[MenuItem<A>] {
+        &self.items
+    }
+
+    pub fn geometry(&self) -> MenuGeometry {
+        self.geometry
+    }
+
+    /// Currently highlighted item (keyboard selection or mouse hover)
+    pub fn selected_index(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Highlight the item under the mouse; returns true if the highlight changed
+    pub fn set_hovered(&mut self, index: Option<usize>) -> bool {
+        let index = index.filter(|&i| self.is_enabled(i));
+        if index.is_none() || self.selected == index {
+            return false;
+        }
+        self.selected = index;
+        true
+    }
+
+    /// Move the highlight to the next enabled item (wrapping)
+    pub fn select_next(&mut self) {
+        self.step_selection(1);
+    }
+
+    /// Move the highlight to the previous enabled item (wrapping)
+    pub fn select_previous(&mut self) {
+        self.step_selection(self.items.len().saturating_sub(1));
+    }
+
+    fn step_selection(&mut self, step: usize) {
+        let count = self.items.len();
+        if count == 0 {
+            return;
+        }
+        // With nothing selected, start just outside the list so the first
+        // step lands on the first (or last) item
+        let mut index = self
+            .selected
+            .unwrap_or(if step == 1 { count - 1 } else { 0 });
+        for _ in 0..count {
+            index = (index + step) % count;
+            if self.is_enabled(index) {
+                self.selected = Some(index);
+                return;
+            }
+        }
+    }
+
+    /// Action of the item at `index`, if it exists and is enabled
+    pub fn action_at(&self, index: usize) -> Option<&A> {
+        self.items
+            .get(index)
+            .filter(|item| item.enabled)
+            .map(|item| &item.action)
+    }
+
+    /// Action of the highlighted item, if any
+    pub fn selected_action(&self) -> Option<&A> {
+        self.selected.and_then(|index| self.action_at(index))
+    }
+
+    fn is_enabled(&self, index: usize) -> bool {
+        self.items.get(index).is_some_and(|item| item.enabled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn menu() -> ContextMenu<u8> {
+        let items = vec![
+            MenuItem::new("Cut", 1).enabled(false),
+            MenuItem::new("Copy", 2),
+            MenuItem::new("Paste", 3),
+        ];
+        ContextMenu::new(items, 10.0, 10.0, 800.0, 600.0, 1.0)
+    }
+
+    #[test]
+    fn test_keyboard_selection_skips_disabled() {
+        let mut menu = menu();
+        menu.select_next();
+        assert_eq!(menu.selected_action(), Some(&2));
+        menu.select_next();
+        assert_eq!(menu.selected_action(), Some(&3));
+        menu.select_next();
+        assert_eq!(menu.selected_action(), Some(&2));
+        menu.select_previous();
+        assert_eq!(menu.selected_action(), Some(&3));
+    }
+
+    #[test]
+    fn test_hit_testing() {
+        let menu = menu();
+        let geometry = menu.geometry();
+        let second = geometry.item_rect(1);
+        assert_eq!(geometry.item_at(second.x + 5.0, second.y + 5.0), Some(1));
+        assert_eq!(geometry.item_at(5.0, 5.0), None);
+        assert_eq!(menu.action_at(0), None);
+    }
+
+    #[test]
+    fn test_menu_stays_inside_window() {
+        let items = vec![MenuItem::new("Copy", 0)];
+        let menu = ContextMenu::new(items, 790.0, 590.0, 800.0, 600.0, 1.0);
+        let rect = menu.geometry().rect;
+        assert!(rect.x + rect.width <= 800.0);
+        assert!(rect.y + rect.height <= 600.0);
+    }
+}
@@ -1,16 +1,27 @@
 //! UI layout and hit-testing
 
 mod types;
+mod backlinks;
+mod calendar;
+mod context_menu;
 mod tab_bar;
 mod list_widget;
 mod scrollbar;
+mod sidebar;
+mod status_bar;
 mod text_area;
 mod text_input;
 mod tree;
 
 // Re-export public types used by other modules
-pub use types::{ResizeEdge, UiAction, UiDragAction, UiNode};
+pub use types::{Rect, ResizeEdge, UiAction, UiDragAction, UiNode};
+pub use backlinks::{backlinks_list_top, backlinks_rect, BacklinksPanel};
+pub use calendar::{calendar_grid_left, calendar_grid_top, calendar_rect, Calendar};
+pub use context_menu::{ContextMenu, MenuItem};
 pub use list_widget::ListWidget;
 pub use scrollbar::ScrollbarWidget;
+pub use sidebar::{sidebar_rect, NotebookTree, SidebarRowKind};
+pub use status_bar::{status_bar_rect, SaveStatus, StatusInfo};
+pub use tab_bar::{tab_close_rect, tab_width};
 pub use text_input::TextInput;
 pub use tree::UiTree;
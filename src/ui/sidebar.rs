@@ -0,0 +1,256 @@
+//! Collapsible notebook tree shown in the sidebar
+//!
+//! The tree is built from the folders and notes below a root directory.
+//! Folders come before notes at every level; both are sorted by name.
+//! Only rows inside expanded folders are visible.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use super::status_bar::status_bar_top;
+use super::types::Rect;
+use crate::config::layout;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SidebarRowKind {
+    Folder { expanded: bool },
+    Note,
+}
+
+/// One visible line of the tree
+#[derive(Debug, Clone)]
+pub struct SidebarRow {
+    pub kind: SidebarRowKind,
+    pub path: PathBuf,
+    pub label: String,
+    pub depth: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct NotebookTree {
+    root: PathBuf,
+    folders: Vec<PathBuf>,
+    /// Note paths with their display titles
+    notes: Vec<(PathBuf, String)>,
+    collapsed: HashSet<PathBuf>,
+    /// Visible rows, rebuilt whenever the tree or a folder's state changes
+    rows: Vec<SidebarRow>,
+    scroll_offset: usize,
+    hovered: Option<usize>,
+}
+
+impl NotebookTree {
+    pub fn new(root: PathBuf, folders: Vec<PathBuf>, notes: Vec<(PathBuf, String)>) -> Self {
+        let mut tree = Self {
+            root,
+            folders,
+            notes,
+            collapsed: HashSet::new(),
+            rows: Vec::new(),
+            scroll_offset: 0,
+            hovered: None,
+        };
+        tree.rebuild_rows();
+        tree
+    }
+
+    /// Replace the folders and notes, keeping collapsed folders collapsed
+    pub fn refresh(&mut self, folders: Vec<PathBuf>, notes: Vec<(PathBuf, String)>) {
+        self.folders = folders;
+        self.notes = notes;
+        self.rebuild_rows();
+    }
+
+    pub fn rows(&self) -> &[SidebarRow] {
+        &self.rows
+    }
+
+    pub fn scroll_offset(&self) -> usize {
+        self.scroll_offset
+    }
+
+    pub fn hovered(&self) -> Option<usize> {
+        self.hovered
+    }
+
+    /// Returns true when the hovered row changed
+    pub fn set_hovered(&mut self, row: Option<usize>) -> bool {
+        let changed = self.hovered != row;
+        self.hovered = row;
+        changed
+    }
+
+    /// Expand or collapse a folder. Returns false if `path` is not a folder.
+    pub fn toggle_folder(&mut self, path: &Path) -> bool {
+        if !self.folders.iter().any(|folder| folder == path) {
+            return false;
+        }
+        if !self.collapsed.remove(path) {
+            self.collapsed.insert(path.to_path_buf());
+        }
+        self.rebuild_rows();
+        true
+    }
+
+    /// Scroll by `delta` rows, keeping at least one screen of rows in view
+    pub fn scroll_by(&mut self, delta: isize, visible_rows: usize) -> bool {
+        let max_offset = self.rows.len().saturating_sub(visible_rows);
+        let offset = (self.scroll_offset as isize + delta).clamp(0, max_offset as isize) as usize;
+        let changed = offset != self.scroll_offset;
+        self.scroll_offset = offset;
+        changed
+    }
+
+    /// Index of the row at `y` pixels below the top of the list
+    pub fn row_at(&self, relative_y: f32, row_height: f32) -> Option<usize> {
+        if relative_y < 0.0 {
+            return None;
+        }
+        let index = self.scroll_offset + (relative_y / row_height) as usize;
+        (index < self.rows.len()).then_some(index)
+    }
+
+    fn rebuild_rows(&mut self) {
+        let mut rows = Vec::new();
+        self.push_children(&self.root, 0, &mut rows);
+        self.rows = rows;
+        self.scroll_offset = self.scroll_offset.min(self.rows.len().saturating_sub(1));
+        self.hovered = None;
+    }
+
+    fn push_children(&self, dir: &Path, depth: usize, rows: &mut Vec<SidebarRow>) {
+        let mut folders: Vec<&PathBuf> = self
+            .folders
+            .iter()
+            .filter(|folder| folder.parent() == Some(dir))
+            .collect();
+        folders.sort_by_key(|folder| file_name(folder).to_lowercase());
+
+        for folder in folders {
+            let expanded = !self.collapsed.contains(folder);
+            rows.push(SidebarRow {
+                kind: SidebarRowKind::Folder { expanded },
+                path: folder.clone(),
+                label: file_name(folder),
+                depth,
+            });
+            if expanded {
+                self.push_children(folder, depth + 1, rows);
+            }
+        }
+
+        let mut notes: Vec<&(PathBuf, String)> = self
+            .notes
+            .iter()
+            .filter(|(path, _)| path.parent() == Some(dir))
+            .collect();
+        notes.sort_by_key(|(_, title)| title.to_lowercase());
+
+        rows.extend(notes.into_iter().map(|(path, title)| SidebarRow {
+            kind: SidebarRowKind::Note,
+            path: path.clone(),
+            label: title.clone(),
+            depth,
+        }));
+    }
+}
+
+/// Sidebar area on the left of the window, below the tab bar
+pub fn sidebar_rect(height: f32, scale: f32) -> Rect {
+    let y = layout::TAB_HEIGHT * scale;
+    Rect {
+        x: 0.0,
+        y,
+        width: layout::SIDEBAR_WIDTH * scale,
+        height: (status_bar_top(height, scale) - y).max(0.0),
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_tree() -> NotebookTree {
+        let root = PathBuf::from("/notes");
+        NotebookTree::new(
+            root.clone(),
+            vec![
+                root.join("work"),
+                root.join("work/projects"),
+                root.join("archive"),
+            ],
+            vec![
+                (root.join("inbox.md"), "Inbox".to_string()),
+                (root.join("work/plan.md"), "Plan".to_string()),
+                (root.join("work/projects/fire.md"), "Fire".to_string()),
+            ],
+        )
+    }
+
+    fn labels(tree: &NotebookTree) -> Vec<(&str, usize)> {
+        tree.rows()
+            .iter()
+            .map(|row| (row.label.as_str(), row.depth))
+            .collect()
+    }
+
+    #[test]
+    fn test_folders_before_notes_depth_first() {
+        let tree = sample_tree();
+        assert_eq!(
+            labels(&tree),
+            vec![
+                ("archive", 0),
+                ("work", 0),
+                ("projects", 1),
+                ("Fire", 2),
+                ("Plan", 1),
+                ("Inbox", 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collapse_hides_children_and_survives_refresh() {
+        let mut tree = sample_tree();
+        assert!(tree.toggle_folder(Path::new("/notes/work")));
+        assert_eq!(
+            labels(&tree),
+            vec![("archive", 0), ("work", 0), ("Inbox", 0)]
+        );
+        assert_eq!(
+            tree.rows()[1].kind,
+            SidebarRowKind::Folder { expanded: false }
+        );
+
+        let folders = tree.folders.clone();
+        let notes = tree.notes.clone();
+        tree.refresh(folders, notes);
+        assert_eq!(tree.rows().len(), 3);
+
+        assert!(!tree.toggle_folder(Path::new("/notes/inbox.md")));
+        assert!(tree.toggle_folder(Path::new("/notes/work")));
+        assert_eq!(tree.rows().len(), 6);
+    }
+
+    #[test]
+    fn test_row_at_accounts_for_scroll() {
+        let mut tree = sample_tree();
+        assert_eq!(tree.row_at(5.0, 10.0), Some(0));
+        assert_eq!(tree.row_at(-1.0, 10.0), None);
+        assert_eq!(tree.row_at(60.0, 10.0), None);
+
+        assert!(tree.scroll_by(2, 3));
+        assert_eq!(tree.row_at(5.0, 10.0), Some(2));
+        // Cannot scroll past the last screen of rows
+        assert!(tree.scroll_by(10, 3));
+        assert_eq!(tree.scroll_offset(), 3);
+        assert!(!tree.scroll_by(1, 3));
+    }
+}
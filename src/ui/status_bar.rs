@@ -0,0 +1,118 @@
+//! Status bar along the bottom of the window
+//!
+//! The left side shows where the cursor is, the right side facts about the
+//! document. Segments are built here so the renderer only lays them out.
+
+use super::types::Rect;
+use crate::config::layout;
+
+/// Whether the document is on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveStatus {
+    /// Never saved to a file
+    NotSaved,
+    Modified,
+    Saved,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusInfo {
+    /// Cursor line and column, counted from 0
+    pub line: usize,
+    pub col: usize,
+    /// Characters selected
+    pub selected: usize,
+    pub words: usize,
+    pub chars: usize,
+    pub crlf: bool,
+    pub word_wrap: bool,
+    pub save: SaveStatus,
+    /// Spell checking is on but has no dictionary to check against
+    pub no_dictionary: bool,
+}
+
+impl StatusInfo {
+    /// Left segments (cursor) and right segments (document)
+    pub fn segments(&self) -> (Vec<String>, Vec<String>) {
+        let mut left = vec![format!("Ln {}, Col {}", self.line + 1, self.col + 1)];
+        if self.selected > 0 {
+            left.push(format!("{} selected", self.selected));
+        }
+        let mut right = vec![
+            format!(
+                "{} {}, {} {}",
+                self.words,
+                plural(self.words, "word"),
+                self.chars,
+                plural(self.chars, "char")
+            ),
+            if self.crlf { "CRLF" } else { "LF" }.to_string(),
+            if self.word_wrap { "Wrap" } else { "No Wrap" }.to_string(),
+            match self.save {
+                SaveStatus::NotSaved => "Not Saved",
+                SaveStatus::Modified => "Modified",
+                SaveStatus::Saved => "Saved",
+            }
+            .to_string(),
+        ];
+        if self.no_dictionary {
+            right.push("No Dictionary".to_string());
+        }
+        (left, right)
+    }
+}
+
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        word.to_string()
+    } else {
+        format!("{word}s")
+    }
+}
+
+/// The bar spans the window width; the editor and side panels end where it
+/// starts
+pub fn status_bar_rect(width: f32, height: f32, scale: f32) -> Rect {
+    let y = status_bar_top(height, scale);
+    Rect {
+        x: 0.0,
+        y,
+        width,
+        height: height - y,
+    }
+}
+
+pub(super) fn status_bar_top(height: f32, scale: f32) -> f32 {
+    height - (layout::STATUS_BAR_HEIGHT * scale).min(height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segments() {
+        let mut info = StatusInfo {
+            line: 4,
+            col: 0,
+            selected: 0,
+            words: 1,
+            chars: 12,
+            crlf: false,
+            word_wrap: true,
+            save: SaveStatus::Modified,
+            no_dictionary: false,
+        };
+        let (left, right) = info.segments();
+        assert_eq!(left, ["Ln 5, Col 1"]);
+        assert_eq!(right, ["1 word, 12 chars", "LF", "Wrap", "Modified"]);
+
+        info.selected = 3;
+        info.crlf = true;
+        assert_eq!(info.segments().0, ["Ln 5, Col 1", "3 selected"]);
+        assert_eq!(info.segments().1[1], "CRLF");
+
+        info.no_dictionary = true;
+        assert_eq!(info.segments().1[4], "No Dictionary");
+    }
+}
@@ -7,6 +7,26 @@ use super::types::{Rect, UiNode};
 pub struct TabMetrics {
     pub index: usize,
     pub rect: Rect,
+    pub close_rect: Rect,
+}
+
+/// Width of a tab with the given title, including room for its close button
+pub fn tab_width(title: &str, scale: f32) -> f32 {
+    (title.len() as f32 * rendering::TAB_CHAR_WIDTH_RATIO * scale
+        + layout::TAB_PADDING * scale * 2.0
+        + layout::TAB_CLOSE_BUTTON_SIZE * scale)
+        .max(layout::MIN_TAB_WIDTH * scale)
+}
+
+/// Close button of a tab occupying `tab_rect`, vertically centered at the right edge
+pub fn tab_close_rect(tab_rect: Rect, scale: f32) -> Rect {
+    let size = layout::TAB_CLOSE_BUTTON_SIZE * scale;
+    Rect {
+        x: tab_rect.x + tab_rect.width - layout::TAB_CLOSE_BUTTON_MARGIN * scale - size,
+        y: tab_rect.y + (tab_rect.height - size) / 2.0,
+        width: size,
+        height: size,
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -22,7 +42,6 @@ pub struct TabBar {
 impl TabBar {
     pub fn new(width: f32, scale: f32, tab_scroll_x: f32, tabs: &[(&str, bool)]) -> Self {
         let tab_height = layout::TAB_HEIGHT * scale;
-        let tab_padding = layout::TAB_PADDING * scale;
         let button_size = 28.0 * scale;
         let button_margin = 8.0 * scale;
         let button_y = (tab_height - button_size) / 2.0;
@@ -51,17 +70,19 @@ impl TabBar {
         let mut tab_metrics = Vec::with_capacity(tabs.len());
 
         for (i, (title, _)) in tabs.iter().enumerate() {
-            let tab_width =
-                (title.len() as f32 * rendering::TAB_CHAR_WIDTH_RATIO * scale + tab_padding * 2.0)
-                    .max(layout::MIN_TAB_WIDTH * scale);
+            let width = tab_width(title, scale);
             let rect = Rect {
                 x: current_x,
                 y: 0.0,
-                width: tab_width,
+                width,
                 height: tab_height,
             };
-            tab_metrics.push(TabMetrics { index: i, rect });
-            current_x += tab_width + 1.0;
+            tab_metrics.push(TabMetrics {
+                index: i,
+                rect,
+                close_rect: tab_close_rect(rect, scale),
+            });
+            current_x += width + 1.0;
         }
 
         let new_tab_button_size = layout::NEW_TAB_BUTTON_SIZE * scale;
@@ -104,6 +125,9 @@ impl TabBar {
         }
 
         for tab in &self.tabs {
+            if tab.close_rect.contains(x, y) {
+                return UiNode::TabClose(tab.index);
+            }
             if tab.rect.contains(x, y) {
                 return UiNode::Tab(tab.index);
             }
@@ -116,3 +140,21 @@ impl TabBar {
         UiNode::TabBar
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_close_button_hit_inside_tab() {
+        let bar = TabBar::new(800.0, 1.0, 0.0, &[("one", true), ("two", false)]);
+        let second = &bar.tabs[1];
+        let close = second.close_rect;
+        assert_eq!(
+            bar.hit_test(close.x + close.width / 2.0, close.y + close.height / 2.0),
+            UiNode::TabClose(1)
+        );
+        assert_eq!(bar.hit_test(second.rect.x + 4.0, 20.0), UiNode::Tab(1));
+        assert!(close.x + close.width <= second.rect.x + second.rect.width);
+    }
+}
@@ -1,5 +1,8 @@
 //! Single-line text input widget
 
+use crate::grapheme;
+use crate::word::{self, WordMode};
+
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct TextInput {
@@ -53,9 +56,9 @@ impl TextInput {
             return;
         }
         if self.cursor > 0 {
-            let prev_char = self.text[..self.cursor].chars().last().unwrap();
-            self.cursor -= prev_char.len_utf8();
-            self.text.remove(self.cursor);
+            let start = grapheme::prev_boundary(&self.text, self.cursor);
+            self.text.drain(start..self.cursor);
+            self.cursor = start;
         }
     }
 
@@ -64,7 +67,8 @@ impl TextInput {
             return;
         }
         if self.cursor < self.text.len() {
-            self.text.remove(self.cursor);
+            let end = grapheme::next_boundary(&self.text, self.cursor);
+            self.text.drain(self.cursor..end);
         }
     }
 
@@ -75,7 +79,7 @@ impl TextInput {
         if self.cursor == 0 {
             return;
         }
-        let start = self.find_word_boundary_left();
+        let start = word::delete_word_left_start(&self.text, self.cursor);
         self.text.drain(start..self.cursor);
         self.cursor = start;
     }
@@ -87,59 +91,10 @@ impl TextInput {
         if self.cursor >= self.text.len() {
             return;
         }
-        // Find end of current word (don't include trailing whitespace)
-        let text = &self.text[self.cursor..];
-        let mut end = self.cursor;
-        let mut chars = text.chars().peekable();
-        
-        // Skip current word characters
-        while let Some(&ch) = chars.peek() {
-            if ch.is_whitespace() {
-                break;
-            }
-            end += ch.len_utf8();
-            chars.next();
-        }
-        
-        // If we started on whitespace, delete just the whitespace
-        if end == self.cursor {
-            while let Some(&ch) = chars.peek() {
-                if !ch.is_whitespace() {
-                    break;
-                }
-                end += ch.len_utf8();
-                chars.next();
-            }
-        }
-        
+        let end = word::delete_word_right_end(&self.text, self.cursor);
         self.text.drain(self.cursor..end);
     }
 
-    fn find_word_boundary_left(&self) -> usize {
-        let text = &self.text[..self.cursor];
-        let mut chars = text.char_indices().rev().peekable();
-        
-        // Skip trailing whitespace
-        while let Some(&(_, ch)) = chars.peek() {
-            if !ch.is_whitespace() {
-                break;
-            }
-            chars.next();
-        }
-        
-        // Skip word characters
-        let mut last_idx = 0;
-        while let Some((idx, ch)) = chars.next() {
-            if ch.is_whitespace() {
-                last_idx = idx + ch.len_utf8();
-                break;
-            }
-            last_idx = idx;
-        }
-        
-        last_idx
-    }
-
     pub fn move_left(&mut self, selecting: bool) {
         if !selecting {
             if let Some((start, _end)) = self.selection_range() {
@@ -152,8 +107,7 @@ impl TextInput {
             if selecting && self.selection_anchor.is_none() {
                 self.selection_anchor = Some(self.cursor);
             }
-            let prev_char = self.text[..self.cursor].chars().last().unwrap();
-            self.cursor -= prev_char.len_utf8();
+            self.cursor = grapheme::prev_boundary(&self.text, self.cursor);
             if !selecting {
                 self.selection_anchor = None;
             }
@@ -172,8 +126,7 @@ impl TextInput {
             if selecting && self.selection_anchor.is_none() {
                 self.selection_anchor = Some(self.cursor);
             }
-            let next_char = self.text[self.cursor..].chars().next().unwrap();
-            self.cursor += next_char.len_utf8();
+            self.cursor = grapheme::next_boundary(&self.text, self.cursor);
             if !selecting {
                 self.selection_anchor = None;
             }
@@ -181,52 +134,41 @@ impl TextInput {
     }
 
     pub fn move_word_left(&mut self, selecting: bool) {
+        self.move_word_left_by(selecting, WordMode::Word);
+    }
+
+    pub fn move_subword_left(&mut self, selecting: bool) {
+        self.move_word_left_by(selecting, WordMode::SubWord);
+    }
+
+    fn move_word_left_by(&mut self, selecting: bool, mode: WordMode) {
         if selecting && self.selection_anchor.is_none() {
             self.selection_anchor = Some(self.cursor);
         }
-        self.cursor = self.find_word_boundary_left();
+        self.cursor = word::prev_word_start(&self.text, self.cursor, mode);
         if !selecting {
             self.selection_anchor = None;
         }
     }
 
     pub fn move_word_right(&mut self, selecting: bool) {
+        self.move_word_right_by(selecting, WordMode::Word);
+    }
+
+    pub fn move_subword_right(&mut self, selecting: bool) {
+        self.move_word_right_by(selecting, WordMode::SubWord);
+    }
+
+    fn move_word_right_by(&mut self, selecting: bool, mode: WordMode) {
         if selecting && self.selection_anchor.is_none() {
             self.selection_anchor = Some(self.cursor);
         }
-        self.cursor = self.find_word_boundary_right();
+        self.cursor = word::next_word_end(&self.text, self.cursor, mode);
         if !selecting {
             self.selection_anchor = None;
         }
     }
 
-    fn find_word_boundary_right(&self) -> usize {
-        let text = &self.text[self.cursor..];
-        let mut chars = text.char_indices().peekable();
-        
-        // Skip current word
-        while let Some(&(_, ch)) = chars.peek() {
-            if ch.is_whitespace() {
-                break;
-            }
-            chars.next();
-        }
-        
-        // Skip whitespace
-        while let Some(&(_, ch)) = chars.peek() {
-            if !ch.is_whitespace() {
-                break;
-            }
-            chars.next();
-        }
-        
-        if let Some(&(idx, _)) = chars.peek() {
-            self.cursor + idx
-        } else {
-            self.text.len()
-        }
-    }
-
     pub fn move_to_start(&mut self, selecting: bool) {
         if selecting && self.selection_anchor.is_none() {
             self.selection_anchor = Some(self.cursor);
@@ -313,7 +255,7 @@ impl TextInput {
             byte_idx += ch.len_utf8();
         }
         
-        self.cursor = byte_idx.min(self.text.len());
+        self.cursor = grapheme::floor_boundary(&self.text, byte_idx.min(self.text.len()));
         
         if !selecting {
             self.selection_anchor = None;
@@ -339,3 +281,50 @@ impl TextInput {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_by_grapheme_cluster() {
+        // Flag pair, then e + combining acute accent
+        let mut input = TextInput::new("🇯🇵e\u{301}".to_string());
+        input.move_left(false);
+        assert_eq!(input.cursor(), 8);
+        input.move_left(false);
+        assert_eq!(input.cursor(), 0);
+        input.move_right(true);
+        assert_eq!(input.selected_text(), "🇯🇵");
+    }
+
+    #[test]
+    fn test_delete_whole_cluster() {
+        let family = "👨\u{200d}👩\u{200d}👧";
+        let mut input = TextInput::new(format!("a{family}"));
+        input.backspace();
+        assert_eq!(input.text(), "a");
+
+        // Decomposed Hangul syllable (choseong + jungseong + jongseong)
+        let mut input = TextInput::new("\u{1100}\u{1161}\u{11a8}b".to_string());
+        input.move_to_start(false);
+        input.delete();
+        assert_eq!(input.text(), "b");
+    }
+
+    #[test]
+    fn test_word_motion_matches_editor() {
+        let mut input = TextInput::new("hello, world".to_string());
+        input.move_word_left(false);
+        assert_eq!(input.cursor(), 7);
+        input.move_word_left(false);
+        assert_eq!(input.cursor(), 5);
+        input.delete_word_left();
+        assert_eq!(input.text(), ", world");
+
+        let mut input = TextInput::new("getUserName".to_string());
+        input.move_subword_left(false);
+        assert_eq!(input.cursor(), 7);
+        input.move_subword_left(true);
+        assert_eq!(input.selected_text(), "User");
+    }
+}
@@ -1,9 +1,11 @@
 //! UI tree coordinator for hit-testing and actions
 
+use super::context_menu::MenuGeometry;
 use super::tab_bar::TabBar;
 use super::scrollbar::{ScrollbarAction, ScrollbarWidget};
+use super::status_bar::status_bar_rect;
 use super::text_area::TextArea;
-use super::types::{ResizeEdge, UiAction, UiDragAction, UiHover, UiNode};
+use super::types::{Rect, ResizeEdge, UiAction, UiDragAction, UiHover, UiNode};
 
 const RESIZE_BORDER: f32 = 5.0;
 
@@ -12,6 +14,10 @@ pub struct UiTree {
     pub tab_bar: TabBar,
     pub scrollbar: ScrollbarWidget,
     pub text_area: TextArea,
+    pub context_menu: Option<MenuGeometry>,
+    pub sidebar: Option<Rect>,
+    pub backlinks: Option<Rect>,
+    pub status_bar: Rect,
     width: f32,
     height: f32,
     scale: f32,
@@ -19,16 +25,45 @@ pub struct UiTree {
 
 impl UiTree {
     pub fn new(width: f32, height: f32, scale: f32, tab_scroll_x: f32, tabs: &[(&str, bool)]) -> Self {
+        // The editor and its scrollbar end above the status bar
+        let status_bar = status_bar_rect(width, height, scale);
         Self {
             tab_bar: TabBar::new(width, scale, tab_scroll_x, tabs),
-            scrollbar: ScrollbarWidget::new(width, height, scale),
-            text_area: TextArea::new(width, height, scale),
+            scrollbar: ScrollbarWidget::new(width, status_bar.y, scale),
+            text_area: TextArea::new(width, status_bar.y, scale),
+            context_menu: None,
+            sidebar: None,
+            backlinks: None,
+            status_bar,
             width,
             height,
             scale,
         }
     }
 
+    /// Include an open context menu, which sits above everything else
+    pub fn with_context_menu(mut self, menu: Option<MenuGeometry>) -> Self {
+        self.context_menu = menu;
+        self
+    }
+
+    /// Include the notebook sidebar when it is shown
+    pub fn with_sidebar(mut self, sidebar: Option<Rect>) -> Self {
+        self.sidebar = sidebar;
+        self
+    }
+
+    /// Include the backlinks panel when it is shown. The editor and its
+    /// scrollbar end where the panel starts.
+    pub fn with_backlinks(mut self, backlinks: Option<Rect>) -> Self {
+        if let Some(rect) = backlinks {
+            self.scrollbar = ScrollbarWidget::new(rect.x, self.status_bar.y, self.scale);
+            self.text_area = TextArea::new(rect.x, self.status_bar.y, self.scale);
+        }
+        self.backlinks = backlinks;
+        self
+    }
+
     fn detect_resize_edge(&self, x: f32, y: f32) -> Option<ResizeEdge> {
         let border = RESIZE_BORDER * self.scale;
         let near_left = x < border;
@@ -59,6 +94,13 @@ impl UiTree {
     ) -> UiHover {
         let mut hover = UiHover::default();
 
+        if let Some(menu) = &self.context_menu {
+            if menu.rect.contains(x, y) {
+                hover.context_menu_item = menu.item_at(x, y);
+                return hover;
+            }
+        }
+
         // Check resize edges first
         if let Some(edge) = self.detect_resize_edge(x, y) {
             hover.resize_edge = Some(edge);
@@ -67,6 +109,10 @@ impl UiTree {
 
         match self.tab_bar.hit_test(x, y) {
             UiNode::Tab(i) => hover.tab_index = Some(i),
+            UiNode::TabClose(i) => {
+                hover.tab_index = Some(i);
+                hover.tab_close = Some(i);
+            }
             UiNode::NewTabButton => hover.plus = true,
             UiNode::WindowMinimize => hover.window_minimize = true,
             UiNode::WindowMaximize => hover.window_maximize = true,
@@ -93,6 +139,7 @@ impl UiTree {
     ) -> UiAction {
         match self.hit_test(x, y) {
             UiNode::Tab(i) if !selecting => UiAction::ActivateTab(i),
+            UiNode::TabClose(i) if !selecting => UiAction::CloseTab(i),
             UiNode::NewTabButton if !selecting => UiAction::NewTab,
             UiNode::TabBar if !selecting => UiAction::WindowDrag,
             UiNode::WindowMinimize if !selecting => UiAction::WindowMinimize,
@@ -115,6 +162,10 @@ impl UiTree {
                 }
             }
             UiNode::TextArea => UiAction::TextClick,
+            // Dragging a selection below the text keeps it going
+            UiNode::StatusBar if selecting => UiAction::TextClick,
+            UiNode::Sidebar if !selecting => UiAction::SidebarClick,
+            UiNode::Backlinks if !selecting => UiAction::BacklinksClick,
             _ => UiAction::None,
         }
     }
@@ -150,7 +201,13 @@ impl UiTree {
             | UiNode::WindowResizeEdge(_) => {
                 return self.click(x, y, total_lines, visible_lines, scroll_offset, false);
             }
-            UiNode::Scrollbar => return UiAction::None,
+            // The first click already closed the tab; another would close
+            // the one that took its place
+            UiNode::TabClose(_)
+            | UiNode::Scrollbar | UiNode::ContextMenu | UiNode::ContextMenuItem(_)
+            | UiNode::Sidebar | UiNode::Backlinks | UiNode::StatusBar => {
+                return UiAction::None
+            }
             UiNode::TextArea => return UiAction::TextClick,
             UiNode::None => return UiAction::None,
         }
@@ -170,13 +227,29 @@ impl UiTree {
             | UiNode::WindowResizeEdge(_) => {
                 return self.click(x, y, total_lines, visible_lines, scroll_offset, false);
             }
-            UiNode::Scrollbar => return UiAction::None,
+            // The first click already closed the tab; another would close
+            // the one that took its place
+            UiNode::TabClose(_)
+            | UiNode::Scrollbar | UiNode::ContextMenu | UiNode::ContextMenuItem(_)
+            | UiNode::Sidebar | UiNode::Backlinks | UiNode::StatusBar => {
+                return UiAction::None
+            }
             UiNode::TextArea => return UiAction::TextClick,
             UiNode::None => return UiAction::None,
         }
     }
 
     pub fn hit_test(&self, x: f32, y: f32) -> UiNode {
+        // An open context menu covers everything underneath it
+        if let Some(menu) = &self.context_menu {
+            if menu.rect.contains(x, y) {
+                return menu
+                    .item_at(x, y)
+                    .map(UiNode::ContextMenuItem)
+                    .unwrap_or(UiNode::ContextMenu);
+            }
+        }
+
         // Check resize edges first (highest priority for borderless window)
         if let Some(edge) = self.detect_resize_edge(x, y) {
             return UiNode::WindowResizeEdge(edge);
@@ -186,6 +259,18 @@ impl UiTree {
             return self.tab_bar.hit_test(x, y);
         }
 
+        if self.sidebar.is_some_and(|rect| rect.contains(x, y)) {
+            return UiNode::Sidebar;
+        }
+
+        if self.backlinks.is_some_and(|rect| rect.contains(x, y)) {
+            return UiNode::Backlinks;
+        }
+
+        if self.status_bar.contains(x, y) {
+            return UiNode::StatusBar;
+        }
+
         if self.scrollbar.hit_test(x, y) {
             return UiNode::Scrollbar;
         }
@@ -4,6 +4,7 @@
 pub enum UiNode {
     None,
     Tab(usize),
+    TabClose(usize),
     NewTabButton,
     Scrollbar,
     TextArea,
@@ -12,27 +13,37 @@ pub enum UiNode {
     WindowMaximize,
     WindowClose,
     WindowResizeEdge(ResizeEdge),
+    ContextMenu,
+    ContextMenuItem(usize),
+    Sidebar,
+    Backlinks,
+    StatusBar,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct UiHover {
     pub tab_index: Option<usize>,
+    pub tab_close: Option<usize>,
     pub plus: bool,
     pub scrollbar: bool,
     pub window_minimize: bool,
     pub window_maximize: bool,
     pub window_close: bool,
     pub resize_edge: Option<ResizeEdge>,
+    pub context_menu_item: Option<usize>,
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum UiAction {
     None,
     ActivateTab(usize),
+    CloseTab(usize),
     NewTab,
     StartScrollbarDrag { drag_offset: f32 },
     ScrollbarJump { ratio: f32 },
     TextClick,
+    SidebarClick,
+    BacklinksClick,
     WindowMinimize,
     WindowMaximize,
     WindowClose,
@@ -2,73 +2,17 @@
 //!
 //! This module provides a centralized abstraction for converting between:
 //! - Character positions (how text is stored in the buffer)
-//! - Visual positions (how text appears on screen, with tabs taking multiple spaces)
+//! - Visual positions (how text appears on screen, with tabs taking multiple spaces,
+//!   East Asian wide characters two columns and combining marks none)
+//!
+//! Pixel positions in the editor come from `text_layout`, which builds on the
+//! column widths defined here.
+
+use unicode_width::UnicodeWidthChar;
 
 /// Width of a tab character in visual columns
 pub const TAB_WIDTH: usize = 4;
 
-/// A line of text with visual position awareness
-/// 
-/// This type encapsulates a line of text and provides methods to work with
-/// visual positions (accounting for tab width) without manual character iteration.
-pub struct VisualLine<'a> {
-    content: &'a str,
-}
-
-impl<'a> VisualLine<'a> {
-    /// Create a new VisualLine from string content
-    pub fn new(content: &'a str) -> Self {
-        Self { content }
-    }
-    
-    /// Convert a character column to a visual column
-    #[allow(dead_code)]
-    pub fn char_col_to_visual_col(&self, char_col: usize) -> usize {
-        char_col_to_visual_col(self.content, char_col)
-    }
-    
-    /// Convert a visual column to a character column
-    pub fn visual_col_to_char_col(&self, visual_col: usize) -> usize {
-        visual_col_to_char_col(self.content, visual_col)
-    }
-    
-    /// Get the visual x position for a character column
-    pub fn char_col_to_visual_x(&self, char_col: usize, base_x: f32, char_width: f32) -> f32 {
-        char_col_to_visual_x(self.content, char_col, base_x, char_width)
-    }
-    
-    /// Get the center visual x position for a character column
-    pub fn char_col_to_visual_center_x(&self, char_col: usize, base_x: f32, char_width: f32) -> f32 {
-        char_col_to_visual_center_x(self.content, char_col, base_x, char_width)
-    }
-    
-    /// Get the character at a given character column
-    #[allow(dead_code)]
-    pub fn char_at(&self, char_col: usize) -> Option<char> {
-        self.content.chars().nth(char_col)
-    }
-    
-    /// Get the visual width of the character at a given character column
-    #[allow(dead_code)]
-    pub fn char_visual_width_at(&self, char_col: usize) -> usize {
-        self.char_at(char_col)
-            .map(get_char_visual_width)
-            .unwrap_or(1)
-    }
-    
-    /// Get the total visual width of the line
-    #[allow(dead_code)]
-    pub fn visual_width(&self) -> usize {
-        self.content.chars().map(get_char_visual_width).sum()
-    }
-    
-    /// Get the number of characters in the line
-    #[allow(dead_code)]
-    pub fn char_count(&self) -> usize {
-        self.content.chars().count()
-    }
-}
-
 /// Get the visual width of a character in columns
 /// 
 /// # Arguments
@@ -81,7 +25,7 @@ pub fn get_char_visual_width(ch: char) -> usize {
     match ch {
         '\t' => TAB_WIDTH,
         '\n' => 0,
-        _ => 1,
+        _ => ch.width().unwrap_or(0),
     }
 }
 
@@ -117,6 +61,7 @@ pub fn char_col_to_visual_col(line_content: &str, char_col: usize) -> usize {
 /// The character column position that contains the visual column.
 /// For example, if a tab spans visual columns 0-3, clicking at visual column 2
 /// will return char column 0 (the tab character).
+#[cfg(test)]
 pub fn visual_col_to_char_col(line_content: &str, visual_col: usize) -> usize {
     let mut char_col = 0;
     let mut current_visual_col = 0;
@@ -147,6 +92,7 @@ pub fn visual_col_to_char_col(line_content: &str, visual_col: usize) -> usize {
 /// 
 /// # Returns
 /// The visual x position in pixels
+#[cfg(test)]
 pub fn char_col_to_visual_x(line_content: &str, char_col: usize, base_x: f32, char_width: f32) -> f32 {
     let mut visual_x = base_x;
     
@@ -160,26 +106,6 @@ pub fn char_col_to_visual_x(line_content: &str, char_col: usize, base_x: f32, ch
     visual_x
 }
 
-/// Calculate the center visual x position for a character at a given column
-/// 
-/// # Arguments
-/// * `line_content` - The text content of the line
-/// * `char_col` - The character column position
-/// * `base_x` - The base x position (e.g., padding - scroll_x)
-/// * `char_width` - The width of a single character in pixels
-/// 
-/// # Returns
-/// The center visual x position in pixels, accounting for tab width
-pub fn char_col_to_visual_center_x(line_content: &str, char_col: usize, base_x: f32, char_width: f32) -> f32 {
-    let start_x = char_col_to_visual_x(line_content, char_col, base_x, char_width);
-    
-    // Get the character at this position to determine its visual width
-    let ch = line_content.chars().nth(char_col).unwrap_or(' ');
-    let char_visual_width = get_char_visual_width(ch);
-    
-    start_x + (char_width * char_visual_width as f32 * 0.5)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -215,6 +141,14 @@ mod tests {
         assert_eq!(visual_col_to_char_col("\thello", 5), 2); // After tab + 'h'
     }
 
+    #[test]
+    fn test_wide_and_zero_width_chars() {
+        assert_eq!(get_char_visual_width('日'), 2);
+        assert_eq!(get_char_visual_width('\u{301}'), 0);
+        assert_eq!(char_col_to_visual_col("日本a", 2), 4);
+        assert_eq!(visual_col_to_char_col("日本a", 3), 1); // Inside second wide char
+    }
+
     #[test]
     fn test_visual_x_calculation() {
         let base_x = 10.0;
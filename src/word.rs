@@ -0,0 +1,224 @@
+//! Word boundaries for word motion, word deletion and double-click selection
+//!
+//! Text is split with UAX #29 word boundaries and the segments are grouped into
+//! tokens: words, whitespace runs and punctuation runs. `TextBuffer` and
+//! `TextInput` both go through this module so Ctrl+arrows, Ctrl+Backspace and
+//! double-click behave the same in the editor and in single-line inputs.
+//!
+//! In `WordMode::SubWord`, words are further split at camelCase humps and after
+//! underscores (`fooBar` -> `foo|Bar`, `HTTPServer` -> `HTTP|Server`,
+//! `foo_bar` -> `foo_|bar`).
+//!
+//! All functions work on byte offsets into a `&str`.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Granularity of word motion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordMode {
+    /// Whole UAX #29 words (Ctrl+arrows)
+    Word,
+    /// camelCase / snake_case parts of words (Alt+arrows)
+    SubWord,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Word,
+    Space,
+    Punct,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token {
+    start: usize,
+    end: usize,
+    kind: Kind,
+}
+
+fn classify(segment: &str) -> Kind {
+    if segment.chars().any(|c| c.is_alphanumeric() || c == '_') {
+        Kind::Word
+    } else if segment.chars().all(char::is_whitespace) {
+        Kind::Space
+    } else {
+        Kind::Punct
+    }
+}
+
+fn tokenize(text: &str, mode: WordMode) -> Vec<Token> {
+    let mut tokens: Vec<Token> = Vec::new();
+    for (start, segment) in text.split_word_bound_indices() {
+        let end = start + segment.len();
+        let kind = classify(segment);
+        if kind == Kind::Word {
+            match mode {
+                WordMode::Word => tokens.push(Token { start, end, kind }),
+                WordMode::SubWord => {
+                    let mut part_start = start;
+                    for split in sub_word_splits(segment) {
+                        tokens.push(Token {
+                            start: part_start,
+                            end: start + split,
+                            kind,
+                        });
+                        part_start = start + split;
+                    }
+                    tokens.push(Token {
+                        start: part_start,
+                        end,
+                        kind,
+                    });
+                }
+            }
+            continue;
+        }
+        // UAX #29 yields one segment per punctuation character and per line
+        // break; merge runs so "..." or blank lines are a single stop
+        if let Some(last) = tokens.last_mut() {
+            if last.kind == kind && last.end == start {
+                last.end = end;
+                continue;
+            }
+        }
+        tokens.push(Token { start, end, kind });
+    }
+    tokens
+}
+
+/// Byte offsets inside a word where a camelCase or snake_case part begins
+fn sub_word_splits(word: &str) -> Vec<usize> {
+    let chars: Vec<(usize, char)> = word.char_indices().collect();
+    let mut splits = Vec::new();
+    for i in 1..chars.len() {
+        let prev = chars[i - 1].1;
+        let (offset, cur) = chars[i];
+        let next = chars.get(i + 1).map(|&(_, c)| c);
+        let hump = (prev.is_lowercase() || prev.is_numeric()) && cur.is_uppercase();
+        let acronym_end =
+            prev.is_uppercase() && cur.is_uppercase() && next.is_some_and(char::is_lowercase);
+        let after_underscore = prev == '_' && cur != '_';
+        if hump || acronym_end || after_underscore {
+            splits.push(offset);
+        }
+    }
+    splits
+}
+
+/// Start of the word before `offset`, skipping whitespace (Ctrl+Left)
+pub fn prev_word_start(text: &str, offset: usize, mode: WordMode) -> usize {
+    let tokens = tokenize(text, mode);
+    let mut idx = tokens.partition_point(|t| t.start < offset);
+    while idx > 0 {
+        idx -= 1;
+        if tokens[idx].kind != Kind::Space {
+            return tokens[idx].start;
+        }
+    }
+    0
+}
+
+/// End of the word after `offset`, skipping whitespace (Ctrl+Right)
+pub fn next_word_end(text: &str, offset: usize, mode: WordMode) -> usize {
+    let tokens = tokenize(text, mode);
+    let idx = tokens.partition_point(|t| t.end <= offset);
+    tokens[idx..]
+        .iter()
+        .find(|t| t.kind != Kind::Space)
+        .map(|t| t.end)
+        .unwrap_or(text.len())
+}
+
+/// Where Ctrl+Backspace stops: a run of several whitespace characters is
+/// deleted on its own, otherwise the previous word goes with it
+pub fn delete_word_left_start(text: &str, offset: usize) -> usize {
+    let tokens = tokenize(text, WordMode::Word);
+    let idx = tokens.partition_point(|t| t.start < offset);
+    if idx > 0 {
+        let token = tokens[idx - 1];
+        if token.kind == Kind::Space && text[token.start..offset].chars().count() > 1 {
+            return token.start;
+        }
+    }
+    prev_word_start(text, offset, WordMode::Word)
+}
+
+/// Where Ctrl+Delete stops: the end of the token at `offset` (word,
+/// punctuation run or whitespace run), without trailing whitespace
+pub fn delete_word_right_end(text: &str, offset: usize) -> usize {
+    let tokens = tokenize(text, WordMode::Word);
+    let idx = tokens.partition_point(|t| t.end <= offset);
+    tokens.get(idx).map(|t| t.end).unwrap_or(text.len())
+}
+
+/// Byte range of the token under `offset` (the last token at the end of the text)
+pub fn word_range_at(text: &str, offset: usize) -> (usize, usize) {
+    let tokens = tokenize(text, WordMode::Word);
+    let idx = tokens.partition_point(|t| t.end <= offset);
+    tokens
+        .get(idx)
+        .or(tokens.last())
+        .map(|t| (t.start, t.end))
+        .unwrap_or((offset, offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stops_right(text: &str, mode: WordMode) -> Vec<usize> {
+        let mut stops = Vec::new();
+        let mut pos = 0;
+        while pos < text.len() {
+            pos = next_word_end(text, pos, mode);
+            stops.push(pos);
+        }
+        stops
+    }
+
+    #[test]
+    fn test_word_motion() {
+        let text = "hello, world... foo_bar";
+        assert_eq!(stops_right(text, WordMode::Word), vec![5, 6, 12, 15, 23]);
+        assert_eq!(prev_word_start(text, 23, WordMode::Word), 16);
+        assert_eq!(prev_word_start(text, 16, WordMode::Word), 12);
+        assert_eq!(prev_word_start(text, 3, WordMode::Word), 0);
+    }
+
+    #[test]
+    fn test_uax29_keeps_contractions_and_numbers() {
+        let text = "can't 3.14";
+        assert_eq!(stops_right(text, WordMode::Word), vec![5, 10]);
+    }
+
+    #[test]
+    fn test_sub_word_motion() {
+        let text = "parseHTTPServer my_var";
+        assert_eq!(stops_right(text, WordMode::SubWord), vec![5, 9, 15, 19, 22]);
+        assert_eq!(prev_word_start(text, 15, WordMode::SubWord), 9);
+        assert_eq!(prev_word_start(text, 22, WordMode::SubWord), 19);
+    }
+
+    #[test]
+    fn test_motion_crosses_lines() {
+        let text = "one\n\n  two";
+        assert_eq!(next_word_end(text, 3, WordMode::Word), 10);
+        assert_eq!(prev_word_start(text, 7, WordMode::Word), 0);
+    }
+
+    #[test]
+    fn test_delete_word_stops() {
+        assert_eq!(delete_word_left_start("foo bar", 4), 0);
+        assert_eq!(delete_word_left_start("foo   ", 6), 3);
+        assert_eq!(delete_word_right_end("foo bar", 0), 3);
+        assert_eq!(delete_word_right_end("foo   bar", 3), 6);
+    }
+
+    #[test]
+    fn test_word_range_at() {
+        assert_eq!(word_range_at("foo bar", 5), (4, 7));
+        assert_eq!(word_range_at("foo bar", 7), (4, 7));
+        assert_eq!(word_range_at("日本 text", 8), (7, 11));
+        assert_eq!(word_range_at("", 0), (0, 0));
+    }
+}